@@ -0,0 +1,250 @@
+//! `meta git cherry-pick <ref>` - apply one commit across every repo that has it.
+//!
+//! Useful for propagating a hotfix between forks or sibling repos that share
+//! history. Repos where the ref doesn't resolve are skipped; conflicts are
+//! left in place for resolution (or `--abort-all`) without stopping the rest.
+
+use console::style;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
+use rayon::prelude::*;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::helpers::get_project_directories_with_fallback;
+use crate::MetaGitError;
+
+enum PickOutcome {
+    Applied,
+    Skipped,
+    Conflicted,
+    Failed(String),
+}
+
+pub(crate) fn execute_git_cherry_pick(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let mut dry_run = options.dry_run;
+    let mut abort_all = false;
+    let mut rev: Option<&str> = None;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--abort-all" => abort_all = true,
+            s if !s.starts_with('-') && rev.is_none() => rev = Some(s),
+            _ => {}
+        }
+    }
+
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+
+    if abort_all {
+        return abort_in_progress(&dirs, cwd, dry_run);
+    }
+
+    let Some(rev) = rev else {
+        return Err(MetaGitError::InvalidArgument(
+            "Usage: meta git cherry-pick <sha-or-ref> [--dry-run] | --abort-all".to_string(),
+        )
+        .into());
+    };
+    let commit = format!("{rev}^{{commit}}");
+
+    if dry_run {
+        println!(
+            "{} Would cherry-pick {} into:",
+            style("[DRY RUN]").cyan(),
+            style(rev).bold()
+        );
+        for dir in &dirs {
+            if git_ok(
+                &cwd.join(dir),
+                &["rev-parse", "--verify", "--quiet", &commit],
+            ) {
+                println!("  {dir}");
+            } else {
+                println!(
+                    "  {} {}",
+                    style(dir).dim(),
+                    style("(ref not found, skip)").dim()
+                );
+            }
+        }
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    let outcomes: Vec<(String, PickOutcome)> = dirs
+        .par_iter()
+        .map(|dir| (dir.clone(), cherry_pick_one(&cwd.join(dir), &commit)))
+        .collect();
+
+    let (mut applied, mut skipped, mut conflicted, mut failed) = (0, 0, 0, 0);
+    for (dir, outcome) in &outcomes {
+        match outcome {
+            PickOutcome::Applied => {
+                applied += 1;
+                println!("  {} {dir}", style("✓").green());
+            }
+            PickOutcome::Skipped => {
+                skipped += 1;
+                println!(
+                    "  {} {dir} {}",
+                    style("-").dim(),
+                    style("(ref not found)").dim()
+                );
+            }
+            PickOutcome::Conflicted => {
+                conflicted += 1;
+                println!(
+                    "  {} {dir} {}",
+                    style("⚠").yellow(),
+                    style("(conflict)").yellow()
+                );
+            }
+            PickOutcome::Failed(e) => {
+                failed += 1;
+                println!("  {} {dir}: {e}", style("✗").red());
+            }
+        }
+    }
+
+    println!();
+    println!("{applied} applied, {skipped} skipped, {conflicted} conflicted, {failed} failed");
+    if conflicted > 0 {
+        println!(
+            "Resolve conflicts and run 'git cherry-pick --continue' in each repo, or 'meta git cherry-pick --abort-all'."
+        );
+    }
+
+    if conflicted + failed > 0 {
+        return Err(MetaGitError::GitFailed(format!(
+            "cherry-pick of {rev} did not apply cleanly in {} repo(s)",
+            conflicted + failed
+        ))
+        .into());
+    }
+    Ok(CommandResult::Message(String::new()))
+}
+
+fn cherry_pick_one(repo: &Path, commit: &str) -> PickOutcome {
+    if !git_ok(repo, &["rev-parse", "--verify", "--quiet", commit]) {
+        return PickOutcome::Skipped;
+    }
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cherry-pick", commit])
+        .stdin(Stdio::null())
+        .output();
+    match out {
+        Ok(out) if out.status.success() => PickOutcome::Applied,
+        Ok(_) if cherry_pick_in_progress(repo) => PickOutcome::Conflicted,
+        Ok(out) => PickOutcome::Failed(
+            String::from_utf8_lossy(&out.stderr)
+                .lines()
+                .next()
+                .unwrap_or("git cherry-pick failed")
+                .to_string(),
+        ),
+        Err(e) => PickOutcome::Failed(format!("failed to spawn git: {e}")),
+    }
+}
+
+/// Abort every cherry-pick left in progress across the workspace.
+fn abort_in_progress(dirs: &[String], cwd: &Path, dry_run: bool) -> anyhow::Result<CommandResult> {
+    let in_progress: Vec<&String> = dirs
+        .iter()
+        .filter(|dir| cherry_pick_in_progress(&cwd.join(dir)))
+        .collect();
+
+    if in_progress.is_empty() {
+        println!("No cherry-picks in progress.");
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    let mut failed = 0;
+    for dir in in_progress {
+        if dry_run {
+            println!(
+                "{} Would abort cherry-pick in {dir}",
+                style("[DRY RUN]").cyan()
+            );
+        } else if git_ok(&cwd.join(dir), &["cherry-pick", "--abort"]) {
+            println!("  {} Aborted cherry-pick in {dir}", style("✓").green());
+        } else {
+            failed += 1;
+            println!(
+                "  {} Could not abort cherry-pick in {dir}",
+                style("✗").red()
+            );
+        }
+    }
+
+    if failed > 0 {
+        return Err(MetaGitError::GitFailed(format!(
+            "Failed to abort cherry-pick in {failed} repo(s)"
+        ))
+        .into());
+    }
+    Ok(CommandResult::Message(String::new()))
+}
+
+fn cherry_pick_in_progress(repo: &Path) -> bool {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-parse", "--git-path", "CHERRY_PICK_HEAD"])
+        .output();
+    match out {
+        Ok(out) if out.status.success() => {
+            let path = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            repo.join(path).exists()
+        }
+        _ => false,
+    }
+}
+
+fn git_ok(repo: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn applies_where_ref_exists_and_skips_elsewhere() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let other = tmp.path().join("other");
+        for dir in [&repo, &other] {
+            init_repo(dir);
+        }
+        git(&repo, &["checkout", "--quiet", "-b", "fix"]);
+        std::fs::write(repo.join("fix.txt"), "fix\n").unwrap();
+        git(&repo, &["add", "fix.txt"]);
+        git(&repo, &["commit", "--quiet", "-m", "fix"]);
+        git(&repo, &["checkout", "--quiet", "-"]);
+
+        assert!(matches!(
+            cherry_pick_one(&repo, "fix^{commit}"),
+            PickOutcome::Applied
+        ));
+        assert!(repo.join("fix.txt").exists());
+        assert!(matches!(
+            cherry_pick_one(&other, "fix^{commit}"),
+            PickOutcome::Skipped
+        ));
+    }
+}
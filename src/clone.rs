@@ -1,11 +1,20 @@
-use crate::clone_worker::clone_with_queue;
+use crate::clone_events::CloneEventLog;
+use crate::clone_state::{discard_partial_clones, CloneState, CloneStateFile, CLONE_STATE_FILE};
+use crate::clone_worker::{
+    clone_git_config, clone_with_queue, split_patterns, CloneOptions, RepoPathFilter,
+    DEFAULT_CLONE_RETRIES, PRESERVE_PERMISSIONS_CONFIG,
+};
+use crate::mirror::{self, MirrorRequest};
 use crate::ssh_setup;
+use crate::url_rewrite::{apply_protocol, Protocol};
+use crate::MetaGitError;
 use console::style;
 use indicatif::MultiProgress;
 use meta_core::config;
 use meta_git_lib::clone_queue::CloneQueue;
 use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
-use std::process::Command;
+use std::path::Path;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
 
 pub(crate) fn execute_git_clone(
@@ -16,11 +25,27 @@ pub(crate) fn execute_git_clone(
     let dry_run = options.dry_run;
 
     // Default options - limit to 4 concurrent clones to avoid SSH multiplexing issues
+    // (user config `jobs` overrides the default, --parallel N overrides both)
     // Start with --recursive from CLI options (passed via PluginRequestOptions)
     let mut recursive = options.recursive;
-    let mut parallel = 4_usize;
+    let mut parallel = crate::user_config::clone_jobs();
     let mut depth: Option<String> = options.depth.map(|d| d.to_string());
     let mut meta_depth: Option<usize> = None; // Limit recursion depth for nested .meta files
+    let mut share_objects = false;
+    let mut verify = false;
+    let mut preserve_permissions = false;
+    let mut resume = false;
+    let mut global_timeout: Option<std::time::Duration> = None;
+    let mut timeout: Option<std::time::Duration> = None;
+    let mut retries = DEFAULT_CLONE_RETRIES;
+    let mut filter: Option<String> = None;
+    let mut event_log: Option<String> = None;
+    let mut include: Vec<String> = Vec::new();
+    let mut exclude: Vec<String> = Vec::new();
+    let mut protocol: Option<Protocol> = None;
+    let mut lfs = false;
+    let mut mirror = false;
+    let mut into: Option<String> = None;
 
     let mut url = String::new();
     let mut dir_arg: Option<String> = None;
@@ -33,32 +58,158 @@ pub(crate) fn execute_git_clone(
                 recursive = true;
                 idx += 1;
             }
-            "--meta-depth" => {
-                if idx + 1 < args.len() {
-                    meta_depth = args[idx + 1].parse().ok();
-                    idx += 2;
-                } else {
-                    idx += 1;
+            "--into" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--into").into());
+                };
+                into = Some(value.clone());
+                idx += 2;
+            }
+            "--mirror" => {
+                mirror = true;
+                idx += 1;
+            }
+            "--share-objects" => {
+                share_objects = true;
+                idx += 1;
+            }
+            "--verify" => {
+                verify = true;
+                idx += 1;
+            }
+            "--lfs" => {
+                lfs = true;
+                idx += 1;
+            }
+            "--preserve-permissions" => {
+                preserve_permissions = true;
+                idx += 1;
+            }
+            "--resume-from-state" => {
+                resume = true;
+                idx += 1;
+            }
+            "--global-timeout" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--global-timeout requires a duration (e.g. 30m)".to_string(),
+                    )
+                    .into());
+                };
+                match meta_git_lib::worktree::helpers::parse_duration(value) {
+                    Ok(secs) => global_timeout = Some(std::time::Duration::from_secs(secs)),
+                    Err(e) => {
+                        return Err(MetaGitError::InvalidArgument(format!(
+                            "Invalid --global-timeout '{value}': {e}"
+                        ))
+                        .into())
+                    }
+                }
+                idx += 2;
+            }
+            "--timeout" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--timeout requires a duration (e.g. 5m)".to_string(),
+                    )
+                    .into());
+                };
+                match meta_git_lib::worktree::helpers::parse_duration(value) {
+                    Ok(secs) => timeout = Some(std::time::Duration::from_secs(secs)),
+                    Err(e) => {
+                        return Err(MetaGitError::InvalidArgument(format!(
+                            "Invalid --timeout '{value}': {e}"
+                        ))
+                        .into())
+                    }
                 }
+                idx += 2;
+            }
+            "--retries" => {
+                let Some(n) = args.get(idx + 1).and_then(|v| v.parse().ok()) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--retries requires a number (0 disables retries)".to_string(),
+                    )
+                    .into());
+                };
+                retries = n;
+                idx += 2;
+            }
+            "--meta-depth" => {
+                let Some(n) = args.get(idx + 1).and_then(|v| v.parse().ok()) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--meta-depth requires a number".to_string(),
+                    )
+                    .into());
+                };
+                meta_depth = Some(n);
+                idx += 2;
             }
             "--parallel" => {
-                if idx + 1 < args.len() {
-                    parallel = args[idx + 1].parse().unwrap_or(4);
-                    idx += 2;
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--parallel").into());
+                };
+                parallel = positive_number("--parallel", value)?;
+                idx += 2;
+            }
+            flag @ ("--branch" | "-b") => {
+                // Only the meta repo: children clone their own default branches
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value(flag).into());
+                };
+                git_clone_args.push("--branch".to_string());
+                git_clone_args.push(value.clone());
+                idx += 2;
+            }
+            flag @ ("--include" | "--exclude") => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value(flag).into());
+                };
+                let patterns = split_patterns(value);
+                if flag == "--include" {
+                    include.extend(patterns);
                 } else {
-                    idx += 1;
+                    exclude.extend(patterns);
                 }
+                idx += 2;
             }
-            "--depth" => {
-                if idx + 1 < args.len() {
-                    let d = args[idx + 1].clone();
-                    depth = Some(d.clone());
-                    git_clone_args.push("--depth".to_string());
-                    git_clone_args.push(d);
-                    idx += 2;
-                } else {
-                    idx += 1;
+            "--protocol" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--protocol").into());
+                };
+                match value.parse() {
+                    Ok(p) => protocol = Some(p),
+                    Err(e) => return Err(MetaGitError::InvalidArgument(e).into()),
                 }
+                idx += 2;
+            }
+            "--event-log" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--event-log").into());
+                };
+                event_log = Some(value.clone());
+                idx += 2;
+            }
+            "--filter" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--filter").into());
+                };
+                filter = Some(value.clone());
+                idx += 2;
+            }
+            s if s.starts_with("--filter=") => {
+                filter = Some(s["--filter=".len()..].to_string());
+                idx += 1;
+            }
+            "--depth" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(missing_value("--depth").into());
+                };
+                positive_number("--depth", value)?;
+                depth = Some(value.clone());
+                git_clone_args.push("--depth".to_string());
+                git_clone_args.push(value.clone());
+                idx += 2;
             }
             s if s.starts_with('-') => {
                 idx += 1; // skip unknown option
@@ -75,9 +226,7 @@ pub(crate) fn execute_git_clone(
     }
 
     if url.is_empty() {
-        return Ok(CommandResult::Error(
-            "No repository URL provided".to_string(),
-        ));
+        return Err(MetaGitError::InvalidArgument("No repository URL provided".to_string()).into());
     }
 
     // If depth was set from options but not added to git_clone_args yet, add it now
@@ -88,6 +237,11 @@ pub(crate) fn execute_git_clone(
         }
     }
 
+    // Partial clone: applies to the meta repo and every child clone
+    if let Some(ref spec) = filter {
+        git_clone_args.push(format!("--filter={spec}"));
+    }
+
     // Derive directory name
     let clone_dir = if let Some(ref dir) = dir_arg {
         dir.clone()
@@ -98,6 +252,55 @@ pub(crate) fn execute_git_clone(
             .unwrap_or("meta")
             .to_string()
     };
+    // After the directory name, which comes from the URL as given
+    let url = apply_protocol(&url, protocol);
+
+    // Cloning inside another work tree makes every child repo show up as
+    // untracked content of the parent, which is rarely what anyone wants.
+    if is_inside_git_work_tree(cwd) {
+        let message = format!(
+            "{} is inside an existing git repository; the cloned repos would be untracked files of it",
+            cwd.display()
+        );
+        if options.strict {
+            return Err(MetaGitError::Other(format!(
+                "{message}. Clone into a different directory (strict mode)"
+            ))
+            .into());
+        }
+        eprintln!("{} {message}", style("⚠").yellow());
+        eprintln!(
+            "  Add '{clone_dir}/' to the parent's .gitignore or clone into a different directory."
+        );
+    }
+
+    if mirror {
+        if dry_run {
+            println!(
+                "{} Would mirror meta repository and its children into {}/:",
+                style("[DRY RUN]").cyan(),
+                clone_dir
+            );
+            println!("  git clone --mirror {url} {clone_dir}/<name>.git");
+            return Ok(CommandResult::Message(String::new()));
+        }
+        let ssh_cmd = match ssh_setup::establish_ssh_masters(&[url.as_str()]) {
+            ssh_setup::SshMasters::OurSockets(dir) => Some(ssh_setup::git_ssh_command(&dir)),
+            ssh_setup::SshMasters::UserManaged | ssh_setup::SshMasters::Failed => None,
+        };
+        let path_filter = RepoPathFilter::new(&cwd.join(&clone_dir), &include, &exclude)?;
+        let request = MirrorRequest {
+            url: &url,
+            out_dir: &clone_dir,
+            recursive,
+            meta_depth,
+            parallel,
+            ssh_cmd: ssh_cmd.as_deref(),
+            protocol,
+            path_filter: path_filter.as_ref(),
+        };
+        return mirror::execute_mirror_clone(&request, cwd);
+    }
 
     // Build the git clone command string for display/dry-run
     let mut clone_cmd_str = "git clone".to_string();
@@ -110,11 +313,48 @@ pub(crate) fn execute_git_clone(
     clone_cmd_str.push(' ');
     clone_cmd_str.push_str(&clone_dir);
 
+    if lfs && !crate::helpers::git_lfs_available() {
+        eprintln!(
+            "{} --lfs given but git-lfs isn't installed; LFS files will stay pointer files",
+            style("⚠").yellow()
+        );
+        lfs = false;
+    }
+
     if dry_run {
+        let lfs_step = "  then: git lfs pull in each cloned repo";
+        // With a meta checkout already on disk (--into, or a previous clone
+        // into the target dir), walk its .meta tree for the full plan.
+        let existing_meta = cwd.join(into.as_deref().unwrap_or(&clone_dir));
+        if config::find_meta_config_in(&existing_meta).is_some() {
+            let path_filter = RepoPathFilter::new(&existing_meta, &include, &exclude)?;
+            let result = preview_clone_plan(
+                &existing_meta,
+                depth,
+                filter.as_deref(),
+                path_filter.as_ref(),
+                protocol,
+                recursive,
+                meta_depth,
+            )?;
+            if lfs {
+                println!("{lfs_step}");
+            }
+            return Ok(result);
+        }
+
         // Output what we know - just the meta repo clone command
         // (Child repos are in .meta file which hasn't been cloned yet)
         println!("{} Would clone meta repository:", style("[DRY RUN]").cyan());
         println!("  {clone_cmd_str}");
+        if lfs {
+            println!("{lfs_step}");
+        }
+        if recursive {
+            println!(
+                "  (nested repos can't be listed until the meta repo exists; use --into <dir> with an existing checkout for the full plan)"
+            );
+        }
         return Ok(CommandResult::Message(String::new()));
     }
 
@@ -124,25 +364,64 @@ pub(crate) fn execute_git_clone(
         ssh_setup::SshMasters::UserManaged | ssh_setup::SshMasters::Failed => None,
     };
 
-    println!("Cloning meta repository: {url}");
-    let mut clone_cmd = Command::new("git");
-    clone_cmd.arg("clone").args(&git_clone_args).arg(&url);
-    if let Some(ref dir) = dir_arg {
-        clone_cmd.arg(dir);
-    }
-    if let Some(ref ssh) = ssh_cmd {
-        clone_cmd.env("GIT_SSH_COMMAND", ssh);
+    let clone_dir_path = cwd.join(&clone_dir);
+    let state_path = clone_dir_path.join(".git").join(CLONE_STATE_FILE);
+
+    let mut previous = None;
+    if resume {
+        if !state_path.exists() {
+            return Err(MetaGitError::NotFound(format!(
+                "No clone state at {}; nothing to resume",
+                state_path.display()
+            ))
+            .into());
+        }
+        let state = CloneState::load(&state_path)?;
+        println!(
+            "Resuming clone into {} ({} done, {} failed, {} interrupted)",
+            clone_dir,
+            state.completed.len(),
+            state.failed.len(),
+            state.in_progress.len()
+        );
+        for partial in discard_partial_clones(&state) {
+            println!(
+                "  {} removed partial clone {}",
+                style("↺").yellow(),
+                partial.display()
+            );
+        }
+        previous = Some(state);
     }
-    clone_cmd.current_dir(cwd);
-    let status = clone_cmd.status()?;
-    if !status.success() {
-        return Ok(CommandResult::Error(
-            "Failed to clone meta repository".to_string(),
-        ));
+
+    // On resume the meta repo is already checked out; only children remain
+    if !(resume && clone_dir_path.join(".git").exists()) {
+        println!("Cloning meta repository: {url}");
+        let mut clone_cmd = Command::new("git");
+        clone_cmd.arg("clone").args(&git_clone_args);
+        if preserve_permissions {
+            // The meta repo's own .meta isn't readable yet; only the flag applies here
+            for (key, value) in PRESERVE_PERMISSIONS_CONFIG {
+                clone_cmd.arg("--config").arg(format!("{key}={value}"));
+            }
+        }
+        clone_cmd.arg(&url);
+        if let Some(ref dir) = dir_arg {
+            clone_cmd.arg(dir);
+        }
+        if let Some(ref ssh) = ssh_cmd {
+            clone_cmd.env("GIT_SSH_COMMAND", ssh);
+        }
+        clone_cmd.current_dir(cwd);
+        let status = clone_cmd.status()?;
+        if !status.success() {
+            return Err(
+                MetaGitError::GitFailed("Failed to clone meta repository".to_string()).into(),
+            );
+        }
     }
 
     // Parse meta config inside cloned repo
-    let clone_dir_path = cwd.join(&clone_dir);
     if config::find_meta_config_in(&clone_dir_path).is_none() {
         return Ok(CommandResult::Message(
             "No .meta config found in cloned repository".to_string(),
@@ -154,8 +433,28 @@ pub(crate) fn execute_git_clone(
     let effective_meta_depth = if recursive { meta_depth } else { Some(0) };
     let queue = Arc::new(CloneQueue::new(depth.clone(), effective_meta_depth));
 
-    // Seed the queue with first-level children
-    let initial_count = queue.push_from_meta(&clone_dir_path, 0)?;
+    // Seed the queue with first-level children, or on resume with what the
+    // previous run left, when it recorded its full task list
+    let remaining = previous.as_ref().and_then(CloneState::remaining_tasks);
+    let initial_count = match remaining {
+        Some(tasks) => {
+            if tasks.is_empty() {
+                let _ = std::fs::remove_file(&state_path);
+                return Ok(CommandResult::Message("Nothing left to resume".to_string()));
+            }
+            let count = tasks.len();
+            for task in tasks {
+                queue.push(task.into());
+            }
+            count
+        }
+        None => {
+            if resume {
+                println!("Previous run stopped before recording its queue; re-reading .meta");
+            }
+            queue.push_from_meta(&clone_dir_path, 0)?
+        }
+    };
 
     if initial_count == 0 {
         return Ok(CommandResult::Message(
@@ -164,7 +463,11 @@ pub(crate) fn execute_git_clone(
     }
 
     // Establish SSH masters for any additional hosts in the queue
-    let queue_urls = queue.peek_urls();
+    let queue_urls: Vec<String> = queue
+        .peek_urls()
+        .iter()
+        .map(|u| apply_protocol(u, protocol))
+        .collect();
     if !queue_urls.is_empty() {
         let url_refs: Vec<&str> = queue_urls.iter().map(|s| s.as_str()).collect();
         match ssh_setup::establish_ssh_masters(&url_refs) {
@@ -187,20 +490,292 @@ pub(crate) fn execute_git_clone(
     );
 
     let mp = MultiProgress::new();
+    let state = Arc::new(CloneStateFile::new(state_path));
 
     // Use the queue-based cloning system
-    clone_with_queue(Arc::clone(&queue), parallel, &mp, ssh_cmd.as_deref())?;
+    let clone_options = CloneOptions {
+        ssh_cmd,
+        share_objects,
+        verify,
+        git_config: clone_git_config(&clone_dir_path, preserve_permissions),
+        state: Some(Arc::clone(&state)),
+        global_timeout,
+        timeout,
+        retries,
+        filter,
+        pins: crate::lock::pinned_targets(&clone_dir_path)?,
+        path_filter: RepoPathFilter::new(&clone_dir_path, &include, &exclude)?,
+        protocol,
+        lfs,
+        event_log: match event_log {
+            Some(path) => Some(Arc::new(CloneEventLog::open(&cwd.join(path))?)),
+            None => None,
+        },
+    };
+    let report = clone_with_queue(Arc::clone(&queue), parallel, &mp, &clone_options)?;
+    state.finish_run();
 
-    let (completed, total) = queue.get_counts();
+    let (_, total) = queue.get_counts();
     if total > initial_count {
         println!(
             "Meta-repo clone completed ({} repos cloned, {} discovered via nested .meta files)",
-            completed,
+            report.cloned,
             total - initial_count
         );
     } else {
-        println!("Meta-repo clone completed ({completed} repos cloned)");
+        println!("Meta-repo clone completed ({} repos cloned)", report.cloned);
+    }
+
+    println!(
+        "Summary: {} cloned, {} skipped (already present), {} failed{}",
+        style(report.cloned).green(),
+        report.skipped.len(),
+        style(report.failed.len()).red(),
+        if report.filtered.is_empty() {
+            String::new()
+        } else {
+            format!(", {} filtered out", report.filtered.len())
+        }
+    );
+    if !report.failed.is_empty() {
+        println!();
+        println!("{} Failed to clone:", style("✗").red());
+        for (name, path) in &report.failed {
+            println!("  {} ({})", style(name).bold(), path.display());
+        }
+    }
+
+    if report.timed_out || report.interrupted {
+        let (what, error) = if report.interrupted {
+            ("Interrupted", "Clone interrupted")
+        } else {
+            (
+                "Global timeout reached",
+                "Clone aborted: --global-timeout exceeded",
+            )
+        };
+        println!();
+        println!(
+            "{} {what}: {} cancelled, {} not started",
+            style("✗").red(),
+            report.cancelled.len(),
+            report.not_started.len()
+        );
+        for name in report.cancelled.iter().chain(&report.not_started) {
+            println!("  {}", style(name).bold());
+        }
+        println!("Re-run with --resume-from-state to continue.");
+        return Err(MetaGitError::Other(error.to_string()).into());
+    }
+
+    if !report.lfs_failures.is_empty() {
+        println!();
+        println!(
+            "{} {} repo(s) cloned without their LFS objects:",
+            style("⚠").yellow(),
+            report.lfs_failures.len()
+        );
+        for (name, reason) in &report.lfs_failures {
+            println!("  {}: {reason}", style(name).bold());
+        }
+        println!("Run 'git lfs pull' in them once the problem is fixed.");
+    }
+
+    if !report.verify_failures.is_empty() {
+        println!();
+        println!(
+            "{} {} repo(s) failed verification:",
+            style("✗").red(),
+            report.verify_failures.len()
+        );
+        for (name, reason) in &report.verify_failures {
+            println!("  {}: {reason}", style(name).bold());
+        }
+        return Err(MetaGitError::GitFailed(format!(
+            "{} cloned repo(s) failed verification",
+            report.verify_failures.len()
+        ))
+        .into());
+    }
+
+    if !report.failed.is_empty() {
+        return Err(MetaGitError::GitFailed(format!(
+            "{} repo(s) failed to clone",
+            report.failed.len()
+        ))
+        .into());
+    }
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+/// Print the clone plan for an existing meta checkout without cloning anything.
+///
+/// Children are queued level by level with `push_from_meta`; nested `.meta`
+/// files are only visible for children that are already present on disk.
+fn preview_clone_plan(
+    meta_root: &Path,
+    depth: Option<String>,
+    filter: Option<&str>,
+    path_filter: Option<&RepoPathFilter>,
+    protocol: Option<Protocol>,
+    recursive: bool,
+    meta_depth: Option<usize>,
+) -> anyhow::Result<CommandResult> {
+    let effective_meta_depth = if recursive { meta_depth } else { Some(0) };
+    let queue = CloneQueue::new(depth.clone(), effective_meta_depth);
+    let mut depth_arg = depth.map(|d| format!(" --depth {d}")).unwrap_or_default();
+    if let Some(spec) = filter {
+        depth_arg.push_str(&format!(" --filter={spec}"));
     }
 
+    let mut level = 0;
+    queue.push_from_meta(meta_root, level)?;
+    let mut planned = 0;
+    let mut unexplored = 0;
+    let mut lines = Vec::new();
+
+    loop {
+        let tasks = queue.drain_all();
+        if tasks.is_empty() {
+            break;
+        }
+        let descend = recursive
+            && match effective_meta_depth {
+                Some(max) => level < max,
+                None => true,
+            };
+        level += 1;
+        for task in tasks {
+            if path_filter.is_some_and(|f| !f.matches(&task.target_path)) {
+                continue;
+            }
+            planned += 1;
+            let exists = task.target_path.exists();
+            lines.push(format!(
+                "  git clone{depth_arg} {} {}{}",
+                apply_protocol(&task.url, protocol),
+                task.target_path.display(),
+                if exists { " (exists, would skip)" } else { "" }
+            ));
+            if !descend {
+                continue;
+            }
+            if config::find_meta_config_in(&task.target_path).is_some() {
+                queue.push_from_meta(&task.target_path, level)?;
+            } else if !exists {
+                unexplored += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} Would clone {} repositories from {}:",
+        style("[DRY RUN]").cyan(),
+        planned,
+        meta_root.display()
+    );
+    for line in lines {
+        println!("{line}");
+    }
+    if unexplored > 0 {
+        println!("  ({unexplored} not-yet-cloned repos may contain further nested .meta files)");
+    }
     Ok(CommandResult::Message(String::new()))
 }
+
+/// The error for a value-taking flag given last on the command line.
+fn missing_value(flag: &str) -> MetaGitError {
+    MetaGitError::InvalidArgument(format!("{flag} requires a value"))
+}
+
+/// Parse a count flag's value, which must be a whole number of at least 1.
+fn positive_number(flag: &str, value: &str) -> Result<usize, MetaGitError> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(MetaGitError::InvalidArgument(format!(
+            "{flag} requires a number of at least 1, got '{value}'"
+        ))),
+    }
+}
+
+/// Check whether `dir` is inside an existing git work tree.
+fn is_inside_git_work_tree(dir: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .map(|out| out.status.success() && String::from_utf8_lossy(&out.stdout).trim() == "true")
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_flags_without_a_value_are_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let options = PluginRequestOptions::default();
+        for flag in [
+            "--filter",
+            "--event-log",
+            "--include",
+            "--exclude",
+            "--meta-depth",
+            "--into",
+            "--protocol",
+            "--parallel",
+            "--branch",
+            "-b",
+            "--depth",
+        ] {
+            let args = vec!["https://example.com/meta.git".to_string(), flag.to_string()];
+            let error = execute_git_clone(&args, &options, tmp.path()).unwrap_err();
+            let error = MetaGitError::from_anyhow(&error);
+            assert_eq!(error.code(), "invalid_argument", "{flag}");
+            assert!(error.to_string().starts_with(flag), "{flag}: {error}");
+        }
+    }
+
+    #[test]
+    fn count_flags_reject_non_numbers_and_zero() {
+        let tmp = tempfile::tempdir().unwrap();
+        let options = PluginRequestOptions::default();
+        for (flag, value) in [("--parallel", "abc"), ("--parallel", "0"), ("--depth", "0")] {
+            let args = vec![
+                "https://example.com/meta.git".to_string(),
+                flag.to_string(),
+                value.to_string(),
+            ];
+            let error = execute_git_clone(&args, &options, tmp.path()).unwrap_err();
+            let error = MetaGitError::from_anyhow(&error);
+            assert_eq!(error.code(), "invalid_argument", "{flag} {value}");
+            assert!(error.to_string().contains(&format!("'{value}'")), "{error}");
+        }
+    }
+
+    #[test]
+    fn is_inside_git_work_tree_false_for_plain_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_inside_git_work_tree(dir.path()));
+    }
+
+    #[test]
+    fn is_inside_git_work_tree_true_for_repo_subdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .arg("init")
+            .arg("-q")
+            .arg(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let nested = dir.path().join("workspace");
+        std::fs::create_dir(&nested).unwrap();
+        assert!(is_inside_git_work_tree(&nested));
+    }
+}
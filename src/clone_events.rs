@@ -0,0 +1,87 @@
+//! Durable per-repo audit log for `meta git clone`/`update --event-log <file>`.
+//!
+//! Every finished clone task appends one JSON line:
+//!
+//! ```json
+//! {"repo":"api","url":"git@github.com:org/api.git","status":"cloned","duration_ms":2140,"attempts":1}
+//! ```
+//!
+//! `status` is one of `cloned`, `skipped` (target already present), `failed`,
+//! `verify_failed`, `timed_out` (`--timeout`) or `cancelled` (global timeout). The file is opened in
+//! append mode and never truncated, so one log can collect many runs.
+
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CloneEvent<'a> {
+    pub repo: &'a str,
+    pub url: &'a str,
+    pub status: &'a str,
+    pub duration_ms: u64,
+    /// Clone attempts made, including retries (0 when skipped)
+    pub attempts: usize,
+}
+
+/// Shared append-only writer; each event is written as one whole line.
+#[derive(Debug)]
+pub(crate) struct CloneEventLog {
+    file: Mutex<std::fs::File>,
+}
+
+impl CloneEventLog {
+    pub(crate) fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open event log {}: {e}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append one event; a failed write is logged, never fatal to the clone.
+    pub(crate) fn record(&self, event: &CloneEvent<'_>) {
+        let Ok(mut line) = serde_json::to_string(event) else {
+            return;
+        };
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            log::warn!("Failed to write clone event for {}: {e}", event.repo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        std::fs::write(&path, "{\"earlier\":true}\n").unwrap();
+
+        let log = CloneEventLog::open(&path).unwrap();
+        for status in ["cloned", "failed"] {
+            log.record(&CloneEvent {
+                repo: "api",
+                url: "git@example.com:org/api.git",
+                status,
+                duration_ms: 5,
+                attempts: 1,
+            });
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let last: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(last["status"], "failed");
+        assert_eq!(last["repo"], "api");
+    }
+}
@@ -0,0 +1,281 @@
+//! Persisted progress for `meta git clone`, so an interrupted run can resume.
+//!
+//! While children clone, `<meta-dir>/.git/meta-clone-state.json` (inside
+//! `.git`, so it never shows up as untracked) records every target path the
+//! workers have started, finished, or failed, the task behind each started
+//! one, and - once the run stops - the tasks still waiting in the queue:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "completed": ["/work/ws/api"],
+//!   "failed": ["/work/ws/broken"],
+//!   "in_progress": ["/work/ws/web"],
+//!   "tasks": {
+//!     "/work/ws/broken": {"name": "broken", "url": "git@...", "target_path": "/work/ws/broken", "depth": 0}
+//!   },
+//!   "not_started": [{"name": "docs", "url": "git@...", "target_path": "/work/ws/docs", "depth": 0}],
+//!   "queue_drained": true
+//! }
+//! ```
+//!
+//! The file is rewritten on every transition, so it is current whenever the
+//! process dies, and removed once a clone finishes with no failures. Ctrl-C
+//! stops the workers like `--global-timeout` does, so the queue is recorded.
+//! `--resume-from-state` deletes the half-written `in_progress` checkouts it
+//! lists. When the previous run got as far as recording its queue
+//! (`queue_drained`), the failed, interrupted and not-started tasks are put
+//! straight back on the queue without reading any `.meta`; after a hard crash
+//! the manifest is walked again and completed repos are skipped as existing.
+
+use meta_git_lib::clone_queue::CloneTask;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name of the state file inside the meta repo's `.git` directory.
+pub(crate) const CLONE_STATE_FILE: &str = "meta-clone-state.json";
+
+const CLONE_STATE_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub(crate) struct CloneState {
+    pub version: u32,
+    pub completed: BTreeSet<PathBuf>,
+    pub failed: BTreeSet<PathBuf>,
+    pub in_progress: BTreeSet<PathBuf>,
+    /// The task behind every started target, to re-queue it on resume
+    #[serde(default)]
+    pub tasks: BTreeMap<PathBuf, StateTask>,
+    /// Tasks still queued when the run stopped
+    #[serde(default)]
+    pub not_started: Vec<StateTask>,
+    /// Whether `not_started` was recorded, i.e. the task list is complete
+    #[serde(default)]
+    pub queue_drained: bool,
+}
+
+/// A queued repo, with enough to queue it again without reading `.meta`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct StateTask {
+    pub name: String,
+    pub url: String,
+    pub target_path: PathBuf,
+    pub depth: usize,
+}
+
+impl From<&CloneTask> for StateTask {
+    fn from(task: &CloneTask) -> Self {
+        Self {
+            name: task.name.clone(),
+            url: task.url.clone(),
+            target_path: task.target_path.clone(),
+            depth: task.depth,
+        }
+    }
+}
+
+impl From<StateTask> for CloneTask {
+    fn from(task: StateTask) -> Self {
+        CloneTask {
+            name: task.name,
+            url: task.url,
+            target_path: task.target_path,
+            depth: task.depth,
+        }
+    }
+}
+
+impl CloneState {
+    /// What's left to clone: failed, interrupted and never-started tasks.
+    /// `None` when the previous run died before recording its queue.
+    pub(crate) fn remaining_tasks(&self) -> Option<Vec<StateTask>> {
+        if !self.queue_drained {
+            return None;
+        }
+        let unfinished = self
+            .failed
+            .iter()
+            .chain(&self.in_progress)
+            .filter_map(|path| self.tasks.get(path).cloned());
+        Some(unfinished.chain(self.not_started.iter().cloned()).collect())
+    }
+
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let state: CloneState = serde_json::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid clone state {}: {e}", path.display()))?;
+        if state.version != CLONE_STATE_VERSION {
+            anyhow::bail!(
+                "Unsupported clone state version {} in {}",
+                state.version,
+                path.display()
+            );
+        }
+        Ok(state)
+    }
+}
+
+/// Shared, write-through handle to the state file used by clone workers.
+#[derive(Debug)]
+pub(crate) struct CloneStateFile {
+    path: PathBuf,
+    state: Mutex<CloneState>,
+}
+
+impl CloneStateFile {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: Mutex::new(CloneState {
+                version: CLONE_STATE_VERSION,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub(crate) fn started(&self, task: &CloneTask) {
+        self.update(|state| {
+            state.in_progress.insert(task.target_path.clone());
+            state
+                .tasks
+                .insert(task.target_path.clone(), StateTask::from(task));
+        });
+    }
+
+    /// Record the tasks left in the queue once workers have stopped, making
+    /// the state's task list complete.
+    pub(crate) fn drained(&self, not_started: Vec<StateTask>) {
+        self.update(|state| {
+            state.not_started = not_started;
+            state.queue_drained = true;
+        });
+    }
+
+    pub(crate) fn finished(&self, target: &Path, success: bool) {
+        self.update(|state| {
+            state.in_progress.remove(target);
+            let (done, other) = if success {
+                (&mut state.completed, &mut state.failed)
+            } else {
+                (&mut state.failed, &mut state.completed)
+            };
+            other.remove(target);
+            done.insert(target.to_path_buf());
+        });
+    }
+
+    /// Remove the file after a clean run; keep it when anything failed.
+    pub(crate) fn finish_run(&self) {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.failed.is_empty() && state.in_progress.is_empty() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut CloneState)) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut state);
+        // Write under the lock so concurrent workers can't reorder snapshots
+        if let Err(e) = write_atomically(&self.path, &state) {
+            log::warn!("Failed to write {}: {e}", self.path.display());
+        }
+    }
+}
+
+fn write_atomically(path: &Path, state: &CloneState) -> anyhow::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Delete checkouts a previous run left half-written; returns what was removed.
+pub(crate) fn discard_partial_clones(state: &CloneState) -> Vec<PathBuf> {
+    state
+        .in_progress
+        .iter()
+        .filter(|path| path.exists() && std::fs::remove_dir_all(path).is_ok())
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str) -> StateTask {
+        StateTask {
+            name: name.to_string(),
+            url: format!("git@github.com:org/{name}.git"),
+            target_path: PathBuf::from(format!("/ws/{name}")),
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn state_file_tracks_transitions_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CLONE_STATE_FILE);
+        let file = CloneStateFile::new(path.clone());
+
+        file.started(&task("api").into());
+        file.started(&task("web").into());
+        file.finished(Path::new("/ws/api"), true);
+
+        let state = CloneState::load(&path).unwrap();
+        assert_eq!(state.completed, BTreeSet::from([PathBuf::from("/ws/api")]));
+        assert_eq!(
+            state.in_progress,
+            BTreeSet::from([PathBuf::from("/ws/web")])
+        );
+
+        // Unfinished work keeps the file around for --resume-from-state
+        file.finish_run();
+        assert!(path.exists());
+        file.finished(Path::new("/ws/web"), true);
+        file.finish_run();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn discard_partial_clones_removes_only_in_progress_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("web");
+        let done = dir.path().join("api");
+        std::fs::create_dir_all(partial.join(".git")).unwrap();
+        std::fs::create_dir_all(done.join(".git")).unwrap();
+
+        let state = CloneState {
+            version: CLONE_STATE_VERSION,
+            completed: BTreeSet::from([done.clone()]),
+            in_progress: BTreeSet::from([partial.clone()]),
+            ..Default::default()
+        };
+        assert_eq!(discard_partial_clones(&state), vec![partial.clone()]);
+        assert!(!partial.exists());
+        assert!(done.exists());
+    }
+
+    #[test]
+    fn remaining_tasks_need_a_drained_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CLONE_STATE_FILE);
+        let file = CloneStateFile::new(path.clone());
+        for name in ["api", "web", "broken"] {
+            file.started(&task(name).into());
+        }
+        file.finished(Path::new("/ws/api"), true);
+        file.finished(Path::new("/ws/broken"), false);
+
+        // Died before the queue was recorded: fall back to the manifest
+        assert_eq!(CloneState::load(&path).unwrap().remaining_tasks(), None);
+
+        file.drained(vec![task("docs")]);
+        let remaining = CloneState::load(&path).unwrap().remaining_tasks().unwrap();
+        let names: Vec<&str> = remaining.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["broken", "web", "docs"]);
+    }
+}
@@ -1,47 +1,331 @@
+use crate::clone_events::{CloneEvent, CloneEventLog};
+use crate::clone_state::{CloneStateFile, StateTask};
+use crate::url_rewrite::{apply_protocol, Protocol};
 use console::style;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use log::debug;
 use meta_git_lib::clone_queue::{CloneQueue, CloneTask};
-use std::process::Command;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-/// Clone repositories using a worker pool where each worker continuously pulls from the queue.
+/// Settings applied to every child clone.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CloneOptions {
+    /// Set as `GIT_SSH_COMMAND` on all spawned git subprocesses to enable SSH
+    /// multiplexing without requiring `~/.ssh/config` setup.
+    pub ssh_cmd: Option<String>,
+    /// Borrow objects from an already-cloned sibling that shares history
+    /// (`git clone --reference-if-able <sibling> --dissociate`).
+    ///
+    /// Always paired with `--dissociate`: without it the new repo would depend
+    /// on the sibling's object store and break if that repo is removed or gc'd.
+    pub share_objects: bool,
+    /// Sanity-check each fresh clone (`git rev-parse HEAD` and `git status`)
+    /// and mark it failed if either check errors.
+    pub verify: bool,
+    /// `git clone --config` pairs applied to every child clone, from
+    /// [`clone_git_config`].
+    pub git_config: Vec<(String, String)>,
+    /// Progress file for `--resume-from-state`, updated as clones start and finish.
+    pub state: Option<Arc<CloneStateFile>>,
+    /// Hard cap on the whole run (`--global-timeout`). When it elapses, running
+    /// clones are killed and no further tasks are started.
+    pub global_timeout: Option<Duration>,
+    /// Per-clone cap (`--timeout`): a `git clone` still running after this is
+    /// killed and the repo marked failed, so one hung clone can't stall the run.
+    pub timeout: Option<Duration>,
+    /// Extra attempts for a clone that fails (`--retries`), with exponential
+    /// backoff between them. Spawn failures and timeouts are never retried.
+    pub retries: usize,
+    /// Partial-clone filter (`--filter`, e.g. `blob:none`) passed to every child clone.
+    pub filter: Option<String>,
+    /// Append-only JSON-lines audit log (`--event-log`), one line per repo.
+    pub event_log: Option<Arc<CloneEventLog>>,
+    /// Commits from `.meta.lock`, keyed by absolute target path; a pinned
+    /// repo is checked out at its SHA right after cloning.
+    pub pins: std::collections::BTreeMap<PathBuf, String>,
+    /// `--include`/`--exclude` globs; repos that don't match are never cloned.
+    pub path_filter: Option<RepoPathFilter>,
+    /// `--protocol`: rewrite each child URL to SSH or HTTPS before cloning.
+    pub protocol: Option<Protocol>,
+    /// `--lfs`: run `git lfs pull` in each fresh clone (after any pin checkout).
+    pub lfs: bool,
+}
+
+/// `--include`/`--exclude` glob matching on a repo's path relative to the
+/// meta root. A nested repo is only discovered if its parent is cloned, so
+/// include patterns must match the parent path as well.
+#[derive(Debug, Clone)]
+pub(crate) struct RepoPathFilter {
+    root: PathBuf,
+    include: Option<globset::GlobSet>,
+    exclude: Option<globset::GlobSet>,
+}
+
+impl RepoPathFilter {
+    /// `None` when neither list has patterns.
+    pub(crate) fn new(
+        root: &Path,
+        include: &[String],
+        exclude: &[String],
+    ) -> anyhow::Result<Option<Self>> {
+        if include.is_empty() && exclude.is_empty() {
+            return Ok(None);
+        }
+        let build = |patterns: &[String]| -> anyhow::Result<Option<globset::GlobSet>> {
+            if patterns.is_empty() {
+                return Ok(None);
+            }
+            let mut set = globset::GlobSetBuilder::new();
+            for pattern in patterns {
+                set.add(
+                    globset::Glob::new(pattern)
+                        .map_err(|e| anyhow::anyhow!("Invalid glob '{pattern}': {e}"))?,
+                );
+            }
+            Ok(Some(set.build()?))
+        };
+        Ok(Some(Self {
+            root: root.to_path_buf(),
+            include: build(include)?,
+            exclude: build(exclude)?,
+        }))
+    }
+
+    pub(crate) fn matches(&self, target: &Path) -> bool {
+        let rel = target.strip_prefix(&self.root).unwrap_or(target);
+        let included = match &self.include {
+            Some(set) => set.is_match(rel),
+            None => true,
+        };
+        included && !self.exclude.as_ref().is_some_and(|set| set.is_match(rel))
+    }
+}
+
+/// Split comma-separated `--include`/`--exclude` values into patterns.
+pub(crate) fn split_patterns(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+}
+
+/// Retries per repo when `--retries` isn't given.
+pub(crate) const DEFAULT_CLONE_RETRIES: usize = 3;
+
+/// Settings `--preserve-permissions` pins, overriding each developer's
+/// global git config: keep executable bits and never rewrite line endings.
+pub(crate) const PRESERVE_PERMISSIONS_CONFIG: [(&str, &str); 2] =
+    [("core.fileMode", "true"), ("core.autocrlf", "false")];
+
+/// Resolve the git config to apply to child clones.
 ///
-/// If `ssh_cmd` is provided, it's set as `GIT_SSH_COMMAND` on all spawned git subprocesses
-/// to enable SSH multiplexing without requiring `~/.ssh/config` setup.
+/// Reads the optional `git_config` map from the meta repo's `.meta` file
+/// (JSON or YAML), e.g.
+///
+/// ```yaml
+/// git_config:
+///   core.autocrlf: input
+/// ```
+///
+/// then layers the `--preserve-permissions` settings on top. Without either,
+/// nothing is set and git's own defaults apply.
+pub(crate) fn clone_git_config(
+    meta_root: &Path,
+    preserve_permissions: bool,
+) -> Vec<(String, String)> {
+    let mut config: std::collections::BTreeMap<String, String> =
+        meta_config_section(meta_root).into_iter().collect();
+    if preserve_permissions {
+        for (key, value) in PRESERVE_PERMISSIONS_CONFIG {
+            config.insert(key.to_string(), value.to_string());
+        }
+    }
+    config.into_iter().collect()
+}
+
+fn meta_config_section(meta_root: &Path) -> Vec<(String, String)> {
+    let Some((meta_path, _format)) = meta_core::config::find_meta_config_in(meta_root) else {
+        return vec![];
+    };
+    let Ok(contents) = std::fs::read_to_string(&meta_path) else {
+        return vec![];
+    };
+    parse_git_config_section(&contents).unwrap_or_else(|e| {
+        log::warn!("Ignoring git_config in {}: {e}", meta_path.display());
+        vec![]
+    })
+}
+
+/// Extract `git_config` from `.meta` contents. YAML parsing also covers JSON.
+fn parse_git_config_section(contents: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let doc: serde_yaml::Value = serde_yaml::from_str(contents)?;
+    let Some(section) = doc.get("git_config") else {
+        return Ok(vec![]);
+    };
+    let map = section
+        .as_mapping()
+        .ok_or_else(|| anyhow::anyhow!("git_config must be a map of key: value"))?;
+    map.iter()
+        .map(|(key, value)| {
+            let key = key
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("git_config keys must be strings"))?;
+            let value = match value {
+                serde_yaml::Value::String(s) => s.clone(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                serde_yaml::Value::Number(n) => n.to_string(),
+                _ => anyhow::bail!("git_config value for '{key}' must be a scalar"),
+            };
+            Ok((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// What happened during a queue run, beyond the queue's own counts.
+#[derive(Debug, Default)]
+pub(crate) struct CloneReport {
+    /// Repos whose clone succeeded but failed `--verify`, with the reason.
+    pub verify_failures: Vec<(String, String)>,
+    /// Repos cloned fine whose `git lfs pull` failed, with the reason. The
+    /// checkout is kept; it just has pointer files where LFS content belongs.
+    pub lfs_failures: Vec<(String, String)>,
+    /// Repos cloned in this run.
+    pub cloned: usize,
+    /// Repos skipped because their target was already present.
+    pub skipped: Vec<String>,
+    /// Repos left out by `--include`/`--exclude`.
+    pub filtered: Vec<String>,
+    /// Repos that still failed to clone after all retries, with their target path.
+    pub failed: Vec<(String, PathBuf)>,
+    /// The global timeout elapsed before the queue drained.
+    pub timed_out: bool,
+    /// Ctrl-C stopped the run before the queue drained.
+    pub interrupted: bool,
+    /// Clones killed mid-flight by the global timeout or Ctrl-C.
+    pub cancelled: Vec<String>,
+    /// Tasks still queued when the run was stopped.
+    pub not_started: Vec<String>,
+}
+
+/// Set by the SIGINT handler; a clone run polls it and stops its workers.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Route Ctrl-C to [`INTERRUPTED`] so a run can record its queue before it
+/// exits. A second Ctrl-C exits at once.
+fn install_interrupt_handler() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let installed = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+        });
+        if let Err(e) = installed {
+            debug!("Could not install the Ctrl-C handler: {e}");
+        }
+    });
+}
+
+/// Clone repositories using a worker pool where each worker continuously pulls from the queue.
 pub(crate) fn clone_with_queue(
     queue: Arc<CloneQueue>,
     parallelism: usize,
     mp: &MultiProgress,
-    ssh_cmd: Option<&str>,
-) -> anyhow::Result<()> {
+    options: &CloneOptions,
+) -> anyhow::Result<CloneReport> {
     use std::sync::Condvar;
 
     let spinner_style = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
         .unwrap()
         .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
 
+    // Overall bar pinned above the per-repo spinners. Each repo is worth 100
+    // units, so in-flight clones move the bar by their transfer progress; the
+    // length grows as nested .meta discovery adds tasks.
+    let overall = mp.insert(0, ProgressBar::new(0));
+    overall.set_style(
+        ProgressStyle::with_template("{prefix:.bold} [{bar:30}] {msg} ({elapsed})")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    overall.set_prefix("Total");
+    refresh_overall(&overall, &queue, 0);
+
     // Track active workers for termination detection
     let active_workers = Arc::new(AtomicUsize::new(0));
     // Condition variable to signal when work might be available or workers finish
     let work_signal = Arc::new((Mutex::new(false), Condvar::new()));
 
-    let ssh_cmd = Arc::new(ssh_cmd.map(|s| s.to_string()));
+    let options = Arc::new(options.clone());
+    // Repos cloned so far in this run, used as object donors for --share-objects
+    let cloned_paths: Arc<Mutex<Vec<PathBuf>>> = Arc::new(Mutex::new(Vec::new()));
+    let report = Arc::new(Mutex::new(CloneReport::default()));
+    let signals = Arc::new(RunSignals::default());
+    let finished = Arc::new(AtomicBool::new(false));
+    install_interrupt_handler();
+    INTERRUPTED.store(false, Ordering::SeqCst);
 
-    // Spawn worker threads
-    let handles: Vec<_> = (0..parallelism)
+    let refresher = {
+        let overall = overall.clone();
+        let queue = Arc::clone(&queue);
+        let signals = Arc::clone(&signals);
+        let finished = Arc::clone(&finished);
+        std::thread::spawn(move || {
+            while !finished.load(Ordering::SeqCst) {
+                let in_flight = signals.in_flight_percent.load(Ordering::Relaxed);
+                refresh_overall(&overall, &queue, in_flight);
+                std::thread::sleep(Duration::from_millis(200));
+            }
+        })
+    };
+
+    // Watchdog: trips `cancel` on Ctrl-C or once the global timeout elapses
+    let watchdog = {
+        let signals = Arc::clone(&signals);
+        let finished = Arc::clone(&finished);
+        let signal = Arc::clone(&work_signal);
+        let global_timeout = options.global_timeout;
+        let started = Instant::now();
+        std::thread::spawn(move || {
+            while !finished.load(Ordering::SeqCst) {
+                let interrupted = INTERRUPTED.load(Ordering::SeqCst);
+                if interrupted || global_timeout.is_some_and(|t| started.elapsed() >= t) {
+                    signals.interrupted.store(interrupted, Ordering::SeqCst);
+                    signals.cancel.store(true, Ordering::SeqCst);
+                    let (_, cvar) = &*signal;
+                    cvar.notify_all();
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        })
+    };
+
+    // Spawn worker threads (at least one, or nothing would be cloned)
+    let handles: Vec<_> = (0..parallelism.max(1))
         .map(|_worker_id| {
             let queue = Arc::clone(&queue);
             let active = Arc::clone(&active_workers);
             let signal = Arc::clone(&work_signal);
             let mp = mp.clone();
             let style = spinner_style.clone();
-            let ssh_cmd = Arc::clone(&ssh_cmd);
+            let options = Arc::clone(&options);
+            let cloned_paths = Arc::clone(&cloned_paths);
+            let report = Arc::clone(&report);
+            let signals = Arc::clone(&signals);
 
             std::thread::spawn(move || {
                 loop {
+                    if signals.cancel.load(Ordering::SeqCst) {
+                        break;
+                    }
+
                     // Mark worker as active BEFORE taking a task to prevent
                     // a race where is_finished() sees pending=empty, active=0
                     // while a worker is between take_one() and starting work.
@@ -61,7 +345,15 @@ pub(crate) fn clone_with_queue(
                             pb.enable_steady_tick(Duration::from_millis(100));
 
                             // Clone the repo (this may add new tasks to queue)
-                            clone_single_repo(&task, &queue, &pb, ssh_cmd.as_deref());
+                            clone_single_repo(
+                                &task,
+                                &queue,
+                                &pb,
+                                &options,
+                                &cloned_paths,
+                                &report,
+                                &signals,
+                            );
 
                             // Mark worker as inactive
                             active.fetch_sub(1, Ordering::SeqCst);
@@ -98,7 +390,26 @@ pub(crate) fn clone_with_queue(
         handle.join().expect("Worker thread panicked");
     }
 
-    Ok(())
+    finished.store(true, Ordering::SeqCst);
+    let _ = watchdog.join();
+    let _ = refresher.join();
+    refresh_overall(&overall, &queue, 0);
+    overall.finish();
+
+    let mut report = std::mem::take(&mut *report.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut not_started = Vec::new();
+    if signals.cancel.load(Ordering::SeqCst) {
+        report.interrupted = signals.interrupted.load(Ordering::SeqCst);
+        report.timed_out = !report.interrupted;
+        while let Some(task) = queue.take_one() {
+            not_started.push(StateTask::from(&task));
+            report.not_started.push(task.name);
+        }
+    }
+    if let Some(state) = &options.state {
+        state.drained(not_started);
+    }
+    Ok(report)
 }
 
 /// Clone a single repository and handle .meta discovery
@@ -106,8 +417,41 @@ fn clone_single_repo(
     task: &CloneTask,
     queue: &Arc<CloneQueue>,
     pb: &ProgressBar,
-    ssh_cmd: Option<&str>,
+    options: &CloneOptions,
+    cloned_paths: &Mutex<Vec<PathBuf>>,
+    report: &Mutex<CloneReport>,
+    signals: &Arc<RunSignals>,
 ) {
+    let started = Instant::now();
+    let url = apply_protocol(&task.url, options.protocol);
+    let log_event = |status: &str, attempts: usize| {
+        if let Some(log) = &options.event_log {
+            log.record(&CloneEvent {
+                repo: &task.name,
+                url: &url,
+                status,
+                duration_ms: started.elapsed().as_millis() as u64,
+                attempts,
+            });
+        }
+    };
+
+    if let Some(filter) = &options.path_filter {
+        if !filter.matches(&task.target_path) {
+            pb.finish_and_clear();
+            report
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .filtered
+                .push(task.name.clone());
+            // Finish the task without mark_completed's nested .meta
+            // discovery: a filtered-out parent may exist on disk, and its
+            // children were never asked for
+            queue.mark_failed(task);
+            return;
+        }
+    }
+
     // Skip if target exists
     if task.target_path.exists()
         && task
@@ -120,6 +464,12 @@ fn clone_single_repo(
             "{}",
             style(format!("Skipped {} (exists)", task.name)).yellow()
         ));
+        report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .skipped
+            .push(task.name.clone());
+        log_event("skipped", 0);
         // Still mark as completed and check for nested .meta
         if let Err(e) = queue.mark_completed(task) {
             debug!("Failed to check nested .meta for {}: {}", task.name, e);
@@ -127,79 +477,229 @@ fn clone_single_repo(
         return;
     }
 
+    if let Some(state) = &options.state {
+        state.started(task);
+    }
+    let record = |success: bool| {
+        if let Some(state) = &options.state {
+            state.finished(&task.target_path, success);
+        }
+    };
+
     // Build git clone command
     let mut cmd = Command::new("git");
-    cmd.arg("clone").arg(&task.url).arg(&task.target_path);
+    cmd.arg("clone");
+    for (key, value) in &options.git_config {
+        cmd.arg("--config").arg(format!("{key}={value}"));
+    }
+    cmd.arg(&url).arg(&task.target_path);
     if let Some(d) = queue.git_depth() {
         cmd.arg("--depth").arg(d);
     }
-    if let Some(ssh) = ssh_cmd {
+    if let Some(ref spec) = options.filter {
+        cmd.arg(format!("--filter={spec}"));
+    }
+    if let Some(ref ssh) = options.ssh_cmd {
         cmd.env("GIT_SSH_COMMAND", ssh);
     }
+    if options.share_objects {
+        let candidates = cloned_paths
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(donor) = find_object_donor(&url, &candidates, options.ssh_cmd.as_deref()) {
+            debug!("Sharing objects for {} from {}", task.name, donor.display());
+            pb.set_message(format!(
+                "Cloning {} (reusing objects from {})",
+                task.name,
+                donor.display()
+            ));
+            cmd.arg("--reference-if-able")
+                .arg(&donor)
+                .arg("--dissociate");
+        }
+    }
 
-    // Run clone
-    match cmd
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-    {
-        Ok(mut child) => {
-            // Stream stderr for progress updates
-            let stderr = child.stderr.take();
-            let pb_clone = pb.clone();
-            let task_name = task.name.clone();
-            if let Some(stderr) = stderr {
-                std::thread::spawn(move || {
-                    use std::io::{BufRead, BufReader};
-                    let reader = BufReader::new(stderr);
-                    for line in reader.lines().map_while(Result::ok) {
-                        pb_clone.set_message(format!("{task_name}: {line}"));
-                    }
-                });
-            }
+    // Run clone, retrying transient failures with backoff
+    // --progress: git only reports transfer progress to a tty otherwise
+    cmd.arg("--progress")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut retry = 0;
+    let attempt = loop {
+        let attempt = run_clone_attempt(&mut cmd, task, pb, signals, options.timeout);
+        if !matches!(attempt, CloneAttempt::Failed) || retry >= options.retries {
+            break attempt;
+        }
+        retry += 1;
+        // Start the next attempt from an empty target
+        let _ = std::fs::remove_dir_all(&task.target_path);
+        pb.set_message(format!(
+            "Cloning {} (retry {}/{})",
+            task.name, retry, options.retries
+        ));
+        if !sleep_unless_cancelled(retry_backoff(retry), &signals.cancel) {
+            break CloneAttempt::Cancelled;
+        }
+    };
 
-            match child.wait() {
-                Ok(status) if status.success() => {
-                    // Check for nested .meta and report new discoveries
-                    match queue.mark_completed(task) {
-                        Ok(added) if added > 0 => {
-                            let (_, total) = queue.get_counts();
-                            pb.finish_with_message(format!(
-                                "{}",
-                                style(format!("Cloned {} (+{} nested)", task.name, added)).green()
-                            ));
-                            // Update for new total
-                            debug!(
-                                "Discovered {} more repos in {}, total now {}",
-                                added, task.name, total
-                            );
-                        }
-                        Ok(_) => {
-                            pb.finish_with_message(format!(
-                                "{}",
-                                style(format!("Cloned {}", task.name)).green()
-                            ));
-                        }
-                        Err(e) => {
-                            pb.finish_with_message(format!(
-                                "{}",
-                                style(format!("Cloned {} (meta parse error: {})", task.name, e))
-                                    .yellow()
-                            ));
-                        }
-                    }
+    match attempt {
+        CloneAttempt::Cancelled => {
+            // Killed by the global timeout or Ctrl-C: drop the half-written checkout
+            record(false);
+            log_event("cancelled", retry + 1);
+            queue.mark_failed(task);
+            let _ = std::fs::remove_dir_all(&task.target_path);
+            report
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .cancelled
+                .push(task.name.clone());
+            let why = if signals.interrupted.load(Ordering::SeqCst) {
+                "interrupted"
+            } else {
+                "global timeout"
+            };
+            pb.finish_with_message(format!(
+                "{}",
+                style(format!("Cancelled {} ({why})", task.name)).red()
+            ));
+        }
+        CloneAttempt::Succeeded => {
+            if options.verify {
+                if let Err(reason) = verify_clone(&task.target_path) {
+                    record(false);
+                    log_event("verify_failed", retry + 1);
+                    queue.mark_failed(task);
+                    pb.finish_with_message(format!(
+                        "{}",
+                        style(format!("Verification failed for {}: {reason}", task.name)).red()
+                    ));
+                    report
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .verify_failures
+                        .push((task.name.clone(), reason));
+                    return;
                 }
-                _ => {
+            }
+            if let Some(sha) = options.pins.get(&task.target_path) {
+                if let Err(reason) = crate::lock::checkout_pin(&task.target_path, sha) {
+                    record(false);
+                    log_event("failed", retry + 1);
                     queue.mark_failed(task);
+                    report
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .failed
+                        .push((task.name.clone(), task.target_path.clone()));
+                    pb.finish_with_message(format!(
+                        "{}",
+                        style(format!(
+                            "Cloned {} but could not check out pinned {sha}: {reason}",
+                            task.name
+                        ))
+                        .red()
+                    ));
+                    return;
+                }
+            }
+            if options.lfs {
+                pb.set_message(format!("Fetching LFS objects for {}", task.name));
+                if let Err(reason) = lfs_pull(&task.target_path, options.ssh_cmd.as_deref()) {
+                    report
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .lfs_failures
+                        .push((task.name.clone(), reason));
+                }
+            }
+            record(true);
+            log_event("cloned", retry + 1);
+            report.lock().unwrap_or_else(|e| e.into_inner()).cloned += 1;
+            if options.share_objects {
+                cloned_paths
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(task.target_path.clone());
+            }
+            // Check for nested .meta and report new discoveries
+            match queue.mark_completed(task) {
+                Ok(added) if added > 0 => {
+                    let (_, total) = queue.get_counts();
+                    pb.finish_with_message(format!(
+                        "{}",
+                        style(format!("Cloned {} (+{} nested)", task.name, added)).green()
+                    ));
+                    // Update for new total
+                    debug!(
+                        "Discovered {} more repos in {}, total now {}",
+                        added, task.name, total
+                    );
+                }
+                Ok(_) => {
+                    pb.finish_with_message(format!(
+                        "{}",
+                        style(format!("Cloned {}", task.name)).green()
+                    ));
+                }
+                Err(e) => {
                     pb.finish_with_message(format!(
                         "{}",
-                        style(format!("Failed to clone {}", task.name)).red()
+                        style(format!("Cloned {} (meta parse error: {})", task.name, e)).yellow()
                     ));
                 }
             }
         }
-        Err(_) => {
+        CloneAttempt::TimedOut => {
+            record(false);
+            log_event("timed_out", retry + 1);
+            queue.mark_failed(task);
+            let _ = std::fs::remove_dir_all(&task.target_path);
+            report
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .failed
+                .push((task.name.clone(), task.target_path.clone()));
+            let limit = options.timeout.map(|t| t.as_secs()).unwrap_or_default();
+            pb.finish_with_message(format!(
+                "{}",
+                style(format!(
+                    "Failed to clone {} (timed out after {})",
+                    task.name,
+                    meta_git_lib::worktree::helpers::format_duration(limit as i64)
+                ))
+                .red()
+            ));
+        }
+        CloneAttempt::Failed => {
+            record(false);
+            log_event("failed", retry + 1);
+            queue.mark_failed(task);
+            report
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .failed
+                .push((task.name.clone(), task.target_path.clone()));
+            let note = if retry > 0 {
+                format!(" after {} attempts", retry + 1)
+            } else {
+                String::new()
+            };
+            pb.finish_with_message(format!(
+                "{}",
+                style(format!("Failed to clone {}{note}", task.name)).red()
+            ));
+        }
+        CloneAttempt::SpawnFailed => {
+            record(false);
+            log_event("failed", retry + 1);
             queue.mark_failed(task);
+            report
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .failed
+                .push((task.name.clone(), task.target_path.clone()));
             pb.finish_with_message(format!(
                 "{}",
                 style(format!("Failed to spawn git for {}", task.name)).red()
@@ -207,3 +707,407 @@ fn clone_single_repo(
         }
     }
 }
+
+/// Outcome of one `git clone` run.
+enum CloneAttempt {
+    Succeeded,
+    /// git exited non-zero; worth retrying
+    Failed,
+    /// git could not be started at all; retrying won't help
+    SpawnFailed,
+    /// Killed by the global timeout
+    Cancelled,
+    /// Killed after running longer than `--timeout`
+    TimedOut,
+}
+
+fn run_clone_attempt(
+    cmd: &mut Command,
+    task: &CloneTask,
+    pb: &ProgressBar,
+    signals: &Arc<RunSignals>,
+    timeout: Option<Duration>,
+) -> CloneAttempt {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let Ok(mut child) = cmd.spawn() else {
+        return CloneAttempt::SpawnFailed;
+    };
+
+    // Stream stderr for progress updates. git redraws progress with `\r`, so
+    // split on that as well as newlines.
+    if let Some(stderr) = child.stderr.take() {
+        let pb_clone = pb.clone();
+        let task_name = task.name.clone();
+        let signals = Arc::clone(signals);
+        std::thread::spawn(move || {
+            use std::io::{BufRead, BufReader};
+            let mut reader = BufReader::new(stderr);
+            let mut reported = 0;
+            let mut segment = Vec::new();
+            while reader.read_until(b'\r', &mut segment).unwrap_or(0) > 0 {
+                for line in String::from_utf8_lossy(&segment).split(['\r', '\n']) {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    match parse_git_progress(line) {
+                        Some((phase, percent)) => {
+                            pb_clone.set_message(format!("{task_name}: {phase} {percent}%"));
+                            let overall = phase.overall_percent(percent);
+                            if overall > reported {
+                                signals
+                                    .in_flight_percent
+                                    .fetch_add(overall - reported, Ordering::Relaxed);
+                                reported = overall;
+                            }
+                        }
+                        None => pb_clone.set_message(format!("{task_name}: {line}")),
+                    }
+                }
+                segment.clear();
+            }
+            signals
+                .in_flight_percent
+                .fetch_sub(reported, Ordering::Relaxed);
+        });
+    }
+
+    wait_for_clone(&mut child, &signals.cancel, deadline)
+}
+
+/// Flags and counters shared by every worker for one `clone_with_queue` run.
+#[derive(Default)]
+struct RunSignals {
+    /// Set by the watchdog on Ctrl-C or when the global timeout elapses
+    cancel: AtomicBool,
+    /// Whether `cancel` came from Ctrl-C
+    interrupted: AtomicBool,
+    /// Sum of the in-flight clones' progress, 0-100 each
+    in_flight_percent: AtomicUsize,
+}
+
+/// Redraw the overall bar: 100 units per repo, finished repos count in full.
+fn refresh_overall(overall: &ProgressBar, queue: &CloneQueue, in_flight_percent: usize) {
+    let (completed, total) = queue.get_counts();
+    let len = (total * 100) as u64;
+    overall.set_length(len);
+    overall.set_position(((completed * 100 + in_flight_percent) as u64).min(len));
+    overall.set_message(format!("{completed}/{total}"));
+}
+
+/// Transfer phases of `git clone --progress`, in the order git runs them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum GitProgressPhase {
+    Counting,
+    Receiving,
+    Resolving,
+}
+
+impl GitProgressPhase {
+    /// Map a phase percentage onto the whole clone: counting is quick,
+    /// receiving dominates, resolving deltas finishes it off.
+    fn overall_percent(self, percent: usize) -> usize {
+        match self {
+            Self::Counting => percent / 10,
+            Self::Receiving => 10 + percent * 8 / 10,
+            Self::Resolving => 90 + percent / 10,
+        }
+    }
+}
+
+impl std::fmt::Display for GitProgressPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Counting => "counting objects",
+            Self::Receiving => "receiving objects",
+            Self::Resolving => "resolving deltas",
+        })
+    }
+}
+
+/// Extract the phase and percentage from a git progress line, e.g.
+/// `Receiving objects:  67% (134/200), 1.20 MiB | 2.00 MiB/s`.
+pub(crate) fn parse_git_progress(line: &str) -> Option<(GitProgressPhase, usize)> {
+    let line = line.trim_start_matches("remote:").trim();
+    let (phase, rest) = [
+        ("Counting objects:", GitProgressPhase::Counting),
+        ("Receiving objects:", GitProgressPhase::Receiving),
+        ("Resolving deltas:", GitProgressPhase::Resolving),
+    ]
+    .iter()
+    .find_map(|(prefix, phase)| line.strip_prefix(prefix).map(|rest| (*phase, rest)))?;
+    let percent = rest
+        .trim_start()
+        .split('%')
+        .next()?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    Some((phase, percent.min(100)))
+}
+
+/// Delay before retry number `retry` (1-based): 1s, 2s, 4s, ... capped at 30s.
+fn retry_backoff(retry: usize) -> Duration {
+    let secs = 1u64 << (retry.saturating_sub(1)).min(5);
+    Duration::from_secs(secs.min(30))
+}
+
+/// Sleep for `duration`, returning early with `false` if `cancel` trips.
+fn sleep_unless_cancelled(duration: Duration, cancel: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if cancel.load(Ordering::SeqCst) {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    !cancel.load(Ordering::SeqCst)
+}
+
+/// Wait for a clone to exit, killing it if `cancel` trips or `deadline` passes first.
+fn wait_for_clone(
+    child: &mut std::process::Child,
+    cancel: &AtomicBool,
+    deadline: Option<Instant>,
+) -> CloneAttempt {
+    loop {
+        let killed = match child.try_wait() {
+            Ok(Some(status)) if status.success() => return CloneAttempt::Succeeded,
+            Ok(Some(_)) | Err(_) => return CloneAttempt::Failed,
+            Ok(None) if cancel.load(Ordering::SeqCst) => CloneAttempt::Cancelled,
+            Ok(None) if deadline.is_some_and(|d| Instant::now() >= d) => CloneAttempt::TimedOut,
+            Ok(None) => {
+                std::thread::sleep(Duration::from_millis(50));
+                continue;
+            }
+        };
+        let _ = child.kill();
+        let _ = child.wait();
+        return killed;
+    }
+}
+
+/// Quick post-clone sanity check: HEAD resolves and the index/worktree can be read.
+fn verify_clone(path: &Path) -> Result<(), String> {
+    for args in [
+        &["rev-parse", "--verify", "--quiet", "HEAD"][..],
+        &["status", "--porcelain"][..],
+    ] {
+        let out = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("failed to spawn git: {e}"))?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            let detail = stderr
+                .lines()
+                .last()
+                .unwrap_or("no output")
+                .trim()
+                .to_string();
+            return Err(format!("git {} failed ({detail})", args[0]));
+        }
+    }
+    Ok(())
+}
+
+/// Download the LFS objects of a fresh clone's checkout.
+fn lfs_pull(path: &Path, ssh_cmd: Option<&str>) -> Result<(), String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C")
+        .arg(path)
+        .args(["lfs", "pull"])
+        .stdin(Stdio::null());
+    if let Some(ssh) = ssh_cmd {
+        cmd.env("GIT_SSH_COMMAND", ssh);
+    }
+    let out = cmd
+        .output()
+        .map_err(|e| format!("failed to spawn git lfs: {e}"))?;
+    if out.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    Err(stderr
+        .lines()
+        .last()
+        .unwrap_or("git lfs pull failed")
+        .trim()
+        .to_string())
+}
+
+/// Find an already-cloned repo that shares history with the remote at `url`.
+///
+/// Lists the remote's refs and returns the first candidate that already has
+/// any of the advertised commits, which is the case for forks of one another.
+///
+/// This matches on branch and tag tips rather than root commits: a remote's
+/// root commits can only be read from its full commit graph, which is most
+/// of what the clone itself would download. Forks whose every branch and tag
+/// has moved on since they split get no donor and clone normally.
+fn find_object_donor(url: &str, candidates: &[PathBuf], ssh_cmd: Option<&str>) -> Option<PathBuf> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["ls-remote", "--heads", "--tags", url])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(ssh) = ssh_cmd {
+        cmd.env("GIT_SSH_COMMAND", ssh);
+    }
+    let output = cmd.output().ok().filter(|o| o.status.success())?;
+    let mut shas: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect();
+    shas.sort();
+    shas.dedup();
+    if shas.is_empty() {
+        return None;
+    }
+
+    candidates
+        .iter()
+        .find(|candidate| has_any_object(candidate, &shas))
+        .cloned()
+}
+
+/// Check whether `repo` contains at least one of `shas`.
+fn has_any_object(repo: &Path, shas: &[String]) -> bool {
+    let Ok(mut child) = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cat-file", "--batch-check"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+
+    // Feed stdin from its own thread: with many refs cat-file fills its
+    // stdout pipe before we finish writing, and both sides would block
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input = shas.join("\n") + "\n";
+        std::thread::spawn(move || stdin.write_all(input.as_bytes()).is_ok())
+    });
+
+    let output = child.wait_with_output();
+    let written = match writer {
+        Some(w) => w.join().unwrap_or(false),
+        None => true,
+    };
+    written
+        && output
+            .map(|out| {
+                out.status.success()
+                    && String::from_utf8_lossy(&out.stdout)
+                        .lines()
+                        .any(|line| !line.ends_with(" missing"))
+            })
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn parse_git_progress_handles_each_phase() {
+        assert_eq!(
+            parse_git_progress("remote: Counting objects:  45% (9/20)"),
+            Some((GitProgressPhase::Counting, 45))
+        );
+        assert_eq!(
+            parse_git_progress("Receiving objects:  67% (134/200), 1.20 MiB | 2.00 MiB/s"),
+            Some((GitProgressPhase::Receiving, 67))
+        );
+        assert_eq!(
+            parse_git_progress("Resolving deltas: 100% (50/50), done."),
+            Some((GitProgressPhase::Resolving, 100))
+        );
+        assert_eq!(parse_git_progress("Cloning into 'api'..."), None);
+        assert_eq!(GitProgressPhase::Receiving.overall_percent(100), 90);
+    }
+
+    #[test]
+    fn repo_path_filter_applies_include_then_exclude() {
+        let root = Path::new("/ws");
+        let patterns = |s: &str| split_patterns(s).collect::<Vec<_>>();
+        let filter = RepoPathFilter::new(root, &patterns("services/*, web"), &patterns("*-legacy"))
+            .unwrap()
+            .unwrap();
+        assert!(filter.matches(&root.join("services/api")));
+        assert!(filter.matches(&root.join("web")));
+        assert!(!filter.matches(&root.join("services/api-legacy")));
+        assert!(!filter.matches(&root.join("docs")));
+        assert!(RepoPathFilter::new(root, &[], &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn retry_backoff_doubles_and_caps() {
+        assert_eq!(retry_backoff(1), Duration::from_secs(1));
+        assert_eq!(retry_backoff(2), Duration::from_secs(2));
+        assert_eq!(retry_backoff(3), Duration::from_secs(4));
+        assert_eq!(retry_backoff(10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn git_config_section_reads_yaml_and_json() {
+        let yaml = "projects:\n  api: git@example.com:org/api.git\ngit_config:\n  core.autocrlf: input\n  core.fileMode: false\n";
+        assert_eq!(
+            parse_git_config_section(yaml).unwrap(),
+            vec![
+                ("core.autocrlf".to_string(), "input".to_string()),
+                ("core.fileMode".to_string(), "false".to_string()),
+            ]
+        );
+
+        let json = r#"{"projects": {}, "git_config": {"core.autocrlf": "false"}}"#;
+        assert_eq!(
+            parse_git_config_section(json).unwrap(),
+            vec![("core.autocrlf".to_string(), "false".to_string())]
+        );
+        assert!(parse_git_config_section(r#"{"projects": {}}"#)
+            .unwrap()
+            .is_empty());
+        assert!(parse_git_config_section("git_config: [a]").is_err());
+    }
+
+    #[test]
+    fn preserve_permissions_overrides_meta_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".meta.yaml"),
+            "projects: {}\ngit_config:\n  core.autocrlf: input\n  pull.rebase: true\n",
+        )
+        .unwrap();
+        let config = clone_git_config(dir.path(), true);
+        assert!(config.contains(&("core.autocrlf".to_string(), "false".to_string())));
+        assert!(config.contains(&("core.fileMode".to_string(), "true".to_string())));
+        assert!(config.contains(&("pull.rebase".to_string(), "true".to_string())));
+    }
+
+    #[test]
+    fn has_any_object_handles_more_refs_than_a_pipe_holds() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join("f"), "x").unwrap();
+        let blob = git(dir.path(), &["hash-object", "-w", "f"]);
+
+        // cat-file answers ~50 bytes per line, well past 64 KiB here
+        let mut shas: Vec<String> = (0..5000).map(|i| format!("{i:040x}")).collect();
+        assert!(!has_any_object(dir.path(), &shas));
+        shas.push(blob.trim().to_string());
+        assert!(has_any_object(dir.path(), &shas));
+    }
+}
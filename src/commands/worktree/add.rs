@@ -8,6 +8,8 @@ use meta_git_lib::worktree::store::store_extend_repos;
 use meta_git_lib::worktree::types::*;
 
 use super::cli_types::AddArgs;
+use crate::error::Categorize;
+use crate::MetaGitError;
 
 pub(crate) fn handle_add(args: AddArgs, verbose: bool, json: bool, strict: bool) -> Result<()> {
     let name = &args.name;
@@ -23,12 +25,17 @@ pub(crate) fn handle_add(args: AddArgs, verbose: bool, json: bool, strict: bool)
         );
     }
 
-    let meta_dir = require_meta_dir()?;
+    let meta_dir = require_meta_dir().categorize(MetaGitError::NotAMetaRepo)?;
     let worktree_root = resolve_worktree_root(Some(&meta_dir))?;
     let wt_dir = worktree_root.join(name);
 
     if !wt_dir.exists() {
-        anyhow::bail!("Worktree '{}' not found at {}", name, wt_dir.display());
+        return Err(MetaGitError::NotFound(format!(
+            "Worktree '{}' not found at {}",
+            name,
+            wt_dir.display()
+        ))
+        .into());
     }
 
     // Check existing repos in the worktree
@@ -2,7 +2,7 @@
 //!
 //! These are clap-derived types that belong in the CLI crate, not the library.
 
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use meta_git_lib::worktree::RepoSpec;
 
 /// Worktree subcommands parsed by clap.
@@ -27,6 +27,16 @@ pub enum WorktreeCommands {
     Exec(ExecArgs),
     /// Remove expired/orphaned worktrees
     Prune(PruneArgs),
+    /// Print a worktree set's path (for `cd "$(meta worktree path NAME)"`)
+    Path(PathArgs),
+    /// Rename a worktree set (moves its directory; branches are kept)
+    Rename(RenameArgs),
+    /// Reset a worktree set's TTL, or make it persistent
+    Extend(ExtendArgs),
+    /// Merge (or rebase) the base branch into every repo in a set
+    Sync(SyncArgs),
+    /// Maintain the worktree store
+    Store(StoreArgs),
     #[command(external_subcommand)]
     Unknown(Vec<String>),
 }
@@ -60,10 +70,19 @@ pub struct CreateArgs {
     #[arg(long, value_name = "REF", hide = true, conflicts_with_all = ["commit_ish", "from_pr"])]
     pub from_ref: Option<String>,
 
-    /// Start from a PR's head branch (owner/repo#N)
+    /// Start from a PR's head branch (owner/repo#N; GitHub, GitLab or Bitbucket)
     #[arg(long, value_name = "OWNER/REPO#N", conflicts_with = "commit_ish")]
     pub from_pr: Option<String>,
 
+    /// Check out an existing remote branch (origin/<NAME>) in every repo that has it
+    ///
+    /// Repos without origin/<NAME> start from origin's default branch
+    /// (origin/HEAD) with a warning (an error with --strict). A local <NAME> that is behind is
+    /// fast-forwarded; one that diverged is used as-is with a warning. An
+    /// explicit --repo alias:branch still wins for that repo.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["commit_ish", "from_ref", "from_pr", "current_branch"])]
+    pub from_branch: Option<String>,
+
     /// Mark for automatic cleanup
     #[arg(long)]
     pub ephemeral: bool,
@@ -93,6 +112,18 @@ pub struct CreateArgs {
     #[arg(long)]
     pub no_deps: bool,
 
+    /// Suppress the human-readable summary (errors and warnings still go to stderr)
+    #[arg(long, short = 'q')]
+    pub quiet: bool,
+
+    /// Exclude the meta repo (".") from the worktree set
+    ///
+    /// By default, the root repo is included whenever the meta dir is a git
+    /// repo. With --no-root, only child repos get worktrees and the set
+    /// directory is created as a plain directory instead of a root checkout.
+    #[arg(long)]
+    pub no_root: bool,
+
     /// Resolve dependencies across the full nested meta tree
     ///
     /// By default, dependencies are resolved from the current .meta level.
@@ -101,6 +132,44 @@ pub struct CreateArgs {
     /// use full paths from root (e.g., open-source/gitkb/core).
     #[arg(long, short = 'r')]
     pub recursive: bool,
+
+    /// Run a shell command in the set's root after creation (and post-create hooks)
+    ///
+    /// A failing command is reported but doesn't fail the create unless --strict.
+    #[arg(long, value_name = "COMMAND")]
+    pub then: Option<String>,
+
+    /// Replace an existing set of the same name
+    ///
+    /// The existing set is removed first, the same as `meta worktree remove
+    /// --force`: uncommitted changes in it are discarded.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Check out each source repo's current commit instead of a per-set branch
+    ///
+    /// Worktrees are detached at the tip of the source's current branch (or
+    /// its HEAD when the source is detached), since git won't let one branch
+    /// be checked out twice. Those repos are recorded (and printed) with the
+    /// commit SHA rather than the branch, so `worktree sync` and friends treat
+    /// them as detached. An explicit --repo alias:branch still wins for that
+    /// repo.
+    #[arg(long, conflicts_with_all = ["branch", "commit_ish", "from_ref", "from_pr"])]
+    pub current_branch: bool,
+
+    /// Fail instead of reusing a branch that already exists in a repo
+    ///
+    /// By default an existing branch is checked out as-is and reported as
+    /// reused.
+    #[arg(long)]
+    pub new_branch_only: bool,
+
+    /// Reset branches that already exist to the start point before checkout
+    ///
+    /// The start point is the <COMMIT-ISH> when given, else the source repo's
+    /// HEAD. Commits only on the old branch are no longer reachable from it.
+    #[arg(long, conflicts_with_all = ["new_branch_only", "current_branch", "from_pr", "from_branch"])]
+    pub reset_branch: bool,
 }
 
 #[derive(Args)]
@@ -121,15 +190,124 @@ pub struct DestroyArgs {
     /// Remove even with uncommitted changes
     #[arg(long)]
     pub force: bool,
+
+    /// Also delete branches this worktree set created
+    ///
+    /// Only branches recorded as created by `meta worktree create`/`add` are
+    /// deleted; pre-existing branches are always kept. Unmerged branches are
+    /// only deleted together with --force.
+    #[arg(long)]
+    pub delete_branch: bool,
+
+    /// Suppress the summary output
+    #[arg(long, short = 'q')]
+    pub quiet: bool,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// Check that every repo in each set is readable by git (slower)
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Group sets under the meta repo (project) that created them
+    #[arg(long)]
+    pub group_by_project: bool,
+
+    /// Only show ephemeral sets
+    #[arg(long, conflicts_with = "persistent")]
+    pub ephemeral: bool,
+
+    /// Only show sets that aren't ephemeral
+    #[arg(long)]
+    pub persistent: bool,
+
+    /// Only show sets whose TTL has run out
+    #[arg(long)]
+    pub expired: bool,
+
+    /// Order sets by name, creation time (oldest first) or TTL left (soonest first)
+    #[arg(long, value_enum, default_value_t = ListSort::Name)]
+    pub sort: ListSort,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ListSort {
+    Name,
+    Created,
+    Ttl,
 }
 
 #[derive(Args)]
-pub struct ListArgs {}
+pub struct PathArgs {
+    /// Worktree name
+    pub name: String,
+
+    /// Print this repo's path instead of the set root
+    pub repo: Option<String>,
+}
+
+#[derive(Args)]
+pub struct RenameArgs {
+    /// Current worktree name
+    pub old: String,
+
+    /// New worktree name
+    pub new: String,
+}
+
+#[derive(Args)]
+pub struct ExtendArgs {
+    /// Worktree name
+    pub name: String,
+
+    /// New time-to-live from now (30s, 5m, 1h, 2d, 1w)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_clap,
+          required_unless_present = "persistent", conflicts_with = "persistent")]
+    pub ttl: Option<u64>,
+
+    /// Clear the TTL and the ephemeral flag so prune keeps the set
+    #[arg(long)]
+    pub persistent: bool,
+}
+
+#[derive(Args)]
+pub struct SyncArgs {
+    /// Worktree name
+    pub name: String,
+
+    /// Base branch to sync onto (fetched from origin). Defaults to each
+    /// repo's origin/HEAD
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Rebase onto the base instead of merging it
+    #[arg(long)]
+    pub rebase: bool,
+}
 
 #[derive(Args)]
 pub struct StatusArgs {
     /// Worktree name
     pub name: String,
+
+    /// Re-render the status periodically until interrupted (Ctrl-C)
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Seconds between refreshes in --watch mode
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 2,
+        requires = "watch",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub interval: u64,
+
+    /// Show the number of stash entries made on each repo's branch
+    #[arg(long)]
+    pub stash: bool,
 }
 
 #[derive(Args)]
@@ -144,6 +322,28 @@ pub struct DiffArgs {
     /// Show diffstat summary only
     #[arg(long)]
     pub stat: bool,
+
+    /// List changed files per repo instead of counts
+    #[arg(long, conflicts_with_all = ["stat", "patch"])]
+    pub name_only: bool,
+
+    /// Print one combined patch for the whole set, with a header per repo
+    ///
+    /// Paths are prefixed with each repo's alias, so the patch applies from
+    /// the worktree root with `git apply`.
+    #[arg(long, short = 'p', conflicts_with = "stat")]
+    pub patch: bool,
+
+    /// Write the --patch output to FILE instead of stdout
+    #[arg(long, short = 'o', value_name = "FILE", requires = "patch")]
+    pub output: Option<std::path::PathBuf>,
+
+    /// Compare each repo against the SHA recorded in a snapshot (overrides --base)
+    #[arg(long, value_name = "SNAPSHOT")]
+    pub against_snapshot: Option<String>,
+    /// Exit non-zero when any repo differs from the base (like `git diff --exit-code`)
+    #[arg(long)]
+    pub exit_code: bool,
 }
 
 #[derive(Args)]
@@ -167,10 +367,54 @@ pub struct ExecArgs {
     #[arg(long)]
     pub parallel: bool,
 
+    /// Run at most N repos at once (implies --parallel; default: CPU count)
+    #[arg(long, short = 'j', value_name = "N", value_parser = parse_jobs_clap)]
+    pub jobs: Option<usize>,
+
+    /// Suppress per-repo headers; only the commands' own output is printed
+    #[arg(long, short = 'q')]
+    pub quiet: bool,
+
+    /// Set an environment variable for the command (repeatable)
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
+    /// Load environment variables from a dotenv-style file
+    ///
+    /// Lines are KEY=VALUE; blank lines and # comments are ignored.
+    /// Precedence (later wins): git defaults, --env-file, --env.
+    #[arg(long, value_name = "PATH")]
+    pub env_file: Option<std::path::PathBuf>,
+
+    /// Run with a minimal environment instead of inheriting the parent's
+    ///
+    /// Only PATH, HOME, the git defaults and --env/--env-file vars are set.
+    #[arg(long)]
+    pub clean_env: bool,
+
+    /// Re-run the command in a repo up to N more times if it fails
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retry: u32,
+
+    /// Keep going in the remaining repos after one fails, then summarize
+    ///
+    /// Prints a per-repo table once every repo ran and exits non-zero if
+    /// any of them failed.
+    #[arg(long)]
+    pub continue_on_error: bool,
+
+    /// Run even in repos with a rebase, merge, cherry-pick or revert in progress
+    #[arg(long)]
+    pub force_in_progress: bool,
+
     /// Atomic create+exec+remove (requires --all or --repo, and -- <cmd>)
     #[arg(long)]
     pub ephemeral: bool,
 
+    /// Keep the ephemeral worktree when the command fails, for inspection
+    #[arg(long)]
+    pub keep_on_failure: bool,
+
     // --- Ephemeral-only create flags (ignored when not --ephemeral) ---
     /// Add specific repo(s) for ephemeral worktree (alias or alias:branch)
     #[arg(long = "repo", value_name = "ALIAS[:BRANCH]")]
@@ -197,6 +441,12 @@ pub struct ExecArgs {
     pub branch: Option<String>,
 
     /// Command and arguments to execute (after --)
+    ///
+    /// `{repo}` (or `{alias}`), `{branch}` and `{path}` are replaced with each
+    /// repo's alias, branch and worktree path before the command runs, e.g.
+    /// `-- 'echo building {alias} on {branch}'`. Values are shell-quoted, so
+    /// don't wrap placeholders in quotes yourself. Write `{{` and `}}` for
+    /// literal braces.
     #[arg(last = true, required = true)]
     pub command: Vec<String>,
 }
@@ -206,6 +456,54 @@ pub struct PruneArgs {
     /// Preview without removing
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Only prune sets whose TTL has expired
+    #[arg(long, conflicts_with = "orphaned_only")]
+    pub ttl_only: bool,
+
+    /// Only prune orphaned sets (missing directory, project or source repos)
+    #[arg(long)]
+    pub orphaned_only: bool,
+
+    /// Only consider ephemeral sets
+    #[arg(long)]
+    pub ephemeral_only: bool,
+}
+
+#[derive(Args)]
+pub struct StoreArgs {
+    #[command(subcommand)]
+    pub command: StoreCommands,
+}
+
+/// Worktree store maintenance subcommands.
+#[derive(Subcommand)]
+pub enum StoreCommands {
+    /// Rewrite store paths after moving the workspace
+    Rebase(StoreRebaseArgs),
+}
+
+#[derive(Args)]
+pub struct StoreRebaseArgs {
+    /// Old absolute path prefix (the workspace's previous location)
+    #[arg(long, value_name = "OLD")]
+    pub from: std::path::PathBuf,
+
+    /// New absolute path prefix (must exist)
+    #[arg(long, value_name = "NEW")]
+    pub to: std::path::PathBuf,
+
+    /// Preview without updating the store
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Parse `--jobs`, which must be at least 1.
+fn parse_jobs_clap(s: &str) -> std::result::Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n),
+        _ => Err(format!("'{s}' is not a number >= 1")),
+    }
 }
 
 /// Parse a human-friendly duration string for clap value_parser.
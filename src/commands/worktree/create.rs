@@ -1,7 +1,9 @@
 use anyhow::Result;
 use chrono::Utc;
 use colored::*;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::io::Write;
 
 use meta_cli::dependency_graph::{DependencyGraph, ProjectDependencies};
 use meta_git_lib::worktree::git_ops::*;
@@ -12,7 +14,22 @@ use meta_git_lib::worktree::types::{
     CreateOutput, CreateRepoEntry, StoreRepoEntry, WorktreeStoreEntry,
 };
 
-use super::cli_types::CreateArgs;
+use super::cli_types::{CreateArgs, DestroyArgs};
+use crate::error::Categorize;
+use crate::helpers::{origin_default_branch, shell_quote};
+use crate::MetaGitError;
+
+#[derive(Serialize)]
+struct CreateView {
+    #[serde(flatten)]
+    output: CreateOutput,
+    /// An existing set at the same path was torn down first (--force)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    replaced: bool,
+    /// Repos whose existing branch was moved to the start point (--reset-branch)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reset_branches: Vec<String>,
+}
 
 pub(crate) fn handle_create(
     args: CreateArgs,
@@ -53,20 +70,46 @@ pub(crate) fn handle_create(
     let from_ref_merged = args.commit_ish.or(args.from_ref);
     let from_ref = from_ref_merged.as_deref();
     let from_pr_spec = args.from_pr.as_deref();
+    let from_branch = args.from_branch.as_deref();
 
     // Belt-and-suspenders: clap enforces conflicts, but guard against programmatic construction
     if from_ref.is_some() && from_pr_spec.is_some() {
-        anyhow::bail!("Cannot specify both a commit-ish and --from-pr");
+        return Err(MetaGitError::InvalidArgument(
+            "Cannot specify both a commit-ish and --from-pr".to_string(),
+        )
+        .into());
+    }
+    if from_branch.is_some() && (from_ref.is_some() || from_pr_spec.is_some()) {
+        return Err(MetaGitError::InvalidArgument(
+            "--from-branch cannot be combined with a commit-ish or --from-pr".to_string(),
+        )
+        .into());
     }
 
     let no_deps = args.no_deps;
+    let no_root = args.no_root;
+    let quiet = args.quiet;
     let recursive = args.recursive;
+    let then = args.then.as_deref();
+    let force = args.force;
+    let current_branch = args.current_branch;
+    let new_branch_only = args.new_branch_only;
+    let reset_branch = args.reset_branch;
 
     if repo_specs.is_empty() && !use_all {
-        anyhow::bail!("Specify repos with --repo <alias> or use --all");
+        return Err(MetaGitError::InvalidArgument(
+            "Specify repos with --repo <alias> or use --all".to_string(),
+        )
+        .into());
+    }
+    if no_root && repo_specs.iter().any(|r| r.alias == ".") {
+        return Err(MetaGitError::InvalidArgument(
+            "Cannot combine --no-root with --repo .".to_string(),
+        )
+        .into());
     }
 
-    let nearest_meta_dir = require_meta_dir()?;
+    let nearest_meta_dir = require_meta_dir().categorize(MetaGitError::NotAMetaRepo)?;
     let meta_dir = if recursive {
         meta_core::config::find_root_meta_dir(&nearest_meta_dir)
     } else {
@@ -76,9 +119,10 @@ pub(crate) fn handle_create(
 
     // Check if worktree already exists
     let wt_dir = worktree_root.join(name);
-    if wt_dir.exists() {
+    let replacing = wt_dir.exists();
+    if replacing && !force {
         anyhow::bail!(
-            "Worktree '{}' already exists at {}. Use 'meta worktree remove {}' first.",
+            "Worktree '{}' already exists at {}. Use 'meta worktree remove {}' first, or --force to replace it.",
             name,
             wt_dir.display(),
             name
@@ -87,10 +131,14 @@ pub(crate) fn handle_create(
 
     // Parse .meta to get project list
     // When --all is specified, include root repo "." if it's a git repository
-    let projects = meta_git_lib::worktree::helpers::load_projects_with_root(&meta_dir, use_all)?;
+    let projects =
+        meta_git_lib::worktree::helpers::load_projects_with_root(&meta_dir, use_all && !no_root)?;
 
-    // Determine which repos to include: Vec<(alias, source_path, branch)>
+    // Determine which repos to include: Vec<(alias, source_path, branch)>,
+    // remembering why each one is in the set for the dry-run preview
+    let mut reasons: HashMap<String, Inclusion> = HashMap::new();
     let repos_to_create: Vec<(String, std::path::PathBuf, String)> = if use_all {
+        reasons.extend(projects.iter().map(|p| (p.name.clone(), Inclusion::All)));
         projects
             .iter()
             .map(|p| {
@@ -109,6 +157,7 @@ pub(crate) fn handle_create(
         // --no-deps: only include explicitly specified repos (legacy behavior)
         let mut list = Vec::new();
         for spec in repo_specs {
+            reasons.insert(spec.alias.clone(), Inclusion::Explicit);
             if spec.alias == "." {
                 list.push((
                     ".".to_string(),
@@ -127,7 +176,7 @@ pub(crate) fn handle_create(
         list
     } else {
         // Default: auto-include root repo + resolve dependencies
-        resolve_repos_with_dependencies(
+        let (list, resolved_reasons) = resolve_repos_with_dependencies(
             &meta_dir,
             &projects,
             repo_specs,
@@ -135,53 +184,244 @@ pub(crate) fn handle_create(
             branch_flag,
             verbose,
             recursive,
-        )?
+        )?;
+        reasons = resolved_reasons;
+        list
+    };
+
+    // --no-root: drop "." even when dependency resolution added it. Without a
+    // root checkout, the set dir is created as a plain directory below.
+    let repos_to_create = if no_root {
+        without_root(repos_to_create)
+    } else {
+        repos_to_create
     };
 
     // For nested aliases like "gitkb/core", ensure intermediate meta: true
     // parents ("gitkb") get worktrees too — the thin spine from root to target.
-    let repos_to_create =
+    let mut repos_to_create =
         ensure_intermediate_parents(&meta_dir, repos_to_create, name, branch_flag)?;
 
+    // --current-branch: check out whatever each source repo is on, unless the
+    // repo was given an explicit alias:branch
+    let mut current_aliases: HashSet<String> = HashSet::new();
+    if current_branch {
+        for (alias, source, branch) in repos_to_create.iter_mut() {
+            let explicit = repo_specs
+                .iter()
+                .any(|r| r.alias == *alias && r.branch.is_some());
+            if !explicit {
+                *branch = current_checkout(source)?;
+                current_aliases.insert(alias.clone());
+            }
+        }
+    }
+
+    // Resolve/apply --from-pr: get PR head branch, override branch for the matching repo, and
+    // fetch it (not under --dry-run)
+    let mut repos_to_create = repos_to_create;
+    let from_pr_info = from_pr_spec
+        .map(|spec| {
+            let sources = projects
+                .iter()
+                .filter(|p| p.name != ".")
+                .map(|p| meta_dir.join(&p.path));
+            resolve_pr_head(spec, sources)
+        })
+        .transpose()?;
+    if let Some((ref pr_repo_spec, ref pr_branch)) = from_pr_info {
+        let mut matched = false;
+        for (alias, source, branch) in repos_to_create.iter_mut() {
+            if *alias != "." && repo_matches_spec(source, pr_repo_spec) {
+                if !dry_run {
+                    if let Err(e) = git_fetch_branch(source, pr_branch) {
+                        super::warn_or_bail(
+                            strict,
+                            format!("Failed to fetch PR branch '{pr_branch}': {e}"),
+                        )?;
+                    }
+                }
+                *branch = pr_branch.clone();
+                matched = true;
+                break;
+            }
+        }
+        if !matched {
+            // The PR's repo exists in .meta but wasn't selected: add it rather
+            // than silently creating a set without the PR checked out
+            let candidates = projects
+                .iter()
+                .filter(|p| p.name != ".")
+                .map(|p| (p.name.clone(), meta_dir.join(&p.path)));
+            match find_repo_outside_set(candidates, &repos_to_create, |source| {
+                repo_matches_spec(source, pr_repo_spec)
+            }) {
+                Some((alias, _)) if strict => {
+                    anyhow::bail!(
+                        "PR repo '{alias}' ({pr_repo_spec}) is not in the worktree set. \
+                         Add it with --repo {alias} (strict mode)"
+                    );
+                }
+                Some((alias, source)) => {
+                    eprintln!(
+                        "{} Adding '{alias}' to the set: it is the --from-pr repo ({pr_repo_spec})",
+                        "note:".cyan().bold()
+                    );
+                    if !dry_run {
+                        if let Err(e) = git_fetch_branch(&source, pr_branch) {
+                            super::warn_or_bail(
+                                strict,
+                                format!("Failed to fetch PR branch '{pr_branch}': {e}"),
+                            )?;
+                        }
+                    }
+                    repos_to_create.push((alias, source, pr_branch.clone()));
+                }
+                None => {
+                    super::warn_or_bail(
+                        strict,
+                        format!(
+                            "No repo matches '{pr_repo_spec}'. PR branch '{pr_branch}' not applied."
+                        ),
+                    )?;
+                }
+            }
+        }
+    }
+
+    // --from-branch: repos that have origin/<name> check it out (tracking it
+    // when the branch is new locally, fast-forwarding it when it exists but
+    // is behind); the rest keep their per-set branch, started from
+    // origin/HEAD. --dry-run asks the remote instead of fetching.
+    let mut start_refs: HashMap<String, String> = HashMap::new();
+    if let Some(shared) = from_branch {
+        for (alias, source, branch) in repos_to_create.iter_mut() {
+            let explicit = repo_specs
+                .iter()
+                .any(|r| r.alias == *alias && r.branch.is_some());
+            if explicit {
+                continue;
+            }
+            let found = if dry_run {
+                remote_has_branch(source, shared)
+            } else {
+                fetch_origin_branch(source, shared)
+            };
+            match found {
+                Ok(true) => {
+                    *branch = shared.to_string();
+                    if !local_branch_exists(source, shared) {
+                        start_refs.insert(alias.clone(), format!("origin/{shared}"));
+                    } else if !dry_run {
+                        match fast_forward_to_origin(source, shared) {
+                            Ok(true) => {}
+                            Ok(false) => super::warn_or_bail(
+                                strict,
+                                format!(
+                                    "'{alias}' has a local {shared} that diverged from origin/{shared}; checking it out as-is"
+                                ),
+                            )?,
+                            Err(e) => super::warn_or_bail(
+                                strict,
+                                format!("Failed to fast-forward {shared} in '{alias}': {e}"),
+                            )?,
+                        }
+                    }
+                }
+                Ok(false) => {
+                    let start = origin_default_branch(source);
+                    let from = start.as_deref().unwrap_or("its current HEAD").to_string();
+                    if let Some(start) = start {
+                        start_refs.insert(alias.clone(), start);
+                    }
+                    super::warn_or_bail(
+                        strict,
+                        format!("'{alias}' has no origin/{shared}; starting from {from}"),
+                    )?
+                }
+                Err(e) => super::warn_or_bail(
+                    strict,
+                    format!("Failed to fetch origin/{shared} for '{alias}': {e}"),
+                )?,
+            }
+        }
+    }
+
+    // Branches that already exist are checked out as-is (reported as reused);
+    // --new-branch-only refuses them and --reset-branch moves them first
+    let existing: HashSet<String> = repos_to_create
+        .iter()
+        .filter(|(alias, source, branch)| {
+            !current_aliases.contains(alias) && local_branch_exists(source, branch)
+        })
+        .map(|(alias, _, _)| alias.clone())
+        .collect();
+    if new_branch_only && !existing.is_empty() {
+        let mut clashes: Vec<String> = repos_to_create
+            .iter()
+            .filter(|(alias, _, _)| existing.contains(alias))
+            .map(|(alias, _, branch)| format!("{alias} ({branch})"))
+            .collect();
+        clashes.sort();
+        anyhow::bail!(
+            "Branch already exists in: {} (--new-branch-only)",
+            clashes.join(", ")
+        );
+    }
+
+    // Everything above only reads: --dry-run stops here with the full plan
     if dry_run {
         let plan = CreateDryRunPlan {
             name,
             wt_dir: &wt_dir,
             repos_to_create: &repos_to_create,
+            reasons: &reasons,
             from_ref,
-            from_pr_spec,
+            from_pr: from_pr_spec.zip(from_pr_info.as_ref().map(|(_, b)| b.as_str())),
+            from_branch,
+            start_refs: &start_refs,
+            existing: &existing,
+            current: &current_aliases,
+            reset_branch,
             ephemeral,
             ttl_seconds,
             custom_meta: &custom_meta,
+            then,
+            replacing,
         };
-        print_create_dry_run(&plan);
+        // --json: stdout is reserved for the JSON document
+        if json {
+            write_create_dry_run(&mut std::io::stderr(), &plan);
+            println!("{}", serde_json::to_string_pretty(&dry_run_json(&plan))?);
+        } else {
+            write_create_dry_run(&mut std::io::stdout(), &plan);
+        }
         return Ok(());
     }
 
-    // Resolve/apply --from-pr: get PR head branch, override branch for the matching repo, and fetch
-    let mut repos_to_create = repos_to_create;
-    let from_pr_info = from_pr_spec.map(resolve_from_pr).transpose()?;
-    if let Some((ref pr_repo_spec, _pr_num, ref pr_branch)) = from_pr_info {
-        let mut matched = false;
-        for (alias, source, branch) in repos_to_create.iter_mut() {
-            if *alias != "." && repo_matches_spec(source, pr_repo_spec) {
-                // Fetch the PR branch
-                if let Err(e) = git_fetch_branch(source, pr_branch) {
-                    super::warn_or_bail(
-                        strict,
-                        format!("Failed to fetch PR branch '{pr_branch}': {e}"),
-                    )?;
-                }
-                *branch = pr_branch.clone();
-                matched = true;
-                break;
-            }
+    // --force: tear down the existing set only once the new one is fully resolved
+    if replacing {
+        if verbose {
+            eprintln!("Removing existing worktree '{name}' (--force)...");
         }
-        if !matched {
-            super::warn_or_bail(
-                strict,
-                format!("No repo matches '{pr_repo_spec}'. PR branch '{pr_branch}' not applied."),
-            )?;
+        let destroy_args = DestroyArgs {
+            name: name.to_string(),
+            force: true,
+            delete_branch: false,
+            quiet: true,
+        };
+        super::remove::handle_remove(destroy_args, verbose, false, strict)?;
+    }
+
+    let mut reset_branches = Vec::new();
+    if reset_branch {
+        let start = from_ref.unwrap_or("HEAD");
+        for (alias, source, branch) in &repos_to_create {
+            if existing.contains(alias) {
+                reset_branch_to(source, branch, start)
+                    .map_err(|e| anyhow::anyhow!("Could not reset '{branch}' in '{alias}': {e}"))?;
+                reset_branches.push(alias.clone());
+            }
         }
     }
 
@@ -207,12 +447,18 @@ pub(crate) fn handle_create(
             std::fs::create_dir_all(parent)?;
         }
 
-        match git_worktree_add(source, &wt_dir, branch, from_ref) {
+        match add_worktree(
+            source,
+            &wt_dir,
+            branch,
+            from_ref.or(start_refs.get(".").map(String::as_str)),
+            checkout_kind(".", &current_aliases, &existing),
+        ) {
             Ok(created_branch) => {
                 created_repos.push(CreateRepoEntry {
                     alias: ".".to_string(),
                     path: wt_dir.display().to_string(),
-                    branch: branch.clone(),
+                    branch: recorded_branch(source, branch, current_aliases.contains("."))?,
                     created_branch,
                 });
                 dot_created = true;
@@ -258,12 +504,18 @@ pub(crate) fn handle_create(
             );
         }
 
-        match git_worktree_add(source, &dest, branch, from_ref) {
+        match add_worktree(
+            source,
+            &dest,
+            branch,
+            from_ref.or(start_refs.get(alias).map(String::as_str)),
+            checkout_kind(alias, &current_aliases, &existing),
+        ) {
             Ok(created_branch) => {
                 created_repos.push(CreateRepoEntry {
                     alias: alias.clone(),
                     path: dest.display().to_string(),
-                    branch: branch.clone(),
+                    branch: recorded_branch(source, branch, current_aliases.contains(alias))?,
                     created_branch,
                 });
             }
@@ -276,12 +528,9 @@ pub(crate) fn handle_create(
         }
     }
 
-    // Ensure .worktrees/ is in .gitignore
-    let dirname = worktree_root
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(".worktrees");
-    ensure_worktrees_in_gitignore(&meta_dir, dirname, json)?;
+    // Ensure the worktree root is in .gitignore (relative to the meta repo;
+    // roots outside the repo are left alone)
+    ensure_worktree_root_ignored(&meta_dir, &worktree_root, json || quiet)?;
 
     // Add to centralized store
     let store_entry = WorktreeStoreEntry {
@@ -306,26 +555,51 @@ pub(crate) fn handle_create(
         Some(&meta_dir),
     );
 
+    // --then: one-off bootstrap command; only fails create in strict mode
+    let then_failure = match then {
+        Some(command) => run_then_command(command, &wt_dir, json)?,
+        None => None,
+    };
+
     // Output
     if json {
-        let output = CreateOutput {
-            name: name.to_string(),
-            root: wt_dir.display().to_string(),
-            repos: created_repos,
-            ephemeral,
-            ttl_seconds,
-            custom: custom_meta,
+        let output = CreateView {
+            output: CreateOutput {
+                name: name.to_string(),
+                root: wt_dir.display().to_string(),
+                repos: created_repos,
+                ephemeral,
+                ttl_seconds,
+                custom: custom_meta,
+            },
+            replaced: replacing,
+            reset_branches,
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
+    } else if !quiet {
         println!(
-            "{} Created worktree '{}' at {}",
+            "{} {} worktree '{}' at {}",
             "✓".green(),
+            if replacing { "Replaced" } else { "Created" },
             name.bold(),
             wt_dir.display()
         );
         for r in &created_repos {
-            let branch_note = if r.created_branch { " (new)" } else { "" };
+            let detached_from = repos_to_create
+                .iter()
+                .find(|(alias, _, _)| current_aliases.contains(alias) && *alias == r.alias)
+                .map(|(_, _, branch)| branch);
+            let branch_note = if let Some(from) = detached_from {
+                format!(" (detached at {from})")
+            } else if r.created_branch {
+                " (new)".to_string()
+            } else if reset_branches.contains(&r.alias) {
+                " (reset existing branch)".to_string()
+            } else if existing.contains(&r.alias) {
+                " (reused existing branch)".to_string()
+            } else {
+                String::new()
+            };
             println!("  {} -> {}{}", r.alias, r.branch, branch_note);
         }
         if ephemeral {
@@ -339,42 +613,424 @@ pub(crate) fn handle_create(
         }
     }
 
+    if let Some(failure) = then_failure {
+        super::warn_or_bail(strict, failure)?;
+    }
+
     Ok(())
 }
 
+/// The source repo's current branch, or its HEAD SHA when detached.
+fn current_checkout(source: &std::path::Path) -> Result<String> {
+    let rev_parse = |args: &[&str]| -> Result<String> {
+        let out = std::process::Command::new("git")
+            .arg("-C")
+            .arg(source)
+            .arg("rev-parse")
+            .args(args)
+            .output()?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "Could not read the current branch of {}: {}",
+                source.display(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+    match rev_parse(&["--abbrev-ref", "HEAD"])?.as_str() {
+        "HEAD" => rev_parse(&["HEAD"]),
+        branch => Ok(branch.to_string()),
+    }
+}
+
+/// The branch recorded for a new worktree. Detached (`--current-branch`)
+/// checkouts record the commit SHA: naming the branch would make later
+/// commands treat the worktree as being on it.
+fn recorded_branch(source: &std::path::Path, branch: &str, detached: bool) -> Result<String> {
+    if !detached {
+        return Ok(branch.to_string());
+    }
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{branch}^{{commit}}"))
+        .output()?;
+    if !out.status.success() {
+        anyhow::bail!("Could not resolve '{branch}' in {}", source.display());
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// How a repo's branch ends up in its worktree. Decided once per repo so
+/// `add_worktree` and the `--dry-run` preview run the same command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Checkout {
+    /// `-b`: the branch is created, from the start ref when there is one
+    NewBranch,
+    /// The branch already exists locally and is checked out as-is
+    Existing,
+    /// `--current-branch`: a detached HEAD at the source's current branch
+    /// tip (or SHA). The source already has that branch checked out, and a
+    /// second checkout of it would go stale whenever the other one commits.
+    Detached,
+}
+
+fn checkout_kind(alias: &str, current: &HashSet<String>, existing: &HashSet<String>) -> Checkout {
+    if current.contains(alias) {
+        Checkout::Detached
+    } else if existing.contains(alias) {
+        Checkout::Existing
+    } else {
+        Checkout::NewBranch
+    }
+}
+
+/// The `git worktree add` arguments for `checkout`. The start ref only
+/// applies to a new branch.
+fn worktree_add_args(
+    checkout: Checkout,
+    dest: &std::path::Path,
+    branch: &str,
+    start: Option<&str>,
+) -> Vec<String> {
+    let dest = dest.display().to_string();
+    let mut args = vec!["worktree".to_string(), "add".to_string()];
+    match checkout {
+        Checkout::NewBranch => {
+            args.extend(["-b".to_string(), branch.to_string(), dest]);
+            args.extend(start.map(str::to_string));
+        }
+        Checkout::Existing => args.extend([dest, branch.to_string()]),
+        Checkout::Detached => args.extend(["--detach".to_string(), dest, branch.to_string()]),
+    }
+    args
+}
+
+/// Run `git worktree add` for `checkout`. Returns whether a branch was
+/// created.
+fn add_worktree(
+    source: &std::path::Path,
+    dest: &std::path::Path,
+    branch: &str,
+    start: Option<&str>,
+    checkout: Checkout,
+) -> Result<bool> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(worktree_add_args(checkout, dest, branch, start))
+        .output()?;
+    if !out.status.success() {
+        return Err(MetaGitError::GitFailed(format!(
+            "git worktree add failed for {}: {}",
+            source.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(checkout == Checkout::NewBranch)
+}
+
+fn local_branch_exists(source: &std::path::Path, branch: &str) -> bool {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["show-ref", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{branch}"))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Fetch `origin/<branch>` into the source repo. `Ok(false)` when origin has
+/// no such branch.
+fn fetch_origin_branch(source: &std::path::Path, branch: &str) -> Result<bool> {
+    if !remote_has_branch(source, branch)? {
+        return Ok(false);
+    }
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["fetch", "--quiet", "origin"])
+        .arg(format!("+refs/heads/{branch}:refs/remotes/origin/{branch}"))
+        .output()?;
+    if !out.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+    Ok(true)
+}
+
+/// Move local `branch` up to `origin/<branch>` when it is strictly behind.
+/// `Ok(false)` when it has commits origin lacks, so it is left alone.
+fn fast_forward_to_origin(source: &std::path::Path, branch: &str) -> Result<bool> {
+    let upstream = format!("origin/{branch}");
+    let is_ancestor = |a: &str, b: &str| -> Result<bool> {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(source)
+            .args(["merge-base", "--is-ancestor", a, b])
+            .status()?;
+        match status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => anyhow::bail!("git merge-base failed for {a} and {b}"),
+        }
+    };
+    // Up to date, or only ahead of origin
+    if is_ancestor(&upstream, branch)? {
+        return Ok(true);
+    }
+    if !is_ancestor(branch, &upstream)? {
+        return Ok(false);
+    }
+    reset_branch_to(source, branch, &upstream)?;
+    Ok(true)
+}
+
+/// `git branch -f`: point an existing branch at `start`.
+fn reset_branch_to(source: &std::path::Path, branch: &str, start: &str) -> Result<()> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["branch", "--force", branch, start])
+        .output()?;
+    if !out.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Whether origin has `branch`, via `git ls-remote` (nothing is fetched).
+fn remote_has_branch(source: &std::path::Path, branch: &str) -> Result<bool> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["ls-remote", "--exit-code", "--heads", "origin"])
+        .arg(format!("refs/heads/{branch}"))
+        .output()?;
+    match out.status.code() {
+        Some(0) => Ok(true),
+        // --exit-code: 2 means no matching ref
+        Some(2) => Ok(false),
+        _ => anyhow::bail!("{}", String::from_utf8_lossy(&out.stderr).trim()),
+    }
+}
+
+/// Why a repo ended up in the set.
+#[derive(Debug, Clone, PartialEq)]
+enum Inclusion {
+    /// Named with --repo
+    Explicit,
+    /// Selected by --all
+    All,
+    /// The meta repo, auto-included alongside --repo
+    Root,
+    /// Transitive dependency of an explicitly requested repo
+    DependencyOf(String),
+    /// Intermediate checkout needed for a nested repo
+    ParentOf(String),
+}
+
+impl Inclusion {
+    fn kind(&self) -> &'static str {
+        match self {
+            Inclusion::Explicit => "explicit",
+            Inclusion::All => "all",
+            Inclusion::Root => "root",
+            Inclusion::DependencyOf(_) => "dependency",
+            Inclusion::ParentOf(_) => "parent",
+        }
+    }
+
+    fn required_by(&self) -> Option<&str> {
+        match self {
+            Inclusion::DependencyOf(by) | Inclusion::ParentOf(by) => Some(by),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Inclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Inclusion::Explicit => write!(f, "explicit"),
+            Inclusion::All => write!(f, "--all"),
+            Inclusion::Root => write!(f, "meta root"),
+            Inclusion::DependencyOf(by) => write!(f, "dependency of {by}"),
+            Inclusion::ParentOf(by) => write!(f, "parent of {by}"),
+        }
+    }
+}
+
+/// Look up why `alias` is in the set; repos without a recorded reason were
+/// added by `ensure_intermediate_parents` for some nested alias.
+fn inclusion_for(
+    alias: &str,
+    reasons: &HashMap<String, Inclusion>,
+    repos: &[(String, std::path::PathBuf, String)],
+) -> Inclusion {
+    if let Some(reason) = reasons.get(alias) {
+        return reason.clone();
+    }
+    let prefix = format!("{alias}/");
+    repos
+        .iter()
+        .find(|(other, _, _)| other.starts_with(&prefix))
+        .map(|(other, _, _)| Inclusion::ParentOf(other.clone()))
+        .unwrap_or(Inclusion::Explicit)
+}
+
+/// `create --dry-run --json`: the `CreateOutput` a real run would print, plus
+/// where each repo comes from and why it is in the set.
+#[derive(Serialize)]
+struct DryRunOutput {
+    dry_run: bool,
+    #[serde(flatten)]
+    output: CreateOutput,
+    plan: Vec<DryRunRepo>,
+}
+
+#[derive(Serialize)]
+struct DryRunRepo {
+    alias: String,
+    source: String,
+    reason: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_by: Option<String>,
+}
+
+fn dry_run_json(plan: &CreateDryRunPlan<'_>) -> DryRunOutput {
+    let repos = plan
+        .repos_to_create
+        .iter()
+        .map(|(alias, source, branch)| CreateRepoEntry {
+            alias: alias.clone(),
+            path: dry_run_dest(plan.wt_dir, alias).display().to_string(),
+            branch: recorded_branch(source, branch, plan.current.contains(alias))
+                .unwrap_or_else(|_| branch.clone()),
+            created_branch: checkout_kind(alias, plan.current, plan.existing)
+                == Checkout::NewBranch,
+        })
+        .collect();
+    DryRunOutput {
+        dry_run: true,
+        output: CreateOutput {
+            name: plan.name.to_string(),
+            root: plan.wt_dir.display().to_string(),
+            repos,
+            ephemeral: plan.ephemeral,
+            ttl_seconds: plan.ttl_seconds,
+            custom: plan.custom_meta.clone(),
+        },
+        plan: plan
+            .repos_to_create
+            .iter()
+            .map(|(alias, source, _)| {
+                let reason = inclusion_for(alias, plan.reasons, plan.repos_to_create);
+                DryRunRepo {
+                    alias: alias.clone(),
+                    source: source.display().to_string(),
+                    reason: reason.kind(),
+                    required_by: reason.required_by().map(str::to_string),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Where `alias` would be checked out: the set root for ".", else under it.
+fn dry_run_dest(wt_dir: &std::path::Path, alias: &str) -> std::path::PathBuf {
+    if alias == "." {
+        wt_dir.to_path_buf()
+    } else {
+        wt_dir.join(alias)
+    }
+}
+
+/// `--from-pr` as (repo spec, head branch). The forge is picked from the
+/// remote of the workspace repo the spec names: GitLab and Bitbucket are
+/// looked up by [`super::forge`], everything else by `resolve_from_pr`.
+fn resolve_pr_head(
+    spec: &str,
+    mut sources: impl Iterator<Item = std::path::PathBuf>,
+) -> Result<(String, String)> {
+    let (repo_spec, number) = super::forge::parse_pr_spec(spec)?;
+    let remote = sources
+        .find(|source| repo_matches_spec(source, repo_spec))
+        .and_then(|source| meta_git_lib::get_remote_url(&source));
+    if let Some(result) = remote.and_then(|url| super::forge::resolve_pr_branch(&url, number)) {
+        return Ok((repo_spec.to_string(), result?));
+    }
+    let (repo_spec, _number, branch) = resolve_from_pr(spec)?;
+    Ok((repo_spec, branch))
+}
+
 struct CreateDryRunPlan<'a> {
     name: &'a str,
     wt_dir: &'a std::path::Path,
     repos_to_create: &'a [(String, std::path::PathBuf, String)],
+    reasons: &'a HashMap<String, Inclusion>,
     from_ref: Option<&'a str>,
-    from_pr_spec: Option<&'a str>,
+    /// `--from-pr` spec and the head branch it resolved to
+    from_pr: Option<(&'a str, &'a str)>,
+    from_branch: Option<&'a str>,
+    /// Per-repo start points for `--from-branch` (`origin/<name>`)
+    start_refs: &'a HashMap<String, String>,
+    /// Aliases whose branch already exists in the source repo
+    existing: &'a HashSet<String>,
+    /// `--current-branch` aliases, checked out detached
+    current: &'a HashSet<String>,
+    reset_branch: bool,
     ephemeral: bool,
     ttl_seconds: Option<u64>,
     custom_meta: &'a HashMap<String, String>,
+    then: Option<&'a str>,
+    /// An existing set would be removed first (--force)
+    replacing: bool,
 }
 
-fn print_create_dry_run(plan: &CreateDryRunPlan<'_>) {
-    println!(
+fn write_create_dry_run(w: &mut dyn std::io::Write, plan: &CreateDryRunPlan<'_>) {
+    let _ = writeln!(
+        w,
         "[DRY RUN] Would create worktree set '{}' at {}",
         plan.name,
         plan.wt_dir.display()
     );
-    println!();
+    let _ = writeln!(w);
 
+    if plan.replacing {
+        let _ = writeln!(
+            w,
+            "Replaces: the existing set at this path would be removed first (--force)"
+        );
+    }
     if let Some(from_ref) = plan.from_ref {
-        println!("Start ref: {from_ref}");
+        let _ = writeln!(w, "Start ref: {from_ref}");
     }
-    if let Some(from_pr_spec) = plan.from_pr_spec {
-        println!("PR source: would resolve {from_pr_spec} and fetch the matching head branch");
+    if let Some((spec, branch)) = plan.from_pr {
+        let _ = writeln!(
+            w,
+            "PR source: {spec} (head branch '{branch}', fetched on create)"
+        );
+    }
+    if let Some(from_branch) = plan.from_branch {
+        let _ = writeln!(
+            w,
+            "Branch source: origin/{from_branch} where it exists (fetched on create), \
+             else origin/HEAD"
+        );
     }
     if plan.ephemeral {
-        println!("Ephemeral: true");
+        let _ = writeln!(w, "Ephemeral: true");
     }
     if let Some(ttl) = plan.ttl_seconds {
-        println!("TTL: {}", format_duration(ttl as i64));
+        let _ = writeln!(w, "TTL: {}", format_duration(ttl as i64));
     }
     if !plan.custom_meta.is_empty() {
-        println!(
+        let _ = writeln!(
+            w,
             "Metadata: {}",
             plan.custom_meta
                 .iter()
@@ -384,46 +1040,186 @@ fn print_create_dry_run(plan: &CreateDryRunPlan<'_>) {
         );
     }
 
-    println!("Planned repo operations:");
+    let count = plan.repos_to_create.len();
+    let _ = writeln!(
+        w,
+        "Planned repo operations ({count} repo{}):",
+        if count == 1 { "" } else { "s" }
+    );
     if plan.repos_to_create.is_empty() {
-        println!("  (none)");
+        let _ = writeln!(w, "  (none)");
     }
     for (alias, source, branch) in plan.repos_to_create {
-        let dest = if alias == "." {
-            plan.wt_dir.to_path_buf()
-        } else {
-            plan.wt_dir.join(alias)
-        };
-        let mut cmd = format!(
-            "git -C {} worktree add -b {} {}",
-            shell_quote(&source.display().to_string()),
-            shell_quote(branch),
-            shell_quote(&dest.display().to_string())
-        );
-        if let Some(from_ref) = plan.from_ref {
+        let dest = dry_run_dest(plan.wt_dir, alias);
+        let checkout = checkout_kind(alias, plan.current, plan.existing);
+        let start = plan
+            .from_ref
+            .or(plan.start_refs.get(alias).map(String::as_str));
+        let mut cmd = format!("git -C {}", shell_quote(&source.display().to_string()));
+        for arg in worktree_add_args(checkout, &dest, branch, start) {
             cmd.push(' ');
-            cmd.push_str(&shell_quote(from_ref));
+            cmd.push_str(&shell_quote(&arg));
+        }
+        if checkout == Checkout::Existing && plan.reset_branch {
+            let reset = format!(
+                "git -C {} branch --force {} {}",
+                shell_quote(&source.display().to_string()),
+                shell_quote(branch),
+                shell_quote(plan.from_ref.unwrap_or("HEAD"))
+            );
+            cmd = format!("{reset} && {cmd}");
         }
-        println!("  {alias}:");
-        println!("    source: {}", source.display());
-        println!("    dest:   {}", dest.display());
-        println!("    branch: {branch}");
-        println!("    bash:   {cmd}");
+        let reason = inclusion_for(alias, plan.reasons, plan.repos_to_create);
+        let _ = writeln!(w, "  {alias}: ({reason})");
+        let _ = writeln!(w, "    source: {}", source.display());
+        let _ = writeln!(w, "    dest:   {}", dest.display());
+        let note = match (checkout, plan.reset_branch) {
+            (Checkout::Existing, true) => " (exists, would be reset)",
+            (Checkout::Existing, false) => " (exists, would be reused)",
+            (Checkout::Detached, _) => " (detached, --current-branch)",
+            (Checkout::NewBranch, _) => "",
+        };
+        let _ = writeln!(w, "    branch: {branch}{note}");
+        let _ = writeln!(w, "    bash:   {cmd}");
     }
 
-    println!();
-    println!("No directories, branches, store entries, hooks, or .gitignore changes were written.");
+    if let Some(command) = plan.then {
+        let _ = writeln!(w, "Then run in {}: {command}", plan.wt_dir.display());
+    }
+
+    let _ = writeln!(w);
+    let _ = writeln!(
+        w,
+        "No directories, branches, store entries, hooks, or .gitignore changes were written."
+    );
 }
 
-fn shell_quote(value: &str) -> String {
-    if value
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':'))
-    {
-        value.to_string()
+/// Run the `--then` command in the set's root.
+///
+/// Returns a failure description when the command doesn't succeed. Under
+/// `--json` its output is replayed on stderr so stdout stays a single document.
+fn run_then_command(command: &str, wt_dir: &std::path::Path, json: bool) -> Result<Option<String>> {
+    if !json {
+        eprintln!("Running '{command}' in {}", wt_dir.display());
+    }
+    let mut cmd = std::process::Command::new("sh");
+    cmd.arg("-c").arg(command).current_dir(wt_dir);
+    let status = if json {
+        let out = cmd.output()?;
+        let mut stderr = std::io::stderr().lock();
+        let _ = stderr.write_all(&out.stdout);
+        let _ = stderr.write_all(&out.stderr);
+        out.status
     } else {
-        format!("'{}'", value.replace('\'', "'\\''"))
+        cmd.status()?
+    };
+
+    if status.success() {
+        if !json {
+            eprintln!("{} '{command}' succeeded", "✓".green());
+        }
+        return Ok(None);
+    }
+    let code = status
+        .code()
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "a signal".to_string());
+    Ok(Some(format!(
+        "--then command '{command}' exited with {code}"
+    )))
+}
+
+/// Compute the `.gitignore` entry for a worktree root, relative to the meta repo.
+///
+/// Returns `None` when the root lies outside `meta_dir` (nothing to ignore)
+/// or is the meta dir itself.
+fn gitignore_entry_for(
+    meta_dir: &std::path::Path,
+    worktree_root: &std::path::Path,
+) -> Option<String> {
+    // Canonicalize what exists so symlinked or `..`-laden paths compare
+    // correctly; the root itself may not have been created yet.
+    let canon = |p: &std::path::Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let meta_dir = canon(meta_dir);
+    let worktree_root = match (worktree_root.parent(), worktree_root.file_name()) {
+        (Some(parent), Some(leaf)) if !worktree_root.exists() => canon(parent).join(leaf),
+        _ => canon(worktree_root),
+    };
+
+    let rel = worktree_root.strip_prefix(&meta_dir).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!("{}/", parts.join("/")))
+}
+
+/// Append the worktree root to the meta repo's `.gitignore` if not already covered.
+///
+/// The notice goes to stderr and is skipped entirely when `silent` (--json/--quiet).
+fn ensure_worktree_root_ignored(
+    meta_dir: &std::path::Path,
+    worktree_root: &std::path::Path,
+    silent: bool,
+) -> Result<()> {
+    let Some(entry) = gitignore_entry_for(meta_dir, worktree_root) else {
+        log::debug!(
+            "Worktree root {} is outside {}, not editing .gitignore",
+            worktree_root.display(),
+            meta_dir.display()
+        );
+        return Ok(());
+    };
+
+    let gitignore = meta_dir.join(".gitignore");
+    let existing = std::fs::read_to_string(&gitignore).unwrap_or_default();
+    let bare = entry.trim_end_matches('/');
+    let already_ignored = existing.lines().map(str::trim).any(|line| {
+        let line = line.trim_start_matches('/').trim_end_matches('/');
+        line == bare
+    });
+    if already_ignored {
+        return Ok(());
     }
+
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&entry);
+    contents.push('\n');
+    std::fs::write(&gitignore, contents)?;
+
+    if !silent {
+        eprintln!("Added '{entry}' to {}", gitignore.display());
+    }
+    Ok(())
+}
+
+/// Find the first candidate `(alias, source)` accepted by `matches` that is
+/// not already part of `repos`.
+fn find_repo_outside_set(
+    candidates: impl IntoIterator<Item = (String, std::path::PathBuf)>,
+    repos: &[(String, std::path::PathBuf, String)],
+    matches: impl Fn(&std::path::Path) -> bool,
+) -> Option<(String, std::path::PathBuf)> {
+    candidates
+        .into_iter()
+        .filter(|(alias, _)| !repos.iter().any(|(a, _, _)| a == alias))
+        .find(|(_, source)| matches(source))
+}
+
+/// Drop the meta repo (".") from a resolved repo list.
+fn without_root(
+    repos: Vec<(String, std::path::PathBuf, String)>,
+) -> Vec<(String, std::path::PathBuf, String)> {
+    repos
+        .into_iter()
+        .filter(|(alias, _, _)| alias != ".")
+        .collect()
 }
 
 /// Resolve repos with automatic dependency resolution.
@@ -443,7 +1239,10 @@ fn resolve_repos_with_dependencies(
     branch_flag: Option<&str>,
     verbose: bool,
     recursive: bool,
-) -> Result<Vec<(String, std::path::PathBuf, String)>> {
+) -> Result<(
+    Vec<(String, std::path::PathBuf, String)>,
+    HashMap<String, Inclusion>,
+)> {
     // Build dependency graph — either flat (current level) or nested (full tree)
     let graph = if recursive {
         build_nested_dep_graph(meta_dir)?
@@ -454,17 +1253,19 @@ fn resolve_repos_with_dependencies(
 
     // Collect all repos to include (using HashSet for deduplication)
     let mut repos_to_include: HashSet<String> = HashSet::new();
+    let mut reasons: HashMap<String, Inclusion> = HashMap::new();
 
-    // Always include root repo "." unless user explicitly excluded it
-    // (exclusion would need to be handled separately, for now always include)
+    // Always include root repo "." here; --no-root filters it out afterwards
     if meta_dir.join(".git").exists() {
         repos_to_include.insert(".".to_string());
+        reasons.insert(".".to_string(), Inclusion::Root);
     }
 
     // For each explicitly requested repo, add it and its transitive dependencies
     for spec in repo_specs {
         if spec.alias == "." {
             repos_to_include.insert(".".to_string());
+            reasons.insert(".".to_string(), Inclusion::Explicit);
             continue;
         }
 
@@ -476,8 +1277,9 @@ fn resolve_repos_with_dependencies(
             spec.alias.clone()
         };
 
-        // Add the explicitly requested repo
+        // Add the explicitly requested repo (explicit beats "dependency of")
         repos_to_include.insert(resolved_alias.clone());
+        reasons.insert(resolved_alias.clone(), Inclusion::Explicit);
 
         // Get transitive dependencies
         let deps = graph.get_all_dependencies(&resolved_alias);
@@ -488,6 +1290,12 @@ fn resolve_repos_with_dependencies(
         );
         for dep in deps {
             repos_to_include.insert(dep.to_string());
+            let reason = reasons
+                .entry(dep.to_string())
+                .or_insert_with(|| Inclusion::DependencyOf(resolved_alias.clone()));
+            if *reason == Inclusion::Root {
+                *reason = Inclusion::DependencyOf(resolved_alias.clone());
+            }
             if verbose {
                 eprintln!("  Including '{}' (dependency of '{}')", dep, resolved_alias);
             }
@@ -535,7 +1343,7 @@ fn resolve_repos_with_dependencies(
         ));
     }
 
-    Ok(list)
+    Ok((list, reasons))
 }
 
 /// Resolve a short alias (e.g., "core") to its full graph key (e.g., "open-source/gitkb/core").
@@ -560,17 +1368,19 @@ fn resolve_alias_in_graph(graph: &DependencyGraph, alias: &str) -> Result<String
 
     match matches.len() {
         1 => Ok(matches[0].to_string()),
-        0 => anyhow::bail!(
+        0 => Err(MetaGitError::NotFound(format!(
             "Repo '{}' not found in the recursive project graph. \
              With --recursive, use the full path from root (e.g., 'open-source/gitkb/core').",
             alias
-        ),
-        _ => anyhow::bail!(
+        ))
+        .into()),
+        _ => Err(MetaGitError::InvalidArgument(format!(
             "Repo alias '{}' is ambiguous — matches: {}. \
              Use the full path to disambiguate.",
             alias,
             matches.join(", ")
-        ),
+        ))
+        .into()),
     }
 }
 
@@ -724,3 +1534,387 @@ fn ensure_intermediate_parents(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{clone_repo, git, init_repo};
+    use std::path::PathBuf;
+
+    #[test]
+    fn current_checkout_handles_branches_and_detached_heads() {
+        let tmp = tempfile::tempdir().unwrap();
+        let on_branch = tmp.path().join("on-branch");
+        let detached = tmp.path().join("detached");
+        for repo in [&on_branch, &detached] {
+            init_repo(repo);
+        }
+        git(&on_branch, &["checkout", "--quiet", "-b", "feature/x"]);
+        git(&detached, &["checkout", "--quiet", "--detach"]);
+
+        assert_eq!(current_checkout(&on_branch).unwrap(), "feature/x");
+        let sha = current_checkout(&detached).unwrap();
+        assert_eq!(sha.len(), 40);
+        assert!(sha.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // Both are checked out detached, so the source keeps sole ownership
+        // of its branch
+        let wt = tmp.path().join("wt");
+        assert!(!add_worktree(&on_branch, &wt.join("a"), "feature/x", None, true).unwrap());
+        assert!(!add_worktree(&detached, &wt.join("b"), &sha, None, true).unwrap());
+        let tip = git(&on_branch, &["rev-parse", "feature/x"]);
+        assert_eq!(current_checkout(&wt.join("a")).unwrap(), tip.trim());
+        assert_eq!(current_checkout(&wt.join("b")).unwrap(), sha);
+    }
+
+    #[test]
+    fn fetch_origin_branch_reports_missing_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        init_repo(&origin);
+        git(&origin, &["branch", "shared"]);
+        let clone = tmp.path().join("clone");
+        clone_repo(&origin, &clone);
+        git(&origin, &["branch", "later"]);
+
+        assert!(fetch_origin_branch(&clone, "later").unwrap());
+        assert!(!local_branch_exists(&clone, "later"));
+        assert!(!fetch_origin_branch(&clone, "nope").unwrap());
+    }
+
+    #[test]
+    fn stale_local_branches_fast_forward_to_origin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        init_repo(&origin);
+        git(&origin, &["branch", "shared"]);
+        let clone = tmp.path().join("clone");
+        clone_repo(&origin, &clone);
+        git(&clone, &["branch", "shared", "origin/shared"]);
+        git(&clone, &["branch", "mine", "origin/shared"]);
+        git(&origin, &["checkout", "-q", "shared"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "newer"]);
+        assert!(fetch_origin_branch(&clone, "shared").unwrap());
+
+        // Behind: moved up to origin
+        assert!(fast_forward_to_origin(&clone, "shared").unwrap());
+        assert_eq!(
+            git(&clone, &["rev-parse", "shared"]),
+            git(&clone, &["rev-parse", "origin/shared"])
+        );
+
+        // Diverged: left where it was
+        git(&clone, &["checkout", "-q", "mine"]);
+        git(&clone, &["commit", "-q", "--allow-empty", "-m", "local"]);
+        git(&clone, &["checkout", "-q", "main"]);
+        git(&clone, &["branch", "-f", "shared", "mine"]);
+        let before = git(&clone, &["rev-parse", "shared"]);
+        assert!(!fast_forward_to_origin(&clone, "shared").unwrap());
+        assert_eq!(git(&clone, &["rev-parse", "shared"]), before);
+    }
+
+    #[test]
+    fn reset_branch_moves_an_existing_branch_to_the_start() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        init_repo(repo);
+        git(repo, &["checkout", "--quiet", "-b", "feat"]);
+        git(
+            repo,
+            &["commit", "--quiet", "--allow-empty", "-m", "old work"],
+        );
+        git(repo, &["checkout", "--quiet", "-"]);
+        assert!(local_branch_exists(repo, "feat"));
+
+        reset_branch_to(repo, "feat", "HEAD").unwrap();
+        let rev = |r: &str| git(repo, &["rev-parse", r]);
+        assert_eq!(rev("feat"), rev("HEAD"));
+        assert!(reset_branch_to(repo, "feat", "no-such-ref").is_err());
+    }
+
+    #[test]
+    fn dry_run_json_matches_create_output() {
+        let repos = vec![
+            (".".to_string(), PathBuf::from("/ws"), "feat".to_string()),
+            (
+                "api".to_string(),
+                PathBuf::from("/ws/api"),
+                "feat".to_string(),
+            ),
+        ];
+        let reasons = HashMap::from([
+            (".".to_string(), Inclusion::Root),
+            (
+                "api".to_string(),
+                Inclusion::DependencyOf("web".to_string()),
+            ),
+        ]);
+        let start_refs = HashMap::new();
+        let existing = HashSet::new();
+        let current = HashSet::new();
+        let custom_meta = HashMap::new();
+        let plan = CreateDryRunPlan {
+            name: "feat",
+            wt_dir: std::path::Path::new("/wt/feat"),
+            repos_to_create: &repos,
+            reasons: &reasons,
+            from_ref: None,
+            from_pr: None,
+            from_branch: None,
+            start_refs: &start_refs,
+            existing: &existing,
+            current: &current,
+            reset_branch: false,
+            ephemeral: false,
+            ttl_seconds: Some(3600),
+            custom_meta: &custom_meta,
+            then: None,
+            replacing: false,
+        };
+        let json = serde_json::to_value(dry_run_json(&plan)).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["root"], "/wt/feat");
+        assert_eq!(json["ttl_seconds"], 3600);
+        assert_eq!(json["repos"][0]["path"], "/wt/feat");
+        assert_eq!(json["repos"][1]["path"], "/wt/feat/api");
+        assert_eq!(json["repos"][1]["branch"], "feat");
+        assert_eq!(json["plan"][1]["reason"], "dependency");
+        assert_eq!(json["plan"][1]["required_by"], "web");
+    }
+
+    #[test]
+    fn dry_run_command_follows_the_checkout_decision() {
+        let repos = vec![
+            (
+                "new".to_string(),
+                PathBuf::from("/ws/new"),
+                "feat".to_string(),
+            ),
+            (
+                "old".to_string(),
+                PathBuf::from("/ws/old"),
+                "feat".to_string(),
+            ),
+            (
+                "cur".to_string(),
+                PathBuf::from("/ws/cur"),
+                "main".to_string(),
+            ),
+        ];
+        let reasons = HashMap::new();
+        let start_refs = HashMap::from([("new".to_string(), "origin/feat".to_string())]);
+        let existing = HashSet::from(["old".to_string()]);
+        let current = HashSet::from(["cur".to_string()]);
+        let custom_meta = HashMap::new();
+        let plan = CreateDryRunPlan {
+            name: "feat",
+            wt_dir: std::path::Path::new("/wt/feat"),
+            repos_to_create: &repos,
+            reasons: &reasons,
+            from_ref: None,
+            from_pr: None,
+            from_branch: None,
+            start_refs: &start_refs,
+            existing: &existing,
+            current: &current,
+            reset_branch: false,
+            ephemeral: false,
+            ttl_seconds: None,
+            custom_meta: &custom_meta,
+            then: None,
+            replacing: false,
+        };
+        let mut out = Vec::new();
+        write_create_dry_run(&mut out, &plan);
+        let out = String::from_utf8(out).unwrap();
+        let bash: Vec<&str> = out
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("bash:"))
+            .map(str::trim)
+            .collect();
+        assert_eq!(
+            bash,
+            vec![
+                "git -C /ws/new worktree add -b feat /wt/feat/new origin/feat",
+                "git -C /ws/old worktree add /wt/feat/old feat",
+                "git -C /ws/cur worktree add --detach /wt/feat/cur main",
+            ]
+        );
+    }
+
+    #[test]
+    fn add_worktree_runs_the_previewed_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        init_repo(&repo);
+        git(&repo, &["branch", "old"]);
+
+        let created = add_worktree(
+            &repo,
+            &dir.path().join("new"),
+            "feat",
+            None,
+            Checkout::NewBranch,
+        );
+        assert!(created.unwrap());
+        let reused = add_worktree(
+            &repo,
+            &dir.path().join("old"),
+            "old",
+            None,
+            Checkout::Existing,
+        );
+        assert!(!reused.unwrap());
+        assert_eq!(
+            git(&dir.path().join("old"), &["branch", "--show-current"]).trim(),
+            "old"
+        );
+        // "main" is checked out in the source, so only a detached add works
+        let detached = add_worktree(
+            &repo,
+            &dir.path().join("cur"),
+            "main",
+            None,
+            Checkout::Detached,
+        );
+        assert!(!detached.unwrap());
+        let cur = dir.path().join("cur");
+        assert_eq!(git(&cur, &["branch", "--show-current"]).trim(), "");
+        // ...and is recorded by SHA, not as being on "main"
+        assert_eq!(
+            recorded_branch(&repo, "main", true).unwrap(),
+            git(&cur, &["rev-parse", "HEAD"]).trim()
+        );
+        assert_eq!(recorded_branch(&repo, "main", false).unwrap(), "main");
+    }
+
+    #[test]
+    fn without_root_drops_only_the_meta_repo() {
+        let repos = vec![
+            (".".to_string(), PathBuf::from("/ws"), "feat".to_string()),
+            (
+                "api".to_string(),
+                PathBuf::from("/ws/api"),
+                "feat".to_string(),
+            ),
+            (
+                "web".to_string(),
+                PathBuf::from("/ws/web"),
+                "feat".to_string(),
+            ),
+        ];
+        let aliases: Vec<String> = without_root(repos).into_iter().map(|(a, _, _)| a).collect();
+        assert_eq!(aliases, vec!["api", "web"]);
+    }
+
+    #[test]
+    fn gitignore_entry_default_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = gitignore_entry_for(dir.path(), &dir.path().join(".worktrees"));
+        assert_eq!(entry.as_deref(), Some(".worktrees/"));
+    }
+
+    #[test]
+    fn gitignore_entry_nested_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("build")).unwrap();
+        let entry = gitignore_entry_for(dir.path(), &dir.path().join("build/worktrees"));
+        assert_eq!(entry.as_deref(), Some("build/worktrees/"));
+    }
+
+    #[test]
+    fn external_root_leaves_gitignore_untouched() {
+        let meta = tempfile::tempdir().unwrap();
+        let elsewhere = tempfile::tempdir().unwrap();
+        assert_eq!(gitignore_entry_for(meta.path(), elsewhere.path()), None);
+
+        ensure_worktree_root_ignored(meta.path(), elsewhere.path(), true).unwrap();
+        assert!(!meta.path().join(".gitignore").exists());
+    }
+
+    #[test]
+    fn ensure_worktree_root_ignored_appends_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target").unwrap();
+        let root = dir.path().join("build/worktrees");
+
+        ensure_worktree_root_ignored(dir.path(), &root, true).unwrap();
+        ensure_worktree_root_ignored(dir.path(), &root, true).unwrap();
+
+        let contents = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert_eq!(contents, "target\nbuild/worktrees/\n");
+    }
+
+    #[test]
+    fn dependency_resolution_includes_root_until_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let (resolved, reasons) =
+            resolve_repos_with_dependencies(dir.path(), &[], &[], "feat", None, false, false)
+                .unwrap();
+        assert!(resolved.iter().any(|(a, _, _)| a == "."));
+        assert_eq!(reasons["."], Inclusion::Root);
+        assert!(without_root(resolved).is_empty());
+    }
+
+    #[test]
+    fn inclusion_falls_back_to_parent_of_nested_alias() {
+        let repos = vec![
+            (
+                "gitkb".to_string(),
+                PathBuf::from("/ws/gitkb"),
+                "feat".to_string(),
+            ),
+            (
+                "gitkb/core".to_string(),
+                PathBuf::from("/ws/gitkb/core"),
+                "feat".to_string(),
+            ),
+        ];
+        let reasons = HashMap::from([(
+            "gitkb/core".to_string(),
+            Inclusion::DependencyOf("api".to_string()),
+        )]);
+
+        let parent = inclusion_for("gitkb", &reasons, &repos);
+        assert_eq!(parent, Inclusion::ParentOf("gitkb/core".to_string()));
+        assert_eq!(
+            inclusion_for("gitkb/core", &reasons, &repos).to_string(),
+            "dependency of api"
+        );
+        assert_eq!(parent.kind(), "parent");
+    }
+
+    #[test]
+    fn from_pr_repo_missing_from_set_is_found() {
+        let repos = vec![(
+            "web".to_string(),
+            PathBuf::from("/ws/web"),
+            "feat".to_string(),
+        )];
+        let candidates = vec![
+            ("web".to_string(), PathBuf::from("/ws/web")),
+            ("api".to_string(), PathBuf::from("/ws/api")),
+        ];
+
+        let found = find_repo_outside_set(candidates.clone(), &repos, |p| p.ends_with("api"));
+        assert_eq!(found, Some(("api".to_string(), PathBuf::from("/ws/api"))));
+
+        // Already in the set: nothing to add
+        assert_eq!(
+            find_repo_outside_set(candidates, &repos, |p| p.ends_with("web")),
+            None
+        );
+    }
+
+    #[test]
+    fn then_command_reports_failure_without_erroring() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(run_then_command("true", dir.path(), true).unwrap(), None);
+        let failure = run_then_command("exit 3", dir.path(), true)
+            .unwrap()
+            .unwrap();
+        assert!(failure.contains("exited with 3"), "{failure}");
+    }
+}
@@ -1,27 +1,176 @@
 use anyhow::Result;
 use colored::*;
 use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
 
 use meta_git_lib::worktree::git_ops::git_diff_stat;
-use meta_git_lib::worktree::helpers::discover_and_validate_worktree;
+use meta_git_lib::worktree::helpers::{
+    discover_and_validate_worktree, lookup_nested_project, require_meta_dir,
+};
 use meta_git_lib::worktree::types::*;
 
 use super::cli_types::DiffArgs;
+use crate::error::Categorize;
+use crate::snapshot::load_named;
+use crate::MetaGitError;
+
+/// One repo of `diff --name-only --json`.
+#[derive(Serialize)]
+struct NameOnlyRepo {
+    alias: String,
+    base_ref: String,
+    files: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct NameOnlyOutput {
+    name: String,
+    base: String,
+    repos: Vec<NameOnlyRepo>,
+}
+
+/// The key a snapshot records `alias` under: its source repo's path relative
+/// to the meta root, which differs from the alias for renamed and nested
+/// projects.
+fn snapshot_key(meta_dir: &Path, alias: &str) -> String {
+    if alias == "." {
+        return alias.to_string();
+    }
+    lookup_nested_project(meta_dir, alias)
+        .ok()
+        .and_then(|(source, _)| {
+            source
+                .strip_prefix(meta_dir)
+                .ok()
+                .map(|rel| rel.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| alias.to_string())
+}
 
 pub(crate) fn handle_diff(args: DiffArgs, _verbose: bool, json: bool) -> Result<()> {
     let name = &args.name;
-    let base_ref = &args.base;
 
-    let repos = discover_and_validate_worktree(name)?;
+    let repos = discover_and_validate_worktree(name).categorize(MetaGitError::NotFound)?;
+
+    // With --against-snapshot, each repo is compared against the SHA recorded
+    // for it in the snapshot instead of a shared base branch.
+    let snapshot = match args.against_snapshot.as_deref() {
+        Some(snap_name) => {
+            let meta_dir = require_meta_dir().categorize(MetaGitError::NotAMetaRepo)?;
+            let snap = load_named(&meta_dir, snap_name)?;
+            Some((meta_dir, snap))
+        }
+        None => None,
+    };
+    let base_ref = match &snapshot {
+        Some((_, snap)) => format!("snapshot '{}'", snap.name),
+        None => args.base.clone(),
+    };
+
+    let targets: Vec<_> = repos
+        .iter()
+        .filter_map(|r| match &snapshot {
+            None => Some((r, args.base.clone())),
+            Some((meta_dir, snap)) => match snap.repos.get(&snapshot_key(meta_dir, &r.alias)) {
+                Some(state) => Some((r, state.sha.clone())),
+                None => {
+                    eprintln!(
+                        "{} '{}' is not in snapshot '{}', skipping",
+                        "warning:".yellow().bold(),
+                        r.alias,
+                        snap.name
+                    );
+                    None
+                }
+            },
+        })
+        .collect();
+
+    if args.name_only {
+        let repos: Vec<NameOnlyRepo> = targets
+            .par_iter()
+            .map(|(r, repo_base)| NameOnlyRepo {
+                alias: r.alias.clone(),
+                base_ref: repo_base.clone(),
+                files: git_diff_names(&r.path, repo_base).unwrap_or_default(),
+            })
+            .collect();
+        let changed = repos.iter().filter(|r| !r.files.is_empty()).count();
+        if json {
+            let output = NameOnlyOutput {
+                name: name.to_string(),
+                base: base_ref.clone(),
+                repos,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("{} vs {}:", name.bold(), base_ref);
+            for r in repos.iter().filter(|r| !r.files.is_empty()) {
+                println!("  {}", r.alias.bold());
+                for file in &r.files {
+                    println!("    {file}");
+                }
+            }
+            if changed == 0 {
+                println!("  No changes vs {base_ref}");
+            }
+        }
+        return exit_code_result(args.exit_code, changed, name, &base_ref);
+    }
+
+    if args.patch {
+        if json && args.output.is_none() {
+            return Err(MetaGitError::InvalidArgument(
+                "--patch --json needs --output <FILE> (stdout holds the JSON document)".to_string(),
+            )
+            .into());
+        }
+        let patches: Vec<(String, String)> = targets
+            .par_iter()
+            .map(|(r, repo_base)| {
+                let patch = git_diff_patch(&r.path, repo_base, &r.alias).unwrap_or_else(|e| {
+                    eprintln!("{} '{}': {e}", "warning:".yellow().bold(), r.alias);
+                    String::new()
+                });
+                (r.alias.clone(), patch)
+            })
+            .collect();
+        let combined = combine_patches(&patches, &base_ref);
+        let changed = patches.iter().filter(|(_, p)| !p.is_empty()).count();
+        match &args.output {
+            Some(path) => {
+                std::fs::write(path, &combined)?;
+                if json {
+                    let output = serde_json::json!({
+                        "name": name,
+                        "base": base_ref,
+                        "patch": path.display().to_string(),
+                        "repos_changed": changed,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                } else {
+                    println!(
+                        "{} Wrote patch for {changed} repo(s) to {}",
+                        "✓".green(),
+                        path.display()
+                    );
+                }
+            }
+            None => print!("{combined}"),
+        }
+        return exit_code_result(args.exit_code, changed, name, &base_ref);
+    }
 
-    let diff_entries: Vec<DiffRepoEntry> = repos
+    let diff_entries: Vec<DiffRepoEntry> = targets
         .par_iter()
-        .map(|r| {
+        .map(|(r, repo_base)| {
             let (files_changed, insertions, deletions, files) =
-                git_diff_stat(&r.path, base_ref).unwrap_or((0, 0, 0, vec![]));
+                git_diff_stat(&r.path, repo_base).unwrap_or((0, 0, 0, vec![]));
             DiffRepoEntry {
                 alias: r.alias.clone(),
-                base_ref: base_ref.to_string(),
+                base_ref: repo_base.clone(),
                 files_changed,
                 insertions,
                 deletions,
@@ -46,7 +195,7 @@ pub(crate) fn handle_diff(args: DiffArgs, _verbose: bool, json: bool) -> Result<
     if json {
         let output = DiffOutput {
             name: name.to_string(),
-            base: base_ref.to_string(),
+            base: base_ref.clone(),
             repos: diff_entries,
             totals: DiffTotals {
                 repos_changed: total_repos_changed,
@@ -87,5 +236,119 @@ pub(crate) fn handle_diff(args: DiffArgs, _verbose: bool, json: bool) -> Result<
         }
     }
 
+    exit_code_result(args.exit_code, total_repos_changed, name, &base_ref)
+}
+
+/// `--exit-code`: output is already printed; the error only sets the exit status.
+fn exit_code_result(exit_code: bool, changed: usize, name: &str, base_ref: &str) -> Result<()> {
+    if exit_code && changed > 0 {
+        anyhow::bail!("{changed} repo(s) in '{name}' differ from {base_ref}");
+    }
     Ok(())
 }
+
+/// Files that differ from `base` (`git diff --name-only`).
+fn git_diff_names(repo: &Path, base: &str) -> Result<Vec<String>> {
+    let out = git_diff(repo, &["--name-only", base])?;
+    Ok(out.lines().map(str::to_string).collect())
+}
+
+/// Raw `git diff <base>` text, with paths prefixed by the repo alias so the
+/// patch applies from the worktree root.
+fn git_diff_patch(repo: &Path, base: &str, alias: &str) -> Result<String> {
+    let prefix = if alias == "." {
+        String::new()
+    } else {
+        format!("{alias}/")
+    };
+    git_diff(
+        repo,
+        &[
+            "--no-color",
+            "--no-ext-diff",
+            &format!("--src-prefix=a/{prefix}"),
+            &format!("--dst-prefix=b/{prefix}"),
+            base,
+        ],
+    )
+}
+
+fn git_diff(repo: &Path, args: &[&str]) -> Result<String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .arg("diff")
+        .args(args)
+        .output()?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Join per-repo patches, each under a `# repo: <alias> (vs <base>)` header.
+/// `git apply` skips the header lines; repos with no changes are left out.
+fn combine_patches(patches: &[(String, String)], base_ref: &str) -> String {
+    let mut combined = String::new();
+    for (alias, patch) in patches.iter().filter(|(_, p)| !p.is_empty()) {
+        combined.push_str(&format!("# repo: {alias} (vs {base_ref})\n"));
+        combined.push_str(patch);
+        if !patch.ends_with('\n') {
+            combined.push('\n');
+        }
+    }
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn snapshot_key_uses_the_project_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(".meta"),
+            r#"{"projects":{"api":{"repo":"git@github.com:org/api.git","path":"services/api"},"web":"git@github.com:org/web.git"}}"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(tmp.path().join("services/api")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("web")).unwrap();
+
+        assert_eq!(snapshot_key(tmp.path(), "api"), "services/api");
+        assert_eq!(snapshot_key(tmp.path(), "web"), "web");
+        assert_eq!(snapshot_key(tmp.path(), "."), ".");
+    }
+
+    #[test]
+    fn combined_patch_prefixes_paths_with_the_alias() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("api");
+        init_repo(&repo);
+        std::fs::write(repo.join("lib.rs"), "one\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "base"]);
+        std::fs::write(repo.join("lib.rs"), "two\n").unwrap();
+
+        assert_eq!(git_diff_names(&repo, "HEAD").unwrap(), vec!["lib.rs"]);
+        let patch = git_diff_patch(&repo, "HEAD", "api").unwrap();
+        assert!(
+            patch.contains("--- a/api/lib.rs\n+++ b/api/lib.rs"),
+            "{patch}"
+        );
+
+        let combined = combine_patches(
+            &[
+                ("api".to_string(), patch.clone()),
+                ("web".to_string(), String::new()),
+            ],
+            "main",
+        );
+        assert!(combined.starts_with("# repo: api (vs main)\ndiff --git"));
+        assert!(!combined.contains("web"));
+    }
+}
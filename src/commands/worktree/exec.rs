@@ -1,67 +1,497 @@
 use anyhow::Result;
 use colored::*;
+use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
 
 use meta_cli::worktree::discover_worktree_repos;
 use meta_git_lib::worktree::helpers::*;
 
 use super::cli_types::{CreateArgs, DestroyArgs, ExecArgs};
+use crate::error::Categorize;
 use crate::git_env;
+use crate::helpers::shell_quote;
+use crate::MetaGitError;
 
-fn build_loop_config(
-    directories: Vec<String>,
+/// Loop settings shared by regular and ephemeral exec.
+struct ExecSettings {
     include_filters: Vec<String>,
     exclude_filters: Vec<String>,
     parallel: bool,
+    /// Concurrency cap when `parallel` (`--jobs`, else the default jobs count)
+    max_parallel: Option<usize>,
+    /// Suppress loop_lib's per-repo headers (`LoopConfig.silent`).
+    quiet: bool,
     verbose: bool,
     json: bool,
+    /// Worktree set name, reported in the `--json` document.
+    worktree: String,
+    /// Extra attempts per repo after a failure (`--retry`).
+    retry: u32,
+    continue_on_error: bool,
+    /// `--clean-env`: wrap each command in `env -i` after interpolation.
+    clean_env: bool,
+}
+
+fn build_loop_config(
+    directories: Vec<String>,
+    settings: &ExecSettings,
     env: Option<HashMap<String, String>>,
 ) -> loop_lib::LoopConfig {
     loop_lib::LoopConfig {
         directories,
         ignore: vec![],
-        include_filters: if include_filters.is_empty() {
+        include_filters: if settings.include_filters.is_empty() {
             None
         } else {
-            Some(include_filters)
+            Some(settings.include_filters.clone())
         },
-        exclude_filters: if exclude_filters.is_empty() {
+        exclude_filters: if settings.exclude_filters.is_empty() {
             None
         } else {
-            Some(exclude_filters)
+            Some(settings.exclude_filters.clone())
         },
-        verbose,
-        silent: false,
-        parallel,
+        verbose: settings.verbose,
+        silent: settings.quiet,
+        parallel: settings.parallel,
         dry_run: false,
-        json_output: json,
+        json_output: settings.json,
         add_aliases_to_global_looprc: false,
         spawn_stagger_ms: 0,
         env,
-        max_parallel: None,
+        max_parallel: settings.max_parallel,
         root_dir: None, // Worktree exec paths don't use "." convention
     }
 }
 
+/// `--jobs N` caps `--parallel`, which otherwise defaults to the CPU count
+/// (or user config `jobs`); `--jobs` alone turns parallel mode on.
+fn jobs_cap(parallel: bool, jobs: Option<usize>) -> Option<usize> {
+    if parallel || jobs.is_some() {
+        Some(jobs.unwrap_or_else(crate::user_config::default_jobs))
+    } else {
+        None
+    }
+}
+
+/// One repo to run in: its alias (for reporting), branch and directory.
+struct ExecTarget {
+    repo: String,
+    branch: String,
+    dir: String,
+}
+
+/// Placeholder names substituted per repo: `{repo}`/`{alias}`, `{branch}`, `{path}`.
+const PLACEHOLDERS: [&str; 4] = ["repo", "alias", "branch", "path"];
+
+/// Whether the command needs per-repo substitution (placeholders or `{{`/`}}` escapes).
+fn has_placeholder(command: &str) -> bool {
+    command.contains("{{")
+        || command.contains("}}")
+        || PLACEHOLDERS
+            .iter()
+            .any(|p| command.contains(&format!("{{{p}}}")))
+}
+
+/// Substitute placeholders for `target`, shell-quoting each value so paths
+/// with spaces and branch names with shell syntax stay single words. `{{`
+/// and `}}` produce literal braces; unknown `{...}` is left as written.
+fn interpolate(command: &str, target: &ExecTarget) -> String {
+    let mut out = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let value = rest
+            .strip_prefix('{')
+            .and_then(|r| r.split_once('}'))
+            .and_then(|(name, _)| match name {
+                "repo" | "alias" => Some(target.repo.as_str()),
+                "branch" => Some(target.branch.as_str()),
+                "path" => Some(target.dir.as_str()),
+                _ => None,
+            });
+        match value {
+            Some(value) => {
+                let end = rest.find('}').unwrap_or(0);
+                out.push_str(&shell_quote(value));
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Run the command through loop_lib, or through the collecting runner when
+/// `--retry`, `--json`, `--continue-on-error` or an alias placeholder is used
+/// (loop_lib can't re-run a failed repo, report per-repo results, or vary the
+/// command per repo).
+fn run_command(
+    targets: Vec<ExecTarget>,
+    settings: &ExecSettings,
+    env: HashMap<String, String>,
+    command: &str,
+) -> Result<()> {
+    if settings.retry == 0
+        && !settings.json
+        && !settings.continue_on_error
+        && !has_placeholder(command)
+    {
+        let (command, env) = prepare_command(command.to_string(), env, settings.clean_env);
+        let directories = targets.into_iter().map(|t| t.dir).collect();
+        let config = build_loop_config(directories, settings, env);
+        loop_lib::run(&config, &command)?;
+        return Ok(());
+    }
+    run_collected(targets, settings, env, command)
+}
+
+/// Per-repo entry of the `--json` document.
+#[derive(Serialize)]
+struct ExecRepoResult {
+    repo: String,
+    /// Exit code of the last attempt; `null` if killed by a signal or not spawned
+    exit_code: Option<i32>,
+    /// Wall time across all attempts
+    duration_ms: u64,
+    attempts: u32,
+}
+
+/// `meta worktree exec --json` output, printed once after every repo ran:
+///
+/// ```json
+/// {"worktree": "feat", "results": [{"repo": "api", "exit_code": 0, "duration_ms": 812, "attempts": 1}]}
+/// ```
+///
+/// Command output goes to stderr so stdout holds only this document.
+#[derive(Serialize)]
+struct ExecOutput {
+    worktree: String,
+    results: Vec<ExecRepoResult>,
+}
+
+fn run_collected(
+    targets: Vec<ExecTarget>,
+    settings: &ExecSettings,
+    env: HashMap<String, String>,
+    command: &str,
+) -> Result<()> {
+    let targets: Vec<ExecTarget> = targets
+        .into_iter()
+        .filter(|t| passes_filters(&t.dir, settings))
+        .collect();
+
+    let run_one = |target: &ExecTarget| -> ExecRepoResult {
+        // Substitute before wrapping so quoted values stay inside `sh -c`
+        let (command, env) = prepare_command(
+            interpolate(command, target),
+            env.clone(),
+            settings.clean_env,
+        );
+        let env = env.unwrap_or_default();
+        let max_attempts = settings.retry + 1;
+        let started = std::time::Instant::now();
+        let mut attempts = 0;
+        let mut exit_code = None;
+        while attempts < max_attempts && exit_code != Some(0) {
+            attempts += 1;
+            if !settings.quiet && !settings.json {
+                let attempt_note = if attempts > 1 {
+                    format!(" (attempt {attempts}/{max_attempts})")
+                } else {
+                    String::new()
+                };
+                eprintln!("{}{}", target.dir.bold(), attempt_note.dimmed());
+            }
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg(&command)
+                .current_dir(&target.dir)
+                .envs(&env);
+            exit_code = if settings.json {
+                // Keep stdout for the JSON document; replay output on stderr
+                cmd.output().ok().map(|out| {
+                    let mut stderr = std::io::stderr().lock();
+                    let _ = stderr.write_all(&out.stdout);
+                    let _ = stderr.write_all(&out.stderr);
+                    out.status.code()
+                })
+            } else {
+                cmd.status().ok().map(|s| s.code())
+            }
+            .flatten();
+        }
+        ExecRepoResult {
+            repo: target.repo.clone(),
+            exit_code,
+            duration_ms: started.elapsed().as_millis() as u64,
+            attempts,
+        }
+    };
+
+    let results: Vec<ExecRepoResult> = if settings.parallel {
+        // Each repo retries independently; nothing waits on a flaky neighbour
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(settings.max_parallel.unwrap_or(0))
+            .build()?;
+        pool.install(|| targets.par_iter().map(run_one).collect())
+    } else {
+        let mut results = Vec::new();
+        for target in &targets {
+            let result = run_one(target);
+            let failed = result.exit_code != Some(0);
+            results.push(result);
+            if failed && !settings.continue_on_error {
+                break;
+            }
+        }
+        results
+    };
+
+    let failed = results.iter().filter(|r| r.exit_code != Some(0)).count();
+    if settings.json {
+        let output = ExecOutput {
+            worktree: settings.worktree.clone(),
+            results,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if settings.retry > 0 || settings.continue_on_error {
+        eprintln!();
+        let _ = write_exec_summary(&mut std::io::stderr(), &results, settings.retry > 0);
+    }
+
+    if failed > 0 {
+        if settings.retry > 0 {
+            anyhow::bail!(
+                "Command failed in {failed} repo(s) after {} attempt(s)",
+                settings.retry + 1
+            );
+        }
+        anyhow::bail!("Command failed in {failed} of {} repo(s)", results.len());
+    }
+    Ok(())
+}
+
+/// Per-repo table printed after `--retry` / `--continue-on-error` runs:
+/// marker, repo, exit code, wall time and (with retries) attempts.
+fn write_exec_summary(
+    w: &mut dyn Write,
+    results: &[ExecRepoResult],
+    show_attempts: bool,
+) -> std::io::Result<()> {
+    let width = results.iter().map(|r| r.repo.len()).max().unwrap_or(0);
+    for r in results {
+        let marker = if r.exit_code == Some(0) {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        let exit = match r.exit_code {
+            Some(code) => format!("exit {code}"),
+            None => "no exit code".to_string(),
+        };
+        let mut line = format!(
+            "  {marker} {:<width$}  {exit:<12} {:>7.1}s",
+            r.repo,
+            r.duration_ms as f64 / 1000.0
+        );
+        if show_attempts {
+            let noun = if r.attempts == 1 {
+                "attempt"
+            } else {
+                "attempts"
+            };
+            line.push_str(&format!("  {} {noun}", r.attempts));
+        }
+        writeln!(w, "{}", line.trim_end())?;
+    }
+    let failed = results.iter().filter(|r| r.exit_code != Some(0)).count();
+    writeln!(w, "{} succeeded, {} failed", results.len() - failed, failed)
+}
+
+/// `--include`/`--exclude` matching for the collecting runner (substring of the path,
+/// as loop_lib does).
+fn passes_filters(dir: &str, settings: &ExecSettings) -> bool {
+    let included = settings.include_filters.is_empty()
+        || settings
+            .include_filters
+            .iter()
+            .any(|f| dir.contains(f.as_str()));
+    included
+        && !settings
+            .exclude_filters
+            .iter()
+            .any(|f| dir.contains(f.as_str()))
+}
+
+/// Git state files that mark an unfinished operation, with a label for each.
+const IN_PROGRESS_MARKERS: [(&str, &str); 5] = [
+    ("rebase-merge", "rebase"),
+    ("rebase-apply", "rebase"),
+    ("MERGE_HEAD", "merge"),
+    ("CHERRY_PICK_HEAD", "cherry-pick"),
+    ("REVERT_HEAD", "revert"),
+];
+
+/// Detect a rebase/merge/cherry-pick/revert left half-finished in `repo`.
+///
+/// Markers live in the per-worktree git dir, so resolve it with git rather
+/// than assuming `.git` is a directory.
+fn operation_in_progress(repo: &Path) -> Option<&'static str> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-parse", "--absolute-git-dir"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let git_dir = std::path::PathBuf::from(String::from_utf8_lossy(&out.stdout).trim());
+    IN_PROGRESS_MARKERS
+        .iter()
+        .find(|(marker, _)| git_dir.join(marker).exists())
+        .map(|(_, label)| *label)
+}
+
+/// Build the command environment: git defaults, then `--env-file`, then `--env`.
+///
+/// Later sources override earlier ones for the same key.
+fn build_exec_env(env_file: Option<&Path>, env_vars: &[String]) -> Result<HashMap<String, String>> {
+    let mut env = git_env::git_env();
+
+    if let Some(path) = env_file {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read env file {}: {e}", path.display()))?;
+        let vars = parse_env_file(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid env file {}: {e}", path.display()))?;
+        env.extend(vars);
+    }
+
+    for var in env_vars {
+        let (key, value) = split_env_assignment(var)
+            .ok_or_else(|| anyhow::anyhow!("--env value '{var}' must be KEY=VALUE"))?;
+        env.insert(key, value);
+    }
+
+    Ok(env)
+}
+
+/// Variables carried over from the parent environment under `--clean-env`.
+const CLEAN_ENV_PRESERVED: [&str; 2] = ["PATH", "HOME"];
+
+/// Produce the final command string and the env to hand to loop_lib.
+///
+/// loop_lib always inherits the parent environment, so `--clean-env` wraps
+/// the command in `env -i` with every variable passed explicitly: the
+/// preserved parent vars (`PATH`, `HOME`) followed by `env`, which already
+/// holds the git defaults and `--env`/`--env-file` values.
+fn prepare_command(
+    command: String,
+    env: HashMap<String, String>,
+    clean_env: bool,
+) -> (String, Option<HashMap<String, String>>) {
+    if !clean_env {
+        return (command, Some(env));
+    }
+
+    let mut vars: Vec<(String, String)> = CLEAN_ENV_PRESERVED
+        .iter()
+        .filter(|key| !env.contains_key(**key))
+        .filter_map(|key| std::env::var(key).ok().map(|v| (key.to_string(), v)))
+        .collect();
+    let mut explicit: Vec<(String, String)> = env.into_iter().collect();
+    explicit.sort();
+    vars.extend(explicit);
+
+    let mut wrapped = "env -i".to_string();
+    for (key, value) in &vars {
+        wrapped.push_str(&format!(" {key}={}", shell_quote(value)));
+    }
+    wrapped.push_str(&format!(" sh -c {}", shell_quote(&command)));
+    (wrapped, None)
+}
+
+/// Parse dotenv-style `KEY=VALUE` lines.
+///
+/// Blank lines and `#` comments are skipped, a leading `export ` is allowed,
+/// and values wrapped in matching single or double quotes are unquoted.
+fn parse_env_file(contents: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    for (idx, raw) in contents.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = split_env_assignment(line)
+            .ok_or_else(|| anyhow::anyhow!("line {}: expected KEY=VALUE, got '{raw}'", idx + 1))?;
+        vars.push((key, unquote(&value).to_string()));
+    }
+    Ok(vars)
+}
+
+fn split_env_assignment(s: &str) -> Option<(String, String)> {
+    let (key, value) = s.split_once('=')?;
+    let key = key.trim();
+    let valid_key = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
 /// RAII guard that removes an ephemeral worktree on drop.
 /// Ensures cleanup even if the exec command panics.
 struct EphemeralGuard {
     name: String,
     verbose: bool,
     json: bool,
+    /// Set by `--keep-on-failure` after a failed run; drop leaves the tree alone
+    keep: bool,
 }
 
 impl Drop for EphemeralGuard {
     fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
         if self.verbose {
             eprintln!("Removing ephemeral worktree '{}'...", self.name);
         }
         let destroy_args = DestroyArgs {
             name: self.name.clone(),
             force: true,
+            delete_branch: false,
+            // --json: the exec summary is the only document on stdout
+            quiet: self.json,
         };
         // Ephemeral cleanup uses strict=false to ensure best-effort cleanup
-        if let Err(e) = super::remove::handle_remove(destroy_args, self.verbose, self.json, false) {
+        if let Err(e) = super::remove::handle_remove(destroy_args, self.verbose, false, false) {
             eprintln!(
                 "{} Failed to remove ephemeral worktree '{}': {e}",
                 "warning:".yellow().bold(),
@@ -75,29 +505,64 @@ impl Drop for EphemeralGuard {
     }
 }
 
-pub(crate) fn handle_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()> {
+pub(crate) fn handle_exec(args: ExecArgs, verbose: bool, json: bool, strict: bool) -> Result<()> {
     if args.ephemeral {
+        // Freshly created from clean refs, so there is nothing in progress to check
         return handle_ephemeral_exec(args, verbose, json);
     }
 
     let name = &args.name;
-    let repos = discover_and_validate_worktree(name)?;
+    let repos = discover_and_validate_worktree(name).categorize(MetaGitError::NotFound)?;
 
-    let directories: Vec<String> = repos.iter().map(|r| r.path.display().to_string()).collect();
+    let mut targets = Vec::new();
+    let mut skipped = Vec::new();
+    for repo in &repos {
+        let operation = if args.force_in_progress {
+            None
+        } else {
+            operation_in_progress(&repo.path)
+        };
+        match operation {
+            Some(op) => {
+                super::warn_or_bail(
+                    strict,
+                    format!(
+                        "Skipping '{}': {op} in progress (use --force-in-progress to run anyway)",
+                        repo.alias
+                    ),
+                )?;
+                skipped.push(repo.alias.as_str());
+            }
+            None => targets.push(ExecTarget {
+                repo: repo.alias.clone(),
+                branch: repo.branch.clone(),
+                dir: repo.path.display().to_string(),
+            }),
+        }
+    }
+    if !skipped.is_empty() && !json {
+        eprintln!(
+            "Skipped {} repo(s) with an operation in progress: {}",
+            skipped.len(),
+            skipped.join(", ")
+        );
+    }
 
-    let command_str = args.command.join(" ");
-    let config = build_loop_config(
-        directories,
-        args.include,
-        args.exclude,
-        args.parallel,
+    let env = build_exec_env(args.env_file.as_deref(), &args.env)?;
+    let settings = ExecSettings {
+        include_filters: args.include,
+        exclude_filters: args.exclude,
+        parallel: args.parallel || args.jobs.is_some(),
+        max_parallel: jobs_cap(args.parallel, args.jobs),
+        quiet: args.quiet,
         verbose,
         json,
-        Some(git_env::git_env()),
-    );
-
-    loop_lib::run(&config, &command_str)?;
-    Ok(())
+        worktree: name.clone(),
+        retry: args.retry,
+        continue_on_error: args.continue_on_error,
+        clean_env: args.clean_env,
+    };
+    run_command(targets, &settings, env, &args.command.join(" "))
 }
 
 fn handle_ephemeral_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()> {
@@ -106,13 +571,29 @@ fn handle_ephemeral_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()
 
     let cmd_parts = args.command;
     if cmd_parts.is_empty() {
-        anyhow::bail!("No command specified after --");
+        return Err(
+            MetaGitError::InvalidArgument("No command specified after --".to_string()).into(),
+        );
     }
 
-    // Extract filters and parallel flag before moving remaining args into CreateArgs
-    let include_filters = args.include;
-    let exclude_filters = args.exclude;
-    let parallel = args.parallel;
+    // Resolve env before creating anything so a bad --env-file fails fast
+    let env = build_exec_env(args.env_file.as_deref(), &args.env)?;
+
+    // Extract loop settings before moving remaining args into CreateArgs
+    let keep_on_failure = args.keep_on_failure;
+    let settings = ExecSettings {
+        include_filters: args.include,
+        exclude_filters: args.exclude,
+        parallel: args.parallel || args.jobs.is_some(),
+        max_parallel: jobs_cap(args.parallel, args.jobs),
+        quiet: args.quiet,
+        verbose,
+        json,
+        worktree: name.clone(),
+        retry: args.retry,
+        continue_on_error: args.continue_on_error,
+        clean_env: args.clean_env,
+    };
 
     // Build CreateArgs from the exec args
     let create_args = CreateArgs {
@@ -124,25 +605,35 @@ fn handle_ephemeral_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()
         dry_run: false,
         from_ref: args.from_ref,
         from_pr: args.from_pr,
+        from_branch: None,
         ephemeral: true,
         ttl: None,
         custom_meta: args.custom_meta,
         strict: false,  // Exec creates ephemeral worktrees, don't fail on skipped repos
         no_deps: false, // Include dependencies by default
+        no_root: false,
+        quiet: settings.quiet || json,
         recursive: false, // Ephemeral exec uses current meta level
+        then: None,
+        force: false,
+        current_branch: false,
+        new_branch_only: false,
+        reset_branch: false,
     };
 
     if verbose {
         eprintln!("Creating ephemeral worktree '{name}'...");
     }
     // Ephemeral worktrees use global_strict=false to ensure creation succeeds
-    super::create::handle_create(create_args, verbose, json, false)?;
+    // --json: keep create's own output off stdout; the exec summary is the document
+    super::create::handle_create(create_args, verbose, false, false)?;
 
     // Guard ensures cleanup even on panic
-    let guard = EphemeralGuard {
+    let mut guard = EphemeralGuard {
         name: name.clone(),
         verbose,
         json,
+        keep: false,
     };
 
     // Resolve worktree path for exec
@@ -152,20 +643,26 @@ fn handle_ephemeral_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()
 
     // Run the command
     let repos = discover_worktree_repos(&wt_dir)?;
-    let directories: Vec<String> = repos.iter().map(|r| r.path.display().to_string()).collect();
+    let targets: Vec<ExecTarget> = repos
+        .iter()
+        .map(|r| ExecTarget {
+            repo: r.alias.clone(),
+            branch: r.branch.clone(),
+            dir: r.path.display().to_string(),
+        })
+        .collect();
 
-    let command_str = cmd_parts.join(" ");
-    let config = build_loop_config(
-        directories,
-        include_filters,
-        exclude_filters,
-        parallel,
-        verbose,
-        json,
-        Some(git_env::git_env()),
-    );
+    let exec_result = run_command(targets, &settings, env, &cmd_parts.join(" "));
 
-    let exec_result = loop_lib::run(&config, &command_str);
+    if keep_on_failure && exec_result.is_err() {
+        guard.keep = true;
+        eprintln!(
+            "{} Command failed; keeping ephemeral worktree '{name}' at {}",
+            "note:".cyan().bold(),
+            wt_dir.display()
+        );
+        eprintln!("  Run 'meta worktree remove {name} --force' when done inspecting it.");
+    }
 
     // Explicitly drop guard to trigger cleanup before propagating result
     drop(guard);
@@ -174,3 +671,255 @@ fn handle_ephemeral_exec(args: ExecArgs, verbose: bool, json: bool) -> Result<()
     exec_result?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let target = ExecTarget {
+            repo: "api".to_string(),
+            branch: "feat/x".to_string(),
+            dir: "/wt/feat/api".to_string(),
+        };
+        assert!(!has_placeholder("cargo build"));
+        assert!(!has_placeholder("echo ${HOME}"));
+        assert!(has_placeholder("echo {alias}"));
+        assert!(has_placeholder("echo {{literal}}"));
+        assert_eq!(
+            interpolate("echo {repo} > /tmp/{alias}.log", &target),
+            "echo api > /tmp/api.log"
+        );
+        assert_eq!(
+            interpolate("git tag {branch}-done && ls {path}", &target),
+            "git tag feat/x-done && ls /wt/feat/api"
+        );
+        let risky = ExecTarget {
+            repo: "api".to_string(),
+            branch: "x;$(touch pwned)".to_string(),
+            dir: "/wt/my feat/api".to_string(),
+        };
+        assert_eq!(
+            interpolate("cd {path} && echo {branch}", &risky),
+            "cd '/wt/my feat/api' && echo 'x;$(touch pwned)'"
+        );
+        // Escaped and unknown braces stay literal
+        assert_eq!(
+            interpolate("echo {{repo}} ${HOME} {nope} }", &target),
+            "echo {repo} ${HOME} {nope} }"
+        );
+    }
+
+    #[test]
+    fn parse_env_file_skips_comments_and_blanks() {
+        let vars = parse_env_file(
+            "# test config\n\nRUST_LOG=debug\nexport DB_URL=\"postgres://localhost/db\"\nEMPTY=\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("DB_URL".to_string(), "postgres://localhost/db".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_env_file_rejects_malformed_lines() {
+        let err = parse_env_file("OK=1\nnot a var\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+        assert!(parse_env_file("1BAD=x").is_err());
+    }
+
+    #[test]
+    fn prepare_command_passes_through_without_clean_env() {
+        let env = HashMap::from([("A".to_string(), "1".to_string())]);
+        let (cmd, env) = prepare_command("cargo test".to_string(), env, false);
+        assert_eq!(cmd, "cargo test");
+        assert_eq!(env.unwrap()["A"], "1");
+    }
+
+    #[test]
+    fn prepare_command_clean_env_wraps_with_explicit_vars() {
+        let env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("HOME".to_string(), "/home/me".to_string()),
+            ("MSG".to_string(), "it's".to_string()),
+        ]);
+        let (cmd, env) = prepare_command("echo $MSG".to_string(), env, true);
+        assert!(env.is_none());
+        assert_eq!(
+            cmd,
+            "env -i HOME=/home/me MSG='it'\\''s' PATH=/usr/bin sh -c 'echo $MSG'"
+        );
+    }
+
+    #[test]
+    fn retry_runner_reports_attempts_until_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = ExecSettings {
+            include_filters: vec![],
+            exclude_filters: vec![],
+            parallel: false,
+            max_parallel: None,
+            quiet: true,
+            verbose: false,
+            json: true,
+            worktree: "feat".to_string(),
+            retry: 3,
+            continue_on_error: false,
+            clean_env: false,
+        };
+        // Fails on the first two attempts, then succeeds
+        let command =
+            "n=$(cat count 2>/dev/null || echo 0); n=$((n+1)); echo $n > count; [ $n -ge 3 ]";
+        let target = || ExecTarget {
+            repo: "api".to_string(),
+            branch: "feat".to_string(),
+            dir: dir.path().display().to_string(),
+        };
+        run_collected(vec![target()], &settings, HashMap::new(), command).unwrap();
+        let count = std::fs::read_to_string(dir.path().join("count")).unwrap();
+        assert_eq!(count.trim(), "3");
+
+        let flaky = ExecSettings {
+            retry: 1,
+            ..settings
+        };
+        std::fs::remove_file(dir.path().join("count")).unwrap();
+        assert!(run_collected(vec![target()], &flaky, HashMap::new(), command).is_err());
+    }
+
+    #[test]
+    fn continue_on_error_runs_every_repo_and_still_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let targets = || {
+            ["api", "web"]
+                .iter()
+                .map(|repo| {
+                    let dir = tmp.path().join(repo);
+                    std::fs::create_dir_all(&dir).unwrap();
+                    ExecTarget {
+                        repo: repo.to_string(),
+                        branch: "feat".to_string(),
+                        dir: dir.display().to_string(),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        std::fs::create_dir_all(tmp.path().join("web")).unwrap();
+        std::fs::write(tmp.path().join("web/ok"), "").unwrap();
+        let settings = ExecSettings {
+            include_filters: vec![],
+            exclude_filters: vec![],
+            parallel: false,
+            max_parallel: None,
+            quiet: true,
+            verbose: false,
+            json: false,
+            worktree: "feat".to_string(),
+            retry: 0,
+            continue_on_error: false,
+            clean_env: false,
+        };
+        // "api" fails; "web" only runs when we keep going
+        let command = "touch ran; [ -f ok ]";
+        assert!(run_collected(targets(), &settings, HashMap::new(), command).is_err());
+        assert!(!tmp.path().join("web/ran").exists());
+
+        let keep_going = ExecSettings {
+            continue_on_error: true,
+            ..settings
+        };
+        let err = run_collected(targets(), &keep_going, HashMap::new(), command).unwrap_err();
+        assert_eq!(err.to_string(), "Command failed in 1 of 2 repo(s)");
+        assert!(tmp.path().join("web/ran").exists());
+    }
+
+    #[test]
+    fn clean_env_keeps_interpolated_values_quoted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("my feat");
+        std::fs::create_dir_all(&dir).unwrap();
+        let settings = ExecSettings {
+            include_filters: vec![],
+            exclude_filters: vec![],
+            parallel: false,
+            max_parallel: None,
+            quiet: true,
+            verbose: false,
+            json: true,
+            worktree: "feat".to_string(),
+            retry: 0,
+            continue_on_error: false,
+            clean_env: true,
+        };
+        let target = ExecTarget {
+            repo: "api".to_string(),
+            branch: "x;$(touch pwned)".to_string(),
+            dir: dir.display().to_string(),
+        };
+        let env = HashMap::from([("MSG".to_string(), "{path}".to_string())]);
+        let command = "echo {branch} > {path}/out; echo \"$MSG\" >> {path}/out";
+        run_collected(vec![target], &settings, env, command).unwrap();
+        let out = std::fs::read_to_string(dir.join("out")).unwrap();
+        assert_eq!(out, "x;$(touch pwned)\n{path}\n");
+        assert!(!dir.join("pwned").exists());
+    }
+
+    #[test]
+    fn exec_summary_lists_each_repo_and_totals() {
+        let result = |repo: &str, exit_code| ExecRepoResult {
+            repo: repo.to_string(),
+            exit_code,
+            duration_ms: 1500,
+            attempts: 1,
+        };
+        let mut out = Vec::new();
+        write_exec_summary(
+            &mut out,
+            &[result("api", Some(0)), result("frontend", Some(101))],
+            false,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("api       exit 0"), "{out}");
+        assert!(out.contains("frontend  exit 101"), "{out}");
+        assert!(out.contains("1.5s"), "{out}");
+        assert!(out.ends_with("1 succeeded, 1 failed\n"), "{out}");
+    }
+
+    #[test]
+    fn operation_in_progress_detects_merge_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = Command::new("git")
+            .args(["init", "--quiet"])
+            .arg(dir.path())
+            .status()
+            .unwrap()
+            .success();
+        assert!(ok);
+        assert_eq!(operation_in_progress(dir.path()), None);
+
+        std::fs::write(dir.path().join(".git/MERGE_HEAD"), "0000\n").unwrap();
+        assert_eq!(operation_in_progress(dir.path()), Some("merge"));
+
+        std::fs::create_dir(dir.path().join(".git/rebase-merge")).unwrap();
+        assert_eq!(operation_in_progress(dir.path()), Some("rebase"));
+    }
+
+    #[test]
+    fn env_flags_override_env_file_and_git_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        std::fs::write(&path, "GIT_PAGER=less\nMODE=file\n").unwrap();
+
+        let env = build_exec_env(Some(&path), &["MODE=flag".to_string()]).unwrap();
+        assert_eq!(env["GIT_PAGER"], "less");
+        assert_eq!(env["MODE"], "flag");
+        assert_eq!(env["GIT_TERMINAL_PROMPT"], "0");
+    }
+}
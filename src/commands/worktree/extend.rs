@@ -0,0 +1,114 @@
+use anyhow::Result;
+use chrono::Utc;
+use colored::*;
+use serde::Serialize;
+
+use meta_git_lib::worktree::helpers::*;
+use meta_git_lib::worktree::store::{entry_ttl_remaining, store_list};
+
+use super::cli_types::ExtendArgs;
+use crate::MetaGitError;
+
+#[derive(Serialize)]
+struct ExtendOutput {
+    name: String,
+    ephemeral: bool,
+    ttl_seconds: Option<u64>,
+    ttl_remaining_seconds: Option<i64>,
+}
+
+/// Give a set a fresh TTL, or drop it with `--persistent`.
+///
+/// The store keeps `created_at` as the real creation time, so the new TTL is
+/// stored as the set's age plus the requested duration: expiry lands that
+/// long from now, and `list` still shows the true age.
+pub(crate) fn handle_extend(
+    args: ExtendArgs,
+    _verbose: bool,
+    json: bool,
+    strict: bool,
+) -> Result<()> {
+    let name = &args.name;
+    validate_worktree_name(name)?;
+
+    let meta_dir = find_meta_dir();
+    let wt_dir = resolve_worktree_root(meta_dir.as_deref())?.join(name);
+    let store = store_list()?;
+    let Some(entry) = store.worktrees.get(&wt_dir.to_string_lossy().to_string()) else {
+        return Err(MetaGitError::NotFound(format!(
+            "Worktree '{}' has no store entry at {}",
+            name,
+            wt_dir.display()
+        ))
+        .into());
+    };
+
+    let now = Utc::now().timestamp();
+    let (ephemeral, ttl_seconds) = if args.persistent {
+        (false, None)
+    } else {
+        let duration = args.ttl.unwrap_or(0);
+        (
+            entry.ephemeral,
+            Some(extended_ttl(&entry.created_at, now, duration)),
+        )
+    };
+    let mut updated = super::store::copy_entry(entry);
+    updated.ephemeral = ephemeral;
+    updated.ttl_seconds = ttl_seconds;
+    let remaining = entry_ttl_remaining(&updated, now);
+    super::warn_store_error(
+        super::store::store_replace(&wt_dir, &wt_dir, updated),
+        strict,
+    )?;
+
+    if json {
+        let output = ExtendOutput {
+            name: name.to_string(),
+            ephemeral,
+            ttl_seconds,
+            ttl_remaining_seconds: remaining,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match remaining {
+            Some(ttl) => println!(
+                "{} '{}' now expires in {}",
+                "✓".green(),
+                name.bold(),
+                format_duration(ttl)
+            ),
+            None => println!(
+                "{} '{}' is now persistent (no TTL)",
+                "✓".green(),
+                name.bold()
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// TTL, counted from `created_at`, that runs out `duration` seconds after `now`.
+fn extended_ttl(created_at: &str, now: i64, duration: u64) -> u64 {
+    let age = chrono::DateTime::parse_from_rfc3339(created_at)
+        .map(|created| (now - created.timestamp()).max(0) as u64)
+        .unwrap_or(0);
+    age + duration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_ttl_counts_from_creation() {
+        let created = "2024-01-01T00:00:00+00:00";
+        let now = chrono::DateTime::parse_from_rfc3339(created)
+            .unwrap()
+            .timestamp()
+            + 7200;
+        assert_eq!(extended_ttl(created, now, 3600), 7200 + 3600);
+        // An unreadable timestamp counts as created now
+        assert_eq!(extended_ttl("garbage", now, 60), 60);
+    }
+}
@@ -0,0 +1,297 @@
+//! `--from-pr` on GitLab and Bitbucket: look up a merge/pull request's head
+//! branch.
+//!
+//! The forge is picked from the host of the remote of the repo the spec names.
+//! GitHub (and any host we don't recognize) stays with `resolve_from_pr`.
+//! GitLab goes through the `glab` CLI when it's installed, else the REST API
+//! (`GITLAB_TOKEN` for private projects). Bitbucket Cloud and Server go
+//! through their REST APIs (`BITBUCKET_TOKEN`, or `BITBUCKET_USERNAME` plus
+//! `BITBUCKET_APP_PASSWORD`). API calls shell out to `curl`, the same way
+//! everything else here shells out to `git`.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::url_rewrite::{remote_host, remote_path, resolve_insteadof};
+use crate::MetaGitError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Forge {
+    GitHub,
+    GitLab,
+    /// bitbucket.org
+    BitbucketCloud,
+    /// Self-hosted Bitbucket Server / Data Center
+    BitbucketServer,
+}
+
+fn forge_for_host(host: &str) -> Forge {
+    if host.contains("gitlab") {
+        Forge::GitLab
+    } else if host == "bitbucket.org" {
+        Forge::BitbucketCloud
+    } else if host.contains("bitbucket") {
+        Forge::BitbucketServer
+    } else {
+        Forge::GitHub
+    }
+}
+
+/// Split `owner/repo#N` (or GitLab's `group/repo!N`) into the repo spec and N.
+pub(super) fn parse_pr_spec(spec: &str) -> Result<(&str, u64)> {
+    let (repo, number) = spec
+        .rsplit_once('#')
+        .or_else(|| spec.rsplit_once('!'))
+        .with_context(|| format!("Invalid --from-pr '{spec}': expected OWNER/REPO#N"))?;
+    if !repo.contains('/') {
+        return Err(MetaGitError::InvalidArgument(format!(
+            "Invalid --from-pr '{spec}': expected OWNER/REPO#N"
+        ))
+        .into());
+    }
+    let number = number
+        .parse()
+        .with_context(|| format!("Invalid --from-pr '{spec}': '{number}' is not a PR number"))?;
+    Ok((repo, number))
+}
+
+/// Head branch of PR `number` when `remote_url` is on GitLab or Bitbucket;
+/// `None` for GitHub and unknown hosts, which `resolve_from_pr` handles.
+pub(super) fn resolve_pr_branch(remote_url: &str, number: u64) -> Option<Result<String>> {
+    let url = resolve_insteadof(remote_url);
+    let (host, path) = remote_host(&url).zip(remote_path(&url))?;
+    let result = match forge_for_host(host) {
+        Forge::GitHub => return None,
+        Forge::GitLab => gitlab_mr_branch(host, path, number),
+        forge @ (Forge::BitbucketCloud | Forge::BitbucketServer) => {
+            bitbucket_pr_branch(forge, host, path, number)
+        }
+    };
+    Some(result)
+}
+
+fn gitlab_mr_branch(host: &str, path: &str, number: u64) -> Result<String> {
+    // glab does auth (and self-hosted hosts) for us when it's set up
+    let glab = Command::new("glab")
+        .args(["mr", "view", &number.to_string(), "--repo", path])
+        .args(["--output", "json"])
+        .env("GITLAB_HOST", host)
+        .output();
+    if let Ok(out) = glab {
+        if out.status.success() {
+            if let Some(branch) = serde_json::from_slice(&out.stdout)
+                .ok()
+                .and_then(|json| head_branch(Forge::GitLab, &json))
+            {
+                return Ok(branch);
+            }
+        }
+    }
+
+    let url = format!(
+        "https://{host}/api/v4/projects/{}/merge_requests/{number}",
+        path.replace('/', "%2F")
+    );
+    let token = std::env::var("GITLAB_TOKEN").ok();
+    let headers: Vec<String> = token
+        .iter()
+        .map(|t| format!("PRIVATE-TOKEN: {t}"))
+        .collect();
+    let json = curl_json(&url, &headers, None).map_err(|e| {
+        let hint = if installed("glab") || token.is_some() {
+            ""
+        } else {
+            " (install the glab CLI or set GITLAB_TOKEN for private projects)"
+        };
+        anyhow::anyhow!("Failed to look up merge request {path}!{number} on {host}: {e}{hint}")
+    })?;
+    head_branch(Forge::GitLab, &json)
+        .with_context(|| format!("Merge request {path}!{number} has no source branch"))
+}
+
+fn bitbucket_pr_branch(forge: Forge, host: &str, path: &str, number: u64) -> Result<String> {
+    let url = match forge {
+        Forge::BitbucketCloud => {
+            format!("https://api.bitbucket.org/2.0/repositories/{path}/pullrequests/{number}")
+        }
+        _ => {
+            // Server clone paths are `scm/<project>/<repo>`
+            let path = path.strip_prefix("scm/").unwrap_or(path);
+            let Some((project, repo)) = path.split_once('/') else {
+                bail!("Can't tell the Bitbucket project of '{path}'");
+            };
+            format!(
+                "https://{host}/rest/api/1.0/projects/{project}/repos/{repo}/pull-requests/{number}"
+            )
+        }
+    };
+
+    let token = std::env::var("BITBUCKET_TOKEN").ok();
+    let user = match (
+        std::env::var("BITBUCKET_USERNAME"),
+        std::env::var("BITBUCKET_APP_PASSWORD"),
+    ) {
+        (Ok(user), Ok(password)) if token.is_none() => Some(format!("{user}:{password}")),
+        _ => None,
+    };
+    let headers: Vec<String> = token
+        .iter()
+        .map(|t| format!("Authorization: Bearer {t}"))
+        .collect();
+    let json = curl_json(&url, &headers, user.as_deref()).map_err(|e| {
+        let hint = if token.is_some() || user.is_some() {
+            ""
+        } else {
+            " (set BITBUCKET_TOKEN, or BITBUCKET_USERNAME and BITBUCKET_APP_PASSWORD, for private repos)"
+        };
+        anyhow::anyhow!("Failed to look up pull request {path}#{number} on {host}: {e}{hint}")
+    })?;
+    head_branch(forge, &json)
+        .with_context(|| format!("Pull request {path}#{number} has no source branch"))
+}
+
+/// The head branch field of each forge's PR/MR JSON.
+fn head_branch(forge: Forge, json: &Value) -> Option<String> {
+    let branch = match forge {
+        Forge::GitLab => json.get("source_branch"),
+        Forge::BitbucketCloud => json.pointer("/source/branch/name"),
+        Forge::BitbucketServer => json.pointer("/fromRef/displayId"),
+        Forge::GitHub => return None,
+    }?;
+    branch
+        .as_str()
+        .filter(|b| !b.is_empty())
+        .map(str::to_string)
+}
+
+/// Headers and credentials go to curl as a config on stdin (`-K -`) rather
+/// than argv, where any local user could read them with `ps`.
+fn curl_json(url: &str, headers: &[String], user: Option<&str>) -> Result<Value, String> {
+    let mut config = String::new();
+    for header in headers {
+        config.push_str(&curl_config_line("header", header));
+    }
+    if let Some(user) = user {
+        config.push_str(&curl_config_line("user", user));
+    }
+
+    let mut child = Command::new("curl")
+        .args(["-sSfL", "-H", "Accept: application/json", "-K", "-"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "curl is not installed".to_string(),
+            _ => e.to_string(),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let out = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    serde_json::from_slice(&out.stdout).map_err(|e| format!("unexpected response: {e}"))
+}
+
+/// One `key = "value"` line of a curl config file, escaped so the value
+/// can't end the string or start another option.
+fn curl_config_line(key: &str, value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    format!("{key} = \"{escaped}\"\n")
+}
+
+fn installed(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pr_specs_parse_with_hash_or_bang() {
+        assert_eq!(parse_pr_spec("org/api#42").unwrap(), ("org/api", 42));
+        assert_eq!(
+            parse_pr_spec("group/sub/api!7").unwrap(),
+            ("group/sub/api", 7)
+        );
+        assert!(parse_pr_spec("api#42").is_err());
+        assert!(parse_pr_spec("org/api#x").is_err());
+        assert!(parse_pr_spec("org/api").is_err());
+    }
+
+    #[test]
+    fn forge_is_picked_from_the_host() {
+        assert_eq!(forge_for_host("github.com"), Forge::GitHub);
+        assert_eq!(forge_for_host("git.example.com"), Forge::GitHub);
+        assert_eq!(forge_for_host("gitlab.com"), Forge::GitLab);
+        assert_eq!(forge_for_host("gitlab.corp.example"), Forge::GitLab);
+        assert_eq!(forge_for_host("bitbucket.org"), Forge::BitbucketCloud);
+        assert_eq!(
+            forge_for_host("bitbucket.corp.example"),
+            Forge::BitbucketServer
+        );
+    }
+
+    #[test]
+    fn curl_config_lines_escape_their_values() {
+        assert_eq!(
+            curl_config_line("header", "PRIVATE-TOKEN: abc"),
+            "header = \"PRIVATE-TOKEN: abc\"\n"
+        );
+        assert_eq!(
+            curl_config_line("user", "me:p\"a\\ss\nurl = x"),
+            "user = \"me:p\\\"a\\\\ss\\nurl = x\"\n"
+        );
+    }
+
+    #[test]
+    fn head_branch_reads_each_forge_shape() {
+        assert_eq!(
+            head_branch(Forge::GitLab, &json!({"source_branch": "fix/login"})).as_deref(),
+            Some("fix/login")
+        );
+        assert_eq!(
+            head_branch(
+                Forge::BitbucketCloud,
+                &json!({"source": {"branch": {"name": "feature"}}})
+            )
+            .as_deref(),
+            Some("feature")
+        );
+        assert_eq!(
+            head_branch(
+                Forge::BitbucketServer,
+                &json!({"fromRef": {"id": "refs/heads/topic", "displayId": "topic"}})
+            )
+            .as_deref(),
+            Some("topic")
+        );
+        assert_eq!(
+            head_branch(Forge::GitLab, &json!({"source_branch": ""})),
+            None
+        );
+        assert_eq!(head_branch(Forge::GitLab, &json!({})), None);
+    }
+}
@@ -2,6 +2,10 @@ use anyhow::Result;
 use chrono::Utc;
 use colored::*;
 use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 use meta_cli::worktree::discover_worktree_repos;
 use meta_git_lib::worktree::git_ops::git_status_summary;
@@ -9,9 +13,83 @@ use meta_git_lib::worktree::helpers::*;
 use meta_git_lib::worktree::store::{entry_ttl_remaining, store_list};
 use meta_git_lib::worktree::types::*;
 
-use super::cli_types::ListArgs;
+use super::cli_types::{ListArgs, ListSort};
 
-pub(crate) fn handle_list(_args: ListArgs, _verbose: bool, json: bool) -> Result<()> {
+/// JSON shape for `list`: `ListOutput` with per-set extras.
+#[derive(Serialize)]
+struct ListView {
+    worktrees: Vec<ListEntryView>,
+}
+
+#[derive(Serialize)]
+struct ListEntryView {
+    #[serde(flatten)]
+    entry: ListEntry,
+    /// Only reported with --verify
+    #[serde(skip_serializing_if = "Option::is_none")]
+    healthy: Option<bool>,
+    /// Repos git could not read (--verify only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    broken_repos: Vec<String>,
+    /// Meta repo that created the set, from the store
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    /// RFC3339 creation time, from the store
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    /// Seconds since `created_at` (human output only)
+    #[serde(skip)]
+    age_seconds: Option<i64>,
+}
+
+/// JSON shape for `list --group-by-project`.
+#[derive(Serialize)]
+struct GroupedListView {
+    projects: Vec<ProjectGroup>,
+}
+
+#[derive(Serialize)]
+struct ProjectGroup {
+    /// `None` for sets with no store entry
+    project: Option<String>,
+    worktrees: Vec<ListEntryView>,
+}
+
+/// Bucket sets by project, keeping name order within each group.
+/// Sets without a store entry sort last.
+fn group_by_project(entries: Vec<ListEntryView>) -> Vec<ProjectGroup> {
+    let mut groups: BTreeMap<(bool, String), Vec<ListEntryView>> = BTreeMap::new();
+    for view in entries {
+        let key = match &view.project {
+            Some(p) => (false, p.clone()),
+            None => (true, String::new()),
+        };
+        groups.entry(key).or_default().push(view);
+    }
+    groups
+        .into_iter()
+        .map(|((unknown, project), worktrees)| ProjectGroup {
+            project: (!unknown).then_some(project),
+            worktrees,
+        })
+        .collect()
+}
+
+/// Cheap check that git can read a worktree: resolves HEAD via its gitdir link.
+fn repo_is_healthy(path: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+pub(crate) fn handle_list(args: ListArgs, _verbose: bool, json: bool) -> Result<()> {
+    let verify = args.verify;
     let meta_dir = find_meta_dir();
     let worktree_root = resolve_worktree_root(meta_dir.as_deref())?;
 
@@ -19,7 +97,7 @@ pub(crate) fn handle_list(_args: ListArgs, _verbose: bool, json: bool) -> Result
         if json {
             println!(
                 "{}",
-                serde_json::to_string_pretty(&ListOutput { worktrees: vec![] })?
+                serde_json::to_string_pretty(&ListView { worktrees: vec![] })?
             );
         } else {
             println!("No worktrees found.");
@@ -37,7 +115,7 @@ pub(crate) fn handle_list(_args: ListArgs, _verbose: bool, json: bool) -> Result
         .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
         .collect();
 
-    let mut entries: Vec<ListEntry> = dir_entries
+    let mut entries: Vec<ListEntryView> = dir_entries
         .par_iter()
         .filter_map(|entry| {
             let name = entry.file_name().to_string_lossy().to_string();
@@ -49,83 +127,214 @@ pub(crate) fn handle_list(_args: ListArgs, _verbose: bool, json: bool) -> Result
             }
 
             let has_meta_root = repos.iter().any(|r| r.alias == ".");
-            let repo_entries: Vec<ListRepoEntry> = repos
+            let checked: Vec<(ListRepoEntry, bool)> = repos
                 .par_iter()
                 .map(|r| {
-                    let dirty = git_status_summary(&r.path)
-                        .map(|s| s.dirty)
-                        .unwrap_or(false);
-                    ListRepoEntry {
+                    let summary = git_status_summary(&r.path);
+                    // --verify: a failed status or unresolvable HEAD means a broken gitdir link
+                    let healthy = !verify || (summary.is_ok() && repo_is_healthy(&r.path));
+                    let dirty = summary.map(|s| s.dirty).unwrap_or(false);
+                    let repo_entry = ListRepoEntry {
                         alias: r.alias.clone(),
                         branch: r.branch.clone(),
                         dirty,
-                    }
+                    };
+                    (repo_entry, healthy)
                 })
                 .collect();
+            let broken_repos: Vec<String> = checked
+                .iter()
+                .filter(|(_, healthy)| !healthy)
+                .map(|(r, _)| r.alias.clone())
+                .collect();
+            let repo_entries: Vec<ListRepoEntry> = checked.into_iter().map(|(r, _)| r).collect();
 
             // Merge store metadata if available
             let wt_key = wt_dir.to_string_lossy().to_string();
-            let (ephemeral, ttl_remaining, custom) =
-                if let Some(store_entry) = store_data.worktrees.get(&wt_key) {
-                    (
-                        Some(store_entry.ephemeral),
-                        entry_ttl_remaining(store_entry, now),
-                        (!store_entry.custom.is_empty()).then(|| store_entry.custom.clone()),
-                    )
-                } else {
-                    (None, None, None)
-                };
-
-            Some(ListEntry {
-                name,
-                root: wt_dir.display().to_string(),
-                has_meta_root,
-                repos: repo_entries,
-                ephemeral,
-                ttl_remaining_seconds: ttl_remaining,
-                custom,
+            let store_entry = store_data.worktrees.get(&wt_key);
+            let (ephemeral, ttl_remaining, custom) = if let Some(store_entry) = store_entry {
+                (
+                    Some(store_entry.ephemeral),
+                    entry_ttl_remaining(store_entry, now),
+                    (!store_entry.custom.is_empty()).then(|| store_entry.custom.clone()),
+                )
+            } else {
+                (None, None, None)
+            };
+
+            Some(ListEntryView {
+                entry: ListEntry {
+                    name,
+                    root: wt_dir.display().to_string(),
+                    has_meta_root,
+                    repos: repo_entries,
+                    ephemeral,
+                    ttl_remaining_seconds: ttl_remaining,
+                    custom,
+                },
+                healthy: verify.then(|| broken_repos.is_empty()),
+                broken_repos,
+                project: store_entry.map(|e| e.project.clone()),
+                created_at: store_entry.map(|e| e.created_at.clone()),
+                age_seconds: store_entry.and_then(|e| age_seconds(&e.created_at, now)),
             })
         })
         .collect();
 
-    // Sort by name for deterministic output
-    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.retain(|view| {
+        let ephemeral = view.entry.ephemeral == Some(true);
+        let expired = matches!(view.entry.ttl_remaining_seconds, Some(ttl) if ttl <= 0);
+        (!args.ephemeral || ephemeral)
+            && (!args.persistent || !ephemeral)
+            && (!args.expired || expired)
+    });
+    sort_entries(&mut entries, args.sort);
 
-    if json {
+    if args.group_by_project {
+        let projects = group_by_project(entries);
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&GroupedListView { projects })?
+            );
+        } else if projects.is_empty() {
+            println!("No worktrees found.");
+        } else {
+            for group in &projects {
+                let header = group.project.as_deref().unwrap_or("(no store entry)");
+                println!("{}", header.cyan().bold());
+                println!();
+                for view in &group.worktrees {
+                    print_entry(view, "  ");
+                }
+            }
+        }
+    } else if json {
         println!(
             "{}",
-            serde_json::to_string_pretty(&ListOutput { worktrees: entries })?
+            serde_json::to_string_pretty(&ListView { worktrees: entries })?
         );
     } else if entries.is_empty() {
         println!("No worktrees found.");
     } else {
-        for e in &entries {
-            let mut header = e.name.bold().to_string();
-            if e.ephemeral == Some(true) {
-                header.push_str(&format!(" {}", "[ephemeral]".dimmed()));
-            }
-            if let Some(ttl) = e.ttl_remaining_seconds {
-                if ttl > 0 {
-                    header.push_str(&format!(
-                        " {}",
-                        format!("[TTL: {}]", format_duration(ttl)).dimmed()
-                    ));
-                } else {
-                    header.push_str(&format!(" {}", "[expired]".red()));
-                }
-            }
-            println!("{header}");
-            for r in &e.repos {
-                let status = if r.dirty {
-                    "modified".yellow().to_string()
-                } else {
-                    "clean".green().to_string()
-                };
-                println!("  {:12} -> {:20} ({})", r.alias, r.branch, status);
-            }
-            println!();
+        for view in &entries {
+            print_entry(view, "");
         }
     }
 
     Ok(())
 }
+
+/// Order sets for output. Name breaks ties (and is the whole order for
+/// `--sort name`); sets missing the sort key (no store entry, no TTL) go last.
+fn sort_entries(entries: &mut [ListEntryView], sort: ListSort) {
+    entries.sort_by(|a, b| {
+        let key = |view: &ListEntryView| match sort {
+            ListSort::Name => None,
+            // Oldest first: the largest age
+            ListSort::Created => view.age_seconds.map(|age| -age),
+            ListSort::Ttl => view.entry.ttl_remaining_seconds,
+        };
+        let (ka, kb) = (key(a), key(b));
+        ka.is_none()
+            .cmp(&kb.is_none())
+            .then(ka.cmp(&kb))
+            .then_with(|| a.entry.name.cmp(&b.entry.name))
+    });
+}
+
+/// Seconds elapsed since an RFC3339 timestamp, or `None` if it doesn't parse.
+fn age_seconds(created_at: &str, now: i64) -> Option<i64> {
+    let created = chrono::DateTime::parse_from_rfc3339(created_at).ok()?;
+    Some((now - created.timestamp()).max(0))
+}
+
+/// Print one set and its repos, each line prefixed with `indent`.
+fn print_entry(view: &ListEntryView, indent: &str) {
+    let e = &view.entry;
+    let mut header = e.name.bold().to_string();
+    if view.healthy == Some(false) {
+        header.push_str(&format!(" {}", "[broken]".red().bold()));
+    }
+    if e.ephemeral == Some(true) {
+        header.push_str(&format!(" {}", "[ephemeral]".dimmed()));
+    }
+    if let Some(ttl) = e.ttl_remaining_seconds {
+        if ttl > 0 {
+            header.push_str(&format!(
+                " {}",
+                format!("[TTL: {}]", format_duration(ttl)).dimmed()
+            ));
+        } else {
+            header.push_str(&format!(" {}", "[expired]".red()));
+        }
+    }
+    if let Some(age) = view.age_seconds {
+        header.push_str(&format!(
+            " {}",
+            format!("created {} ago", format_duration(age)).dimmed()
+        ));
+    }
+    println!("{indent}{header}");
+    for r in &e.repos {
+        let status = if view.broken_repos.contains(&r.alias) {
+            "unreadable".red().to_string()
+        } else if r.dirty {
+            "modified".yellow().to_string()
+        } else {
+            "clean".green().to_string()
+        };
+        println!("{indent}  {:12} -> {:20} ({})", r.alias, r.branch, status);
+    }
+    if view.healthy == Some(false) {
+        println!(
+            "{indent}  {}",
+            "Run 'git worktree repair' in the source repos, or remove and recreate the set."
+                .dimmed()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(name: &str, age: Option<i64>, ttl: Option<i64>) -> ListEntryView {
+        ListEntryView {
+            entry: ListEntry {
+                name: name.to_string(),
+                root: format!("/wt/{name}"),
+                has_meta_root: false,
+                repos: vec![],
+                ephemeral: ttl.map(|_| true),
+                ttl_remaining_seconds: ttl,
+                custom: None,
+            },
+            healthy: None,
+            broken_repos: vec![],
+            project: None,
+            created_at: None,
+            age_seconds: age,
+        }
+    }
+
+    fn names(entries: &[ListEntryView]) -> Vec<&str> {
+        entries.iter().map(|v| v.entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_puts_missing_keys_last() {
+        let mut entries = vec![
+            view("b", Some(60), Some(-5)),
+            view("a", None, None),
+            view("c", Some(3600), Some(300)),
+        ];
+        sort_entries(&mut entries, ListSort::Name);
+        assert_eq!(names(&entries), ["a", "b", "c"]);
+        sort_entries(&mut entries, ListSort::Created);
+        assert_eq!(names(&entries), ["c", "b", "a"]);
+        sort_entries(&mut entries, ListSort::Ttl);
+        assert_eq!(names(&entries), ["b", "c", "a"]);
+    }
+}
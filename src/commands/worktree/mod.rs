@@ -5,16 +5,24 @@ pub(crate) mod cli_types;
 mod create;
 mod diff;
 mod exec;
+mod extend;
+mod forge;
 mod list;
+mod path;
 mod prune;
 mod remove;
+mod rename;
 mod status;
+mod store;
+mod sync;
 
 use anyhow::Result;
 use clap::Parser;
 use colored::*;
 use meta_plugin_protocol::CommandResult;
 
+use crate::MetaGitError;
+
 use cli_types::WorktreeCommands;
 
 /// Clap parser wrapper for worktree subcommands.
@@ -51,8 +59,11 @@ pub fn execute_worktree_command(
         // Bare "worktree" or "git worktree" — args already contains the subcommand (if any)
         args.to_vec()
     } else {
-        // Subcommand was part of the command prefix — prepend it
-        std::iter::once(subcommand.to_string())
+        // Subcommand was part of the command prefix — prepend it (split so
+        // nested subcommands like "store rebase" reach clap as separate args)
+        subcommand
+            .split_whitespace()
+            .map(String::from)
             .chain(args.iter().cloned())
             .collect()
     };
@@ -81,7 +92,7 @@ pub fn execute_worktree_command(
     match WorktreeParser::try_parse_from(&clap_args) {
         Ok(parsed) => match handle_worktree_command(parsed.command, verbose, json, strict) {
             Ok(()) => CommandResult::Message(String::new()),
-            Err(e) => CommandResult::Error(format!("{e}")),
+            Err(e) => MetaGitError::from_anyhow(&e).into_result(json),
         },
         Err(e) => {
             // Clap treats --help and --version as "errors" with special ErrorKind
@@ -116,8 +127,13 @@ fn handle_worktree_command(
         WorktreeCommands::List(args) => list::handle_list(args, verbose, json),
         WorktreeCommands::Status(args) => status::handle_status(args, verbose, json),
         WorktreeCommands::Diff(args) => diff::handle_diff(args, verbose, json),
-        WorktreeCommands::Exec(args) => exec::handle_exec(args, verbose, json),
+        WorktreeCommands::Exec(args) => exec::handle_exec(args, verbose, json, global_strict),
         WorktreeCommands::Prune(args) => prune::handle_prune(args, verbose, json, global_strict),
+        WorktreeCommands::Path(args) => path::handle_path(args, verbose, json),
+        WorktreeCommands::Rename(args) => rename::handle_rename(args, verbose, json, global_strict),
+        WorktreeCommands::Extend(args) => extend::handle_extend(args, verbose, json, global_strict),
+        WorktreeCommands::Sync(args) => sync::handle_sync(args, verbose, json),
+        WorktreeCommands::Store(args) => store::handle_store(args, verbose, json, global_strict),
         WorktreeCommands::Unknown(args) => {
             let cmd = args.first().map(|s| s.as_str()).unwrap_or("");
             eprintln!(
@@ -126,7 +142,10 @@ fn handle_worktree_command(
             );
             eprintln!();
             eprint_worktree_help();
-            anyhow::bail!("unrecognized worktree subcommand '{cmd}'");
+            Err(
+                MetaGitError::InvalidArgument(format!("unrecognized worktree subcommand '{cmd}'"))
+                    .into(),
+            )
         }
     }
 }
@@ -182,6 +201,17 @@ fn write_worktree_help(w: &mut dyn std::io::Write) {
     let _ = writeln!(w, "  diff     Show cross-repo diff vs base branch");
     let _ = writeln!(w, "  exec     Run a command across worktree repos");
     let _ = writeln!(w, "  prune    Remove expired/orphaned worktrees");
+    let _ = writeln!(w, "  path     Print a worktree set's (or repo's) path");
+    let _ = writeln!(w, "  rename   Rename a worktree set");
+    let _ = writeln!(
+        w,
+        "  sync     Merge or rebase the base branch into a set's repos"
+    );
+    let _ = writeln!(
+        w,
+        "  extend   Reset a set's TTL (--ttl) or drop it (--persistent)"
+    );
+    let _ = writeln!(w, "  store    Maintain the worktree store (rebase)");
     let _ = writeln!(w);
     let _ = writeln!(w, "CREATE OPTIONS:");
     let _ = writeln!(w, "  --repo <ALIAS[:BRANCH]>  Add specific repo(s)");
@@ -198,6 +228,10 @@ fn write_worktree_help(w: &mut dyn std::io::Write) {
         w,
         "  --from-pr <OWNER/REPO#N> Start from a PR's head branch"
     );
+    let _ = writeln!(
+        w,
+        "  --from-branch <NAME>     Check out origin/<NAME> in every repo that has it"
+    );
     let _ = writeln!(w, "  --dry-run                Preview planned operations");
     let _ = writeln!(w, "  --ephemeral              Mark for automatic cleanup");
     let _ = writeln!(
@@ -205,18 +239,113 @@ fn write_worktree_help(w: &mut dyn std::io::Write) {
         "  --ttl <DURATION>         Time-to-live (30s, 5m, 1h, 2d, 1w)"
     );
     let _ = writeln!(w, "  --meta <KEY=VALUE>       Store custom metadata");
+    let _ = writeln!(w, "  --quiet, -q              Suppress the summary output");
+    let _ = writeln!(
+        w,
+        "  --no-root                Exclude the meta repo (\".\") from the set"
+    );
+    let _ = writeln!(
+        w,
+        "  --then <COMMAND>         Run a command in the new set's root afterwards"
+    );
+    let _ = writeln!(
+        w,
+        "  --new-branch-only        Fail if a branch already exists (default: reuse it)"
+    );
+    let _ = writeln!(
+        w,
+        "  --reset-branch           Reset existing branches to the start point"
+    );
     let _ = writeln!(w);
     let _ = writeln!(w, "REMOVE OPTIONS:");
     let _ = writeln!(
         w,
         "  --force                  Remove even with uncommitted changes"
     );
+    let _ = writeln!(
+        w,
+        "  --delete-branch          Also delete branches the set created"
+    );
+    let _ = writeln!(w, "  --quiet, -q              Suppress the summary output");
     let _ = writeln!(w);
     let _ = writeln!(w, "EXEC OPTIONS:");
     let _ = writeln!(w, "  --include <REPOS>        Only run in specified repos");
     let _ = writeln!(w, "  --exclude <REPOS>        Skip specified repos");
     let _ = writeln!(w, "  --parallel               Run commands concurrently");
+    let _ = writeln!(
+        w,
+        "  --jobs, -j <N>           Run at most N repos at once (default: CPU count)"
+    );
+    let _ = writeln!(w, "  --quiet, -q              Suppress per-repo headers");
+    let _ = writeln!(
+        w,
+        "  --env <KEY=VALUE>        Set an env var (overrides --env-file)"
+    );
+    let _ = writeln!(
+        w,
+        "  --env-file <PATH>        Load KEY=VALUE lines from a file"
+    );
+    let _ = writeln!(
+        w,
+        "  --clean-env              Only PATH, HOME, git and --env vars are set"
+    );
+    let _ = writeln!(
+        w,
+        "  --retry <N>              Re-run a failing command up to N more times"
+    );
+    let _ = writeln!(
+        w,
+        "  --continue-on-error      Keep going after a repo exhausts its retries"
+    );
+    let _ = writeln!(
+        w,
+        "  --force-in-progress      Don't skip repos mid-rebase/merge"
+    );
     let _ = writeln!(w, "  --ephemeral              Atomic create+exec+remove");
+    let _ = writeln!(
+        w,
+        "  --keep-on-failure        Keep the ephemeral worktree if the command fails"
+    );
+    let _ = writeln!(
+        w,
+        "  --json                   One summary: {{worktree, results: [{{repo, exit_code,"
+    );
+    let _ = writeln!(
+        w,
+        "                           duration_ms, attempts}}]}}; command output on stderr"
+    );
+    let _ = writeln!(w);
+    let _ = writeln!(w, "STORE REBASE OPTIONS:");
+    let _ = writeln!(
+        w,
+        "  --from <OLD> --to <NEW>  Rewrite store paths after moving the workspace"
+    );
+    let _ = writeln!(w);
+    let _ = writeln!(w, "LIST OPTIONS:");
+    let _ = writeln!(
+        w,
+        "  --verify                 Flag sets with repos git cannot read"
+    );
+    let _ = writeln!(
+        w,
+        "  --group-by-project       Group sets under the meta repo that created them"
+    );
+    let _ = writeln!(
+        w,
+        "  --ephemeral, --persistent Only show ephemeral / non-ephemeral sets"
+    );
+    let _ = writeln!(
+        w,
+        "  --expired                Only show sets past their TTL"
+    );
+    let _ = writeln!(w, "  --sort <name|created|ttl> Order sets (default: name)");
+    let _ = writeln!(w);
+    let _ = writeln!(w, "STATUS OPTIONS:");
+    let _ = writeln!(w, "  --watch                  Refresh until Ctrl-C");
+    let _ = writeln!(
+        w,
+        "  --interval <SECONDS>     Refresh cadence for --watch (default: 2)"
+    );
     let _ = writeln!(w);
     let _ = writeln!(w, "DIFF OPTIONS:");
     let _ = writeln!(
@@ -224,6 +353,23 @@ fn write_worktree_help(w: &mut dyn std::io::Write) {
         "  --base <BRANCH>          Base branch for comparison (default: main)"
     );
     let _ = writeln!(w, "  --stat                   Show diffstat summary only");
+    let _ = writeln!(w, "  --name-only              List changed files per repo");
+    let _ = writeln!(
+        w,
+        "  --patch, -p              Print a combined patch for all repos"
+    );
+    let _ = writeln!(
+        w,
+        "  --output, -o <FILE>      Write the --patch output to FILE"
+    );
+    let _ = writeln!(
+        w,
+        "  --against-snapshot <SNAP> Compare against SHAs recorded in a snapshot"
+    );
+    let _ = writeln!(
+        w,
+        "  --exit-code              Fail when any repo differs from the base"
+    );
     let _ = writeln!(w);
     let _ = writeln!(
         w,
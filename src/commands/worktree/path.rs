@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use meta_cli::worktree::discover_worktree_repos;
+use meta_git_lib::worktree::helpers::*;
+
+use super::cli_types::PathArgs;
+use crate::MetaGitError;
+
+/// Print a worktree set's root (or one repo's) path, for `cd "$(meta worktree path NAME)"`.
+///
+/// Nothing but the path goes to stdout; a missing set or repo is an error, so
+/// the shell substitution fails instead of yielding a bogus directory.
+pub(crate) fn handle_path(args: PathArgs, _verbose: bool, json: bool) -> Result<()> {
+    let name = &args.name;
+    validate_worktree_name(name)?;
+
+    let meta_dir = find_meta_dir();
+    let wt_dir = resolve_worktree_root(meta_dir.as_deref())?.join(name);
+    if !wt_dir.is_dir() {
+        return Err(MetaGitError::NotFound(format!(
+            "Worktree '{}' not found at {}",
+            name,
+            wt_dir.display()
+        ))
+        .into());
+    }
+
+    let path = match args.repo.as_deref() {
+        None => wt_dir,
+        Some(alias) => discover_worktree_repos(&wt_dir)?
+            .into_iter()
+            .find(|r| r.alias == alias)
+            .map(|r| r.path)
+            .ok_or_else(|| anyhow::anyhow!("Repo '{alias}' is not in worktree '{name}'"))?,
+    };
+    let path = std::fs::canonicalize(&path).unwrap_or(path);
+
+    if json {
+        let output = serde_json::json!({
+            "name": name,
+            "repo": args.repo,
+            "path": path.display().to_string(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
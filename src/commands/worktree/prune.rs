@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::Utc;
 use colored::*;
+use serde::Serialize;
 use std::path::Path;
 
 use meta_cli::worktree::discover_worktree_repos;
@@ -12,6 +13,57 @@ use meta_git_lib::worktree::types::*;
 
 use super::cli_types::PruneArgs;
 
+/// `PruneOutput` plus the disk space freed by the removed worktrees.
+#[derive(Serialize)]
+struct PruneReport {
+    #[serde(flatten)]
+    output: PruneOutput,
+    /// Bytes freed (or, for dry runs, that would be freed)
+    reclaimed_bytes: u64,
+}
+
+fn print_prune_json(removed: Vec<PruneEntry>, dry_run: bool, reclaimed_bytes: u64) -> Result<()> {
+    let report = PruneReport {
+        output: PruneOutput { removed, dry_run },
+        reclaimed_bytes,
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// Total size of regular files under `path`, without following symlinks.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return if meta.is_file() { meta.len() } else { 0 };
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| dir_size(&e.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Human-readable byte count (1024-based, one decimal above bytes).
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 /// Helper to create a PruneEntry with consistent structure.
 fn create_prune_entry(
     name: String,
@@ -27,6 +79,26 @@ fn create_prune_entry(
     }
 }
 
+/// The reason (and, for TTL expiry, the age) a set is pruned for, given
+/// its orphan reason and TTL expiry checked independently.
+/// `--ttl-only` / `--orphaned-only` pick one; otherwise orphaning wins.
+fn prune_reason(
+    orphaned: Option<String>,
+    expired_age: Option<u64>,
+    ttl_only: bool,
+    orphaned_only: bool,
+) -> Option<(String, Option<u64>)> {
+    let expired = expired_age.map(|age| ("ttl_expired".to_string(), Some(age)));
+    let orphaned = orphaned.map(|reason| (reason, None));
+    if ttl_only {
+        return expired;
+    }
+    if orphaned_only {
+        return orphaned;
+    }
+    orphaned.or(expired)
+}
+
 /// Check if a worktree entry is orphaned due to missing source repos.
 /// Uses a config cache to avoid redundant file I/O when multiple worktrees
 /// share the same source project.
@@ -76,13 +148,7 @@ pub(crate) fn handle_prune(
     let store: WorktreeStoreData = store_list()?;
     if store.worktrees.is_empty() {
         if json {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&PruneOutput {
-                    removed: vec![],
-                    dry_run,
-                })?
-            );
+            print_prune_json(vec![], dry_run, 0)?;
         } else {
             println!("No worktrees in store. Nothing to prune.");
         }
@@ -97,67 +163,43 @@ pub(crate) fn handle_prune(
     > = std::collections::HashMap::new();
 
     for (path_key, entry) in &store.worktrees {
-        let wt_path = Path::new(path_key);
-
-        // Check if path exists (orphaned detection)
-        if !wt_path.exists() {
-            to_remove.push(create_prune_entry(
-                entry.name.clone(),
-                path_key.clone(),
-                "orphaned (missing directory)",
-                None,
-            ));
+        if args.ephemeral_only && !entry.ephemeral {
             continue;
         }
-
-        // Check if source project directory still exists
+        let wt_path = Path::new(path_key);
         let project_path = Path::new(&entry.project);
-        if !project_path.exists() {
-            to_remove.push(create_prune_entry(
-                entry.name.clone(),
-                path_key.clone(),
-                "orphaned (source project missing)",
-                None,
-            ));
-            continue;
-        }
 
-        // Check if source repos still exist in project (with config caching)
-        if let Some(reason) = check_repo_orphaned(entry, &mut config_cache) {
+        // Orphaned: missing directory, source project, or source repos
+        // (with config caching)
+        let orphaned = if !wt_path.exists() {
+            Some("orphaned (missing directory)".to_string())
+        } else if !project_path.exists() {
+            Some("orphaned (source project missing)".to_string())
+        } else {
+            check_repo_orphaned(entry, &mut config_cache)
+        };
+
+        // TTL expiry, checked whether or not the set is also orphaned
+        let expired_age = entry_ttl_remaining(entry, now)
+            .filter(|remaining| *remaining <= 0)
+            // Total age = configured TTL + seconds past expiry
+            .map(|remaining| entry.ttl_seconds.unwrap() + (-remaining) as u64);
+
+        if let Some((reason, age)) =
+            prune_reason(orphaned, expired_age, args.ttl_only, args.orphaned_only)
+        {
             to_remove.push(create_prune_entry(
                 entry.name.clone(),
                 path_key.clone(),
                 reason,
-                None,
+                age,
             ));
-            continue;
-        }
-
-        // Check TTL expiration
-        if let Some(remaining) = entry_ttl_remaining(entry, now) {
-            if remaining <= 0 {
-                // Total age = configured TTL + seconds past expiry
-                let overdue = (-remaining) as u64;
-                let age = entry.ttl_seconds.unwrap() + overdue;
-                to_remove.push(create_prune_entry(
-                    entry.name.clone(),
-                    path_key.clone(),
-                    "ttl_expired",
-                    Some(age),
-                ));
-            }
         }
     }
 
     if to_remove.is_empty() {
         if json {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&PruneOutput {
-                    removed: vec![],
-                    dry_run,
-                })?
-            );
+            print_prune_json(vec![], dry_run, 0)?;
         } else {
             println!("Nothing to prune.");
         }
@@ -165,29 +207,32 @@ pub(crate) fn handle_prune(
     }
 
     if dry_run {
+        // Estimate only; nothing is removed
+        let estimate: u64 = to_remove.iter().map(|e| dir_size(Path::new(&e.path))).sum();
         if json {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&PruneOutput {
-                    removed: to_remove,
-                    dry_run: true,
-                })?
-            );
+            print_prune_json(to_remove, true, estimate)?;
         } else {
             println!("Would prune {} worktree(s):", to_remove.len());
             for entry in &to_remove {
                 println!("  {} ({}) — {}", entry.name, entry.reason, entry.path);
             }
+            if estimate > 0 {
+                println!("Would reclaim ~{}", format_bytes(estimate));
+            }
         }
         return Ok(());
     }
 
     // Actually remove: physical cleanup first, then batch store update.
     let mut removed = Vec::new();
+    let mut reclaimed_bytes = 0_u64;
     for prune_entry in &to_remove {
         let wt_path = Path::new(&prune_entry.path);
 
         if wt_path.exists() {
+            // Measure before removal; counted only once the directory is gone
+            let size = dir_size(wt_path);
+
             // Try to properly remove via git worktree remove
             let repos = discover_worktree_repos(wt_path).unwrap_or_default();
             let _ = remove_worktree_repos(&repos, true, false);
@@ -203,6 +248,7 @@ pub(crate) fn handle_prune(
                 )?;
                 continue;
             }
+            reclaimed_bytes += size;
         }
 
         removed.push(prune_entry.clone());
@@ -217,18 +263,15 @@ pub(crate) fn handle_prune(
     fire_post_prune(&removed, meta_dir.as_deref());
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&PruneOutput {
-                removed,
-                dry_run: false,
-            })?
-        );
+        print_prune_json(removed, false, reclaimed_bytes)?;
     } else {
         println!("{} Pruned {} worktree(s):", "✓".green(), removed.len());
         for entry in &removed {
             println!("  {} ({}) — {}", entry.name, entry.reason, entry.path);
         }
+        if reclaimed_bytes > 0 {
+            println!("Reclaimed {}", format_bytes(reclaimed_bytes));
+        }
     }
 
     Ok(())
@@ -299,6 +342,78 @@ mod tests {
         assert_eq!(entry.reason, "test reason");
     }
 
+    // ── prune_reason ────────────────────────────────────
+
+    #[test]
+    fn prune_scope_filters_by_reason() {
+        let orphaned = || Some("orphaned (missing directory)".to_string());
+        let expired = ("ttl_expired".to_string(), Some(90));
+        let missing = ("orphaned (missing directory)".to_string(), None);
+        assert_eq!(
+            prune_reason(None, Some(90), false, false),
+            Some(expired.clone())
+        );
+        assert_eq!(
+            prune_reason(orphaned(), None, false, false),
+            Some(missing.clone())
+        );
+        assert_eq!(
+            prune_reason(None, Some(90), true, false),
+            Some(expired.clone())
+        );
+        assert_eq!(prune_reason(orphaned(), None, true, false), None);
+        assert_eq!(
+            prune_reason(orphaned(), None, false, true),
+            Some(missing.clone())
+        );
+        assert_eq!(prune_reason(None, Some(90), false, true), None);
+        assert_eq!(prune_reason(None, None, false, false), None);
+    }
+
+    #[test]
+    fn expired_orphans_are_in_scope_for_ttl_only() {
+        let orphaned = || Some("orphaned (missing directory)".to_string());
+        assert_eq!(
+            prune_reason(orphaned(), Some(90), false, false),
+            Some(("orphaned (missing directory)".to_string(), None))
+        );
+        assert_eq!(
+            prune_reason(orphaned(), Some(90), true, false),
+            Some(("ttl_expired".to_string(), Some(90)))
+        );
+        assert_eq!(
+            prune_reason(orphaned(), Some(90), false, true),
+            Some(("orphaned (missing directory)".to_string(), None))
+        );
+    }
+
+    // ── dir_size / format_bytes ─────────────────────────
+
+    #[test]
+    fn dir_size_sums_nested_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(temp_dir.path().join("top.txt"), vec![0u8; 100]).unwrap();
+        std::fs::write(nested.join("deep.txt"), vec![0u8; 24]).unwrap();
+
+        assert_eq!(dir_size(temp_dir.path()), 124);
+    }
+
+    #[test]
+    fn dir_size_missing_path_is_zero() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(dir_size(&temp_dir.path().join("gone")), 0);
+    }
+
+    #[test]
+    fn format_bytes_picks_unit() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
     // ── check_repo_orphaned ─────────────────────────────
 
     #[test]
@@ -1,14 +1,32 @@
 use anyhow::Result;
 use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use meta_cli::worktree::discover_worktree_repos;
 use meta_git_lib::worktree::git_ops::*;
 use meta_git_lib::worktree::helpers::*;
 use meta_git_lib::worktree::hooks::fire_post_destroy;
-use meta_git_lib::worktree::store::store_remove;
+use meta_git_lib::worktree::store::{store_list, store_remove};
 use meta_git_lib::worktree::types::*;
 
 use super::cli_types::DestroyArgs;
+use crate::MetaGitError;
+
+#[derive(Serialize)]
+struct RemoveOutput {
+    #[serde(flatten)]
+    output: DestroyOutput,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deleted_branches: Vec<DeletedBranch>,
+}
+
+#[derive(Serialize)]
+struct DeletedBranch {
+    repo: String,
+    branch: String,
+}
 
 pub(crate) fn handle_remove(
     args: DestroyArgs,
@@ -25,7 +43,12 @@ pub(crate) fn handle_remove(
     let wt_dir = worktree_root.join(name);
 
     if !wt_dir.exists() {
-        anyhow::bail!("Worktree '{}' not found at {}", name, wt_dir.display());
+        return Err(MetaGitError::NotFound(format!(
+            "Worktree '{}' not found at {}",
+            name,
+            wt_dir.display()
+        ))
+        .into());
     }
 
     let repos = discover_worktree_repos(&wt_dir)?;
@@ -51,6 +74,13 @@ pub(crate) fn handle_remove(
         }
     }
 
+    // Read created branches before the store entry goes away
+    let created = if args.delete_branch {
+        created_branches(&wt_dir)
+    } else {
+        vec![]
+    };
+
     // Remove worktrees in correct order (children first, "." last)
     remove_worktree_repos(&repos, force, verbose)?;
 
@@ -62,18 +92,85 @@ pub(crate) fn handle_remove(
     // Remove from centralized store
     super::warn_store_error(store_remove(&wt_dir), strict)?;
 
+    let mut deleted_branches = Vec::new();
+    for (alias, source, branch) in created {
+        match delete_branch(&source, &branch, force) {
+            Ok(()) => deleted_branches.push(DeletedBranch {
+                repo: alias,
+                branch,
+            }),
+            Err(e) => super::warn_or_bail(
+                strict,
+                format!("Could not delete branch '{branch}' in {alias}: {e}"),
+            )?,
+        }
+    }
+
     // Fire post-destroy hook
     fire_post_destroy(name, &wt_dir, force, meta_dir.as_deref());
 
     if json {
-        let output = DestroyOutput {
-            name: name.to_string(),
-            path: wt_dir.display().to_string(),
-            repos_removed: repos.len(),
+        let output = RemoveOutput {
+            output: DestroyOutput {
+                name: name.to_string(),
+                path: wt_dir.display().to_string(),
+                repos_removed: repos.len(),
+            },
+            deleted_branches,
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
+    } else if !args.quiet {
         println!("{} Removed worktree '{}'", "✓".green(), name.bold());
+        for d in &deleted_branches {
+            println!("  Deleted branch {} in {}", d.branch.bold(), d.repo);
+        }
+    }
+    Ok(())
+}
+
+/// Branches the store records as created by this set: `(alias, source repo, branch)`.
+///
+/// Source repos are resolved from the entry's `project`, not the cwd, so this
+/// works from any directory. Without a store entry nothing is returned, so
+/// pre-existing branches are never deleted on a guess.
+fn created_branches(wt_dir: &Path) -> Vec<(String, PathBuf, String)> {
+    let Ok(store) = store_list() else {
+        return vec![];
+    };
+    let canonical = wt_dir.canonicalize().ok();
+    let Some(entry) = store.worktrees.iter().find_map(|(key, entry)| {
+        let key = Path::new(key);
+        (key == wt_dir || canonical.as_deref() == Some(key)).then_some(entry)
+    }) else {
+        return vec![];
+    };
+
+    let meta_dir = Path::new(&entry.project);
+    entry
+        .repos
+        .iter()
+        .filter(|r| r.created_branch)
+        .filter_map(|r| {
+            let source = if r.alias == "." {
+                meta_dir.to_path_buf()
+            } else {
+                lookup_nested_project(meta_dir, &r.alias).ok()?.0
+            };
+            Some((r.alias.clone(), source, r.branch.clone()))
+        })
+        .collect()
+}
+
+fn delete_branch(source: &Path, branch: &str, force: bool) -> Result<()> {
+    let flag = if force { "-D" } else { "-d" };
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(source)
+        .args(["branch", flag, branch])
+        .output()?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(MetaGitError::GitFailed(stderr.trim().to_string()).into());
     }
     Ok(())
 }
@@ -0,0 +1,167 @@
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+use meta_cli::worktree::discover_worktree_repos;
+use meta_git_lib::worktree::helpers::*;
+use meta_git_lib::worktree::store::store_list;
+
+use super::cli_types::RenameArgs;
+use crate::MetaGitError;
+
+#[derive(Serialize)]
+struct RenameOutput {
+    old_name: String,
+    name: String,
+    root: String,
+    repos: Vec<String>,
+}
+
+/// Rename a worktree set: move its directory, then let git repair the
+/// source repos' links to each moved worktree. Branches are left alone.
+pub(crate) fn handle_rename(
+    args: RenameArgs,
+    verbose: bool,
+    json: bool,
+    strict: bool,
+) -> Result<()> {
+    let (old, new) = (&args.old, &args.new);
+    validate_worktree_name(old)?;
+    validate_worktree_name(new)?;
+
+    let meta_dir = find_meta_dir();
+    let worktree_root = resolve_worktree_root(meta_dir.as_deref())?;
+    let old_dir = worktree_root.join(old);
+    let new_dir = worktree_root.join(new);
+    if !old_dir.is_dir() {
+        return Err(MetaGitError::NotFound(format!(
+            "Worktree '{}' not found at {}",
+            old,
+            old_dir.display()
+        ))
+        .into());
+    }
+    if new_dir.exists() {
+        anyhow::bail!("Worktree '{}' already exists at {}", new, new_dir.display());
+    }
+
+    // Paths inside the set stay the same relative to its root
+    let repos: Vec<(String, std::path::PathBuf)> = discover_worktree_repos(&old_dir)?
+        .into_iter()
+        .map(|r| {
+            let rel = r
+                .path
+                .strip_prefix(&old_dir)
+                .unwrap_or(Path::new(""))
+                .to_path_buf();
+            (r.alias, rel)
+        })
+        .collect();
+
+    std::fs::rename(&old_dir, &new_dir).with_context(|| {
+        format!(
+            "Failed to move {} to {}",
+            old_dir.display(),
+            new_dir.display()
+        )
+    })?;
+
+    for (alias, rel) in &repos {
+        let path = new_dir.join(rel);
+        if verbose {
+            eprintln!(
+                "Repairing worktree link for '{alias}' at {}",
+                path.display()
+            );
+        }
+        if let Err(e) = repair_worktree(&path) {
+            super::warn_or_bail(strict, format!("Could not repair '{alias}': {e}"))?;
+        }
+    }
+
+    // The store is keyed by path: re-add the entry under the new one
+    let store = store_list().unwrap_or_default();
+    if let Some(entry) = store.worktrees.get(&old_dir.to_string_lossy().to_string()) {
+        let mut renamed = super::store::copy_entry(entry);
+        renamed.name = new.clone();
+        super::warn_store_error(
+            super::store::store_replace(&old_dir, &new_dir, renamed),
+            strict,
+        )?;
+    }
+
+    if json {
+        let output = RenameOutput {
+            old_name: old.clone(),
+            name: new.clone(),
+            root: new_dir.display().to_string(),
+            repos: repos.into_iter().map(|(alias, _)| alias).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!(
+            "{} Renamed worktree '{}' to '{}' at {}",
+            "✓".green(),
+            old,
+            new.bold(),
+            new_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// `git worktree repair` from inside a moved worktree points its source repo
+/// back at the new location.
+fn repair_worktree(path: &Path) -> Result<()> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["worktree", "repair"])
+        .output()?;
+    if !out.status.success() {
+        return Err(MetaGitError::GitFailed(
+            String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn repair_reconnects_a_moved_worktree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("api");
+        init_repo(&source);
+        let old = tmp.path().join("old");
+        git(
+            &source,
+            &[
+                "worktree",
+                "add",
+                "--quiet",
+                "-b",
+                "feat",
+                old.join("api").to_str().unwrap(),
+            ],
+        );
+
+        let new = tmp.path().join("new");
+        std::fs::rename(&old, &new).unwrap();
+        repair_worktree(&new.join("api")).unwrap();
+
+        let list = git(&source, &["worktree", "list", "--porcelain"]);
+        let new_api = std::fs::canonicalize(new.join("api")).unwrap();
+        assert!(list.contains(&new_api.display().to_string()), "{list}");
+        assert_eq!(
+            git(&new.join("api"), &["branch", "--show-current"]).trim(),
+            "feat"
+        );
+    }
+}
@@ -1,19 +1,84 @@
 use anyhow::Result;
 use colored::*;
 use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
 
+use crate::error::Categorize;
+use crate::helpers::{git_ahead_behind, git_output};
+use crate::MetaGitError;
 use meta_git_lib::worktree::git_ops::*;
 use meta_git_lib::worktree::helpers::discover_and_validate_worktree;
 use meta_git_lib::worktree::types::*;
 
 use super::cli_types::StatusArgs;
 
+/// A repo's status plus the opt-in extras computed by this command.
+#[derive(Serialize)]
+struct StatusRepoView {
+    #[serde(flatten)]
+    entry: StatusRepoEntry,
+    /// Number of stash entries made on this repo's branch; only computed
+    /// with `--stash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stash_count: Option<usize>,
+    /// HEAD's short SHA and subject; `None` before the first commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_commit: Option<LastCommit>,
+}
+
+#[derive(Serialize)]
+struct LastCommit {
+    sha: String,
+    subject: String,
+}
+
+#[derive(Serialize)]
+struct StatusView {
+    name: String,
+    repos: Vec<StatusRepoView>,
+}
+
 pub(crate) fn handle_status(args: StatusArgs, _verbose: bool, json: bool) -> Result<()> {
     let name = &args.name;
 
-    let repos = discover_and_validate_worktree(name)?;
+    if !args.watch {
+        let statuses = collect_statuses(name, args.stash)?;
+        return print_status(name, statuses, json, false);
+    }
 
-    let statuses: Vec<StatusRepoEntry> = repos
+    // --watch: redraw until the process is interrupted. Nothing is held open
+    // between ticks, so the default Ctrl-C handling exits cleanly.
+    let term = console::Term::stdout();
+    let interval = std::time::Duration::from_secs(args.interval);
+    loop {
+        let statuses = collect_statuses(name, args.stash)?;
+        if !json {
+            let _ = term.clear_screen();
+        }
+        print_status(name, statuses, json, true)?;
+        if !json {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "Every {}s — {} — Ctrl-C to exit",
+                    args.interval,
+                    chrono::Local::now().format("%H:%M:%S")
+                )
+                .dimmed()
+            );
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Collect per-repo status for a worktree set in parallel.
+fn collect_statuses(name: &str, stash: bool) -> Result<Vec<StatusRepoView>> {
+    let repos = discover_and_validate_worktree(name).categorize(MetaGitError::NotFound)?;
+
+    let statuses: Vec<StatusRepoView> = repos
         .par_iter()
         .map(|r| {
             let summary = git_status_summary(&r.path).unwrap_or(GitStatusSummary {
@@ -23,7 +88,7 @@ pub(crate) fn handle_status(args: StatusArgs, _verbose: bool, json: bool) -> Res
             });
             let (ahead, behind) = git_ahead_behind(&r.path).unwrap_or((0, 0));
 
-            StatusRepoEntry {
+            let entry = StatusRepoEntry {
                 alias: r.alias.clone(),
                 path: r.path.display().to_string(),
                 branch: r.branch.clone(),
@@ -33,19 +98,79 @@ pub(crate) fn handle_status(args: StatusArgs, _verbose: bool, json: bool) -> Res
                 ahead,
                 behind,
                 modified_files: summary.modified_files,
+            };
+            StatusRepoView {
+                entry,
+                stash_count: stash.then(|| stash_count(&r.path, &r.branch)),
+                last_commit: last_commit(&r.path),
             }
         })
         .collect();
 
+    Ok(statuses)
+}
+
+/// Number of stash entries made on `branch` (0 if git fails).
+///
+/// `refs/stash` is shared by a source repo and all its worktrees, so the
+/// whole list would give every repo in a set the same count.
+fn stash_count(repo: &Path, branch: &str) -> usize {
+    git_output(repo, &["stash", "list", "--format=%gs"])
+        .map(|out| {
+            out.lines()
+                .filter(|subject| stash_branch(subject) == Some(branch))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// The branch in a stash subject: `WIP on <branch>: ...` or `On <branch>: ...`.
+fn stash_branch(subject: &str) -> Option<&str> {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))?;
+    rest.split_once(": ").map(|(branch, _)| branch)
+}
+
+/// HEAD's short SHA and subject, via `git log -1`.
+fn last_commit(repo: &Path) -> Option<LastCommit> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "-1", "--format=%h%x00%s"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let (sha, subject) = stdout.trim_end().split_once('\0')?;
+    Some(LastCommit {
+        sha: sha.to_string(),
+        subject: subject.to_string(),
+    })
+}
+
+/// Print a status report. `compact_json` emits one JSON document per line,
+/// so --watch --json produces a stream of snapshots.
+fn print_status(
+    name: &str,
+    statuses: Vec<StatusRepoView>,
+    json: bool,
+    compact_json: bool,
+) -> Result<()> {
     if json {
-        let output = StatusOutput {
+        let output = StatusView {
             name: name.to_string(),
             repos: statuses,
         };
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        if compact_json {
+            println!("{}", serde_json::to_string(&output)?);
+        } else {
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
     } else {
         println!("{}:", name.bold());
-        for s in &statuses {
+        for view in &statuses {
+            let s = &view.entry;
             let status_icon = if s.dirty {
                 "●".yellow().to_string()
             } else {
@@ -64,17 +189,50 @@ pub(crate) fn handle_status(args: StatusArgs, _verbose: bool, json: bool) -> Res
             if s.behind > 0 {
                 details.push(format!("↓{}", s.behind));
             }
+            match view.stash_count {
+                Some(n) if n > 0 => details.push(format!("{n} stashed")),
+                _ => {}
+            }
             let detail_str = if details.is_empty() {
                 "clean".to_string()
             } else {
                 details.join(", ")
             };
+            let commit = view
+                .last_commit
+                .as_ref()
+                .map(|c| {
+                    format!("  {} {}", c.sha, truncate(&c.subject, 50))
+                        .dimmed()
+                        .to_string()
+                })
+                .unwrap_or_default();
             println!(
-                "  {} {:12} {:20} {}",
-                status_icon, s.alias, s.branch, detail_str
+                "  {} {:12} {:20} {}{}",
+                status_icon, s.alias, s.branch, detail_str, commit
             );
         }
     }
 
     Ok(())
 }
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(max - 1).collect();
+    format!("{cut}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stash_branch_reads_both_subject_forms() {
+        assert_eq!(stash_branch("WIP on feat/x: abc123 msg"), Some("feat/x"));
+        assert_eq!(stash_branch("On main: before rebase"), Some("main"));
+        assert_eq!(stash_branch("autostash"), None);
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use meta_git_lib::worktree::store::{store_add, store_list, store_remove, store_remove_batch};
+use meta_git_lib::worktree::types::{StoreRepoEntry, WorktreeStoreEntry};
+
+use super::cli_types::{StoreArgs, StoreCommands, StoreRebaseArgs};
+use crate::MetaGitError;
+
+#[derive(Serialize)]
+struct RebaseOutput {
+    rebased: Vec<RebaseEntry>,
+    skipped: Vec<RebaseEntry>,
+    dry_run: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct RebaseEntry {
+    name: String,
+    old_path: String,
+    new_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+pub(crate) fn handle_store(args: StoreArgs, verbose: bool, json: bool, strict: bool) -> Result<()> {
+    match args.command {
+        StoreCommands::Rebase(args) => handle_rebase(args, verbose, json, strict),
+    }
+}
+
+/// Rewrite the path prefix of every store entry after the workspace moved.
+///
+/// Entries are keyed by absolute worktree path and record the absolute
+/// project path, so both are rewritten. Entries whose new paths don't exist
+/// are left untouched (or fail the command in strict mode).
+fn handle_rebase(args: StoreRebaseArgs, verbose: bool, json: bool, strict: bool) -> Result<()> {
+    if !args.to.is_dir() {
+        return Err(MetaGitError::NotFound(format!(
+            "--to path does not exist: {}",
+            args.to.display()
+        ))
+        .into());
+    }
+
+    let store = store_list()?;
+    let mut rebased = Vec::new();
+    let mut skipped = Vec::new();
+    let mut updates: Vec<(String, PathBuf, WorktreeStoreEntry)> = Vec::new();
+
+    for (key, entry) in &store.worktrees {
+        let new_wt = rebase_path(Path::new(key), &args.from, &args.to);
+        let new_project = rebase_path(Path::new(&entry.project), &args.from, &args.to);
+        if new_wt.is_none() && new_project.is_none() {
+            continue; // Not under --from
+        }
+        let new_wt = new_wt.unwrap_or_else(|| PathBuf::from(key));
+        let new_project = new_project.unwrap_or_else(|| PathBuf::from(&entry.project));
+
+        let mut report = RebaseEntry {
+            name: entry.name.clone(),
+            old_path: key.clone(),
+            new_path: new_wt.display().to_string(),
+            reason: None,
+        };
+
+        let missing = if !new_wt.exists() {
+            Some(format!("worktree not found at {}", new_wt.display()))
+        } else if !new_project.exists() {
+            Some(format!("project not found at {}", new_project.display()))
+        } else {
+            None
+        };
+        if let Some(reason) = missing {
+            super::warn_or_bail(strict, format!("Skipping '{}': {reason}", entry.name))?;
+            report.reason = Some(reason);
+            skipped.push(report);
+            continue;
+        }
+
+        if verbose {
+            eprintln!("Rebasing '{}': {} -> {}", entry.name, key, new_wt.display());
+        }
+        let mut updated = copy_entry(entry);
+        updated.project = new_project.to_string_lossy().to_string();
+        updates.push((key.clone(), new_wt, updated));
+        rebased.push(report);
+    }
+
+    if !args.dry_run && !updates.is_empty() {
+        // Add under the new paths first; an old key is only dropped once its
+        // replacement is stored, so a failed add never loses the entry
+        let mut old_keys = Vec::new();
+        for (old_key, new_wt, entry) in updates {
+            let moved = new_wt.to_string_lossy() != old_key.as_str();
+            match store_add(&new_wt, entry) {
+                Ok(()) if moved => old_keys.push(old_key),
+                Ok(()) => {}
+                Err(e) => super::warn_store_error(Err(e), strict)?,
+            }
+        }
+        if !old_keys.is_empty() {
+            store_remove_batch(&old_keys)?;
+        }
+    }
+
+    if json {
+        let output = RebaseOutput {
+            rebased,
+            skipped,
+            dry_run: args.dry_run,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if rebased.is_empty() && skipped.is_empty() {
+        println!(
+            "No store entries under {}. Nothing to rebase.",
+            args.from.display()
+        );
+        return Ok(());
+    }
+
+    let verb = if args.dry_run {
+        "Would rebase"
+    } else {
+        "Rebased"
+    };
+    println!(
+        "{} {} {} store entr{}:",
+        "✓".green(),
+        verb,
+        rebased.len(),
+        if rebased.len() == 1 { "y" } else { "ies" }
+    );
+    for r in &rebased {
+        println!("  {} {} -> {}", r.name.bold(), r.old_path, r.new_path);
+    }
+    if !skipped.is_empty() {
+        println!("{} Skipped {}:", "⚠".yellow(), skipped.len());
+        for r in &skipped {
+            println!(
+                "  {} ({})",
+                r.name.bold(),
+                r.reason.as_deref().unwrap_or("")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Store `entry` under `new`, then drop the entry at `old` if that's a
+/// different path.
+///
+/// Adding first means a failure leaves the old entry in place rather than
+/// losing it.
+pub(super) fn store_replace(old: &Path, new: &Path, entry: WorktreeStoreEntry) -> Result<()> {
+    store_add(new, entry)?;
+    if old != new {
+        store_remove(old)?;
+    }
+    Ok(())
+}
+
+/// A field-by-field copy of a store entry, to edit before [`store_replace`].
+pub(super) fn copy_entry(entry: &WorktreeStoreEntry) -> WorktreeStoreEntry {
+    WorktreeStoreEntry {
+        name: entry.name.clone(),
+        project: entry.project.clone(),
+        created_at: entry.created_at.clone(),
+        ephemeral: entry.ephemeral,
+        ttl_seconds: entry.ttl_seconds,
+        repos: entry
+            .repos
+            .iter()
+            .map(|r| StoreRepoEntry {
+                alias: r.alias.clone(),
+                branch: r.branch.clone(),
+                created_branch: r.created_branch,
+            })
+            .collect(),
+        custom: entry.custom.clone(),
+    }
+}
+
+/// Replace the `from` prefix of `path` with `to`, matching whole components.
+///
+/// Returns `None` when `path` is not under `from`.
+fn rebase_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let rest = path.strip_prefix(from).ok()?;
+    Some(if rest.as_os_str().is_empty() {
+        to.to_path_buf()
+    } else {
+        to.join(rest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebase_path_swaps_prefix() {
+        let rebased = rebase_path(
+            Path::new("/old/ws/.worktrees/feat"),
+            Path::new("/old/ws"),
+            Path::new("/new/place"),
+        );
+        assert_eq!(rebased, Some(PathBuf::from("/new/place/.worktrees/feat")));
+    }
+
+    #[test]
+    fn rebase_path_exact_match() {
+        let rebased = rebase_path(
+            Path::new("/old/ws"),
+            Path::new("/old/ws"),
+            Path::new("/new"),
+        );
+        assert_eq!(rebased, Some(PathBuf::from("/new")));
+    }
+
+    #[test]
+    fn rebase_path_requires_whole_component_prefix() {
+        // "/old/ws2" must not match "--from /old/ws"
+        assert_eq!(
+            rebase_path(
+                Path::new("/old/ws2/x"),
+                Path::new("/old/ws"),
+                Path::new("/new")
+            ),
+            None
+        );
+    }
+}
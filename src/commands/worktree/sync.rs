@@ -0,0 +1,266 @@
+use anyhow::Result;
+use colored::*;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::Categorize;
+use crate::helpers::{git_line, origin_default_branch, run_git};
+use crate::MetaGitError;
+use meta_git_lib::worktree::git_ops::git_status_summary;
+use meta_git_lib::worktree::helpers::discover_and_validate_worktree;
+
+use super::cli_types::SyncArgs;
+
+/// What `sync` did in one repo.
+#[derive(Debug, PartialEq)]
+enum SyncOutcome {
+    UpToDate,
+    FastForwarded,
+    Merged,
+    Rebased,
+    /// Merge or rebase stopped on conflicts; left in progress for the user
+    Conflicted,
+    Skipped(String),
+    Failed(String),
+}
+
+impl SyncOutcome {
+    fn kind(&self) -> &'static str {
+        match self {
+            SyncOutcome::UpToDate => "up_to_date",
+            SyncOutcome::FastForwarded => "fast_forwarded",
+            SyncOutcome::Merged => "merged",
+            SyncOutcome::Rebased => "rebased",
+            SyncOutcome::Conflicted => "conflicted",
+            SyncOutcome::Skipped(_) => "skipped",
+            SyncOutcome::Failed(_) => "failed",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SyncRepoResult {
+    repo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SyncOutput {
+    name: String,
+    /// `--base`, when given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    repos: Vec<SyncRepoResult>,
+}
+
+/// Bring every repo in a set up to date with `origin/<base>`, where the
+/// base is `--base` or else the branch each repo's origin/HEAD points at.
+///
+/// Each repo is fetched and merged (fast-forward when possible) or, with
+/// `--rebase`, rebased. A repo that conflicts is left mid-merge/rebase for
+/// manual resolution and the rest carry on; the command fails at the end if
+/// any repo conflicted or failed.
+pub(crate) fn handle_sync(args: SyncArgs, _verbose: bool, json: bool) -> Result<()> {
+    let name = &args.name;
+    let repos = discover_and_validate_worktree(name).categorize(MetaGitError::NotFound)?;
+
+    let mut results: Vec<(String, Option<String>, SyncOutcome)> = repos
+        .par_iter()
+        .map(|r| {
+            let base = sync_base(&r.path, args.base.as_deref());
+            let outcome = match &base {
+                Some(base) => sync_repo(&r.path, base, args.rebase),
+                None => SyncOutcome::Skipped("origin/HEAD is not set; pass --base".to_string()),
+            };
+            (r.alias.clone(), base, outcome)
+        })
+        .collect();
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let attention = results
+        .iter()
+        .filter(|(_, _, o)| matches!(o, SyncOutcome::Conflicted | SyncOutcome::Failed(_)))
+        .count();
+
+    if json {
+        let output = SyncOutput {
+            name: name.to_string(),
+            base: args.base.clone(),
+            repos: results
+                .iter()
+                .map(|(repo, base, outcome)| SyncRepoResult {
+                    repo: repo.clone(),
+                    base: base.clone(),
+                    result: outcome.kind(),
+                    detail: match outcome {
+                        SyncOutcome::Skipped(d) | SyncOutcome::Failed(d) => Some(d.clone()),
+                        _ => None,
+                    },
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match &args.base {
+            Some(base) => println!("{} onto origin/{base}:", name.bold()),
+            None => println!("{} onto each repo's origin/HEAD:", name.bold()),
+        }
+        for (repo, base, outcome) in &results {
+            let (marker, text) = match outcome {
+                SyncOutcome::UpToDate => ("✓".green(), "up to date".to_string()),
+                SyncOutcome::FastForwarded => ("↑".green(), "fast-forwarded".to_string()),
+                SyncOutcome::Merged => ("✓".green(), "merged".to_string()),
+                SyncOutcome::Rebased => ("✓".green(), "rebased".to_string()),
+                SyncOutcome::Conflicted => (
+                    "✗".red(),
+                    "conflicts, resolve and continue in the repo".to_string(),
+                ),
+                SyncOutcome::Skipped(reason) => ("-".yellow(), format!("skipped: {reason}")),
+                SyncOutcome::Failed(e) => ("✗".red(), format!("failed: {e}")),
+            };
+            match base {
+                Some(base) if args.base.is_none() => {
+                    println!("  {marker} {repo:12} {text} (origin/{base})")
+                }
+                _ => println!("  {marker} {repo:12} {text}"),
+            }
+        }
+    }
+
+    if attention > 0 {
+        anyhow::bail!("{attention} repo(s) in '{name}' need attention");
+    }
+    Ok(())
+}
+
+/// `--base`, or the branch origin/HEAD names in `repo` (without the
+/// `origin/` prefix).
+fn sync_base(repo: &Path, base: Option<&str>) -> Option<String> {
+    match base {
+        Some(base) => Some(base.to_string()),
+        None => origin_default_branch(repo)
+            .map(|b| b.strip_prefix("origin/").map(str::to_string).unwrap_or(b)),
+    }
+}
+
+/// Fetch `origin/<base>` and merge or rebase the current branch onto it.
+fn sync_repo(repo: &Path, base: &str, rebase: bool) -> SyncOutcome {
+    if git(repo, &["symbolic-ref", "--quiet", "HEAD"]).is_err() {
+        return SyncOutcome::Skipped("detached HEAD".to_string());
+    }
+    if git_status_summary(repo).map(|s| s.dirty).unwrap_or(false) {
+        return SyncOutcome::Skipped("uncommitted changes".to_string());
+    }
+    let refspec = format!("+refs/heads/{base}:refs/remotes/origin/{base}");
+    if let Err(e) = git(repo, &["fetch", "--quiet", "origin", &refspec]) {
+        return SyncOutcome::Failed(e);
+    }
+
+    let upstream = format!("origin/{base}");
+    let is_ancestor = |a: &str, b: &str| git(repo, &["merge-base", "--is-ancestor", a, b]).is_ok();
+    if is_ancestor(&upstream, "HEAD") {
+        return SyncOutcome::UpToDate;
+    }
+    if is_ancestor("HEAD", &upstream) {
+        return match git(repo, &["merge", "--ff-only", "--quiet", &upstream]) {
+            Ok(()) => SyncOutcome::FastForwarded,
+            Err(e) => SyncOutcome::Failed(e),
+        };
+    }
+
+    let (args, done): (Vec<&str>, SyncOutcome) = if rebase {
+        (
+            vec!["rebase", "--quiet", upstream.as_str()],
+            SyncOutcome::Rebased,
+        )
+    } else {
+        (
+            vec!["merge", "--no-edit", "--quiet", upstream.as_str()],
+            SyncOutcome::Merged,
+        )
+    };
+    match git(repo, &args) {
+        Ok(()) => done,
+        // The conflict stays in the working tree for the user to resolve
+        Err(_) if in_conflict(repo) => SyncOutcome::Conflicted,
+        Err(e) => SyncOutcome::Failed(e),
+    }
+}
+
+/// Whether a merge or rebase is stopped with unresolved paths.
+fn in_conflict(repo: &Path) -> bool {
+    git_line(repo, &["diff", "--name-only", "--diff-filter=U"]).is_some()
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<(), String> {
+    let out = run_git(repo, args).map_err(|e| e.to_string())?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{clone_repo, git as run, init_repo};
+
+    /// An origin with `main`, and a clone on `feat` branched from it.
+    fn setup() -> (tempfile::TempDir, std::path::PathBuf, std::path::PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        init_repo(&origin);
+        std::fs::write(origin.join("f"), "base\n").unwrap();
+        run(&origin, &["add", "f"]);
+        run(&origin, &["commit", "--quiet", "-m", "f"]);
+        let clone = tmp.path().join("clone");
+        clone_repo(&origin, &clone);
+        run(&clone, &["checkout", "--quiet", "-b", "feat"]);
+        (tmp, origin, clone)
+    }
+
+    #[test]
+    fn sync_fast_forwards_then_reports_up_to_date() {
+        let (_tmp, origin, clone) = setup();
+        assert_eq!(sync_repo(&clone, "main", false), SyncOutcome::UpToDate);
+
+        run(
+            &origin,
+            &["commit", "--quiet", "--allow-empty", "-m", "upstream"],
+        );
+        assert_eq!(sync_repo(&clone, "main", false), SyncOutcome::FastForwarded);
+        assert_eq!(sync_repo(&clone, "main", false), SyncOutcome::UpToDate);
+    }
+
+    #[test]
+    fn base_defaults_to_origin_head() {
+        let (tmp, origin, clone) = setup();
+        run(&origin, &["branch", "-m", "main", "trunk"]);
+        run(&clone, &["fetch", "--quiet"]);
+        run(&clone, &["remote", "set-head", "origin", "--auto"]);
+        assert_eq!(sync_base(&clone, None).as_deref(), Some("trunk"));
+        assert_eq!(sync_base(&clone, Some("dev")).as_deref(), Some("dev"));
+
+        let local = tmp.path().join("local");
+        init_repo(&local);
+        assert_eq!(sync_base(&local, None), None);
+    }
+
+    #[test]
+    fn sync_leaves_conflicts_for_the_user() {
+        let (_tmp, origin, clone) = setup();
+        std::fs::write(origin.join("f"), "theirs\n").unwrap();
+        run(&origin, &["commit", "--quiet", "-am", "theirs"]);
+        std::fs::write(clone.join("f"), "ours\n").unwrap();
+        run(&clone, &["commit", "--quiet", "-am", "ours"]);
+
+        assert_eq!(sync_repo(&clone, "main", true), SyncOutcome::Conflicted);
+        assert!(in_conflict(&clone));
+    }
+}
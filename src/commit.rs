@@ -1,8 +1,42 @@
+use crate::helpers::{shell_quote, BatchStatus, BatchSummary};
+use crate::MetaGitError;
 use console::style;
 use meta_core::config;
-use meta_plugin_protocol::{CommandResult, PlannedCommand, PluginRequestOptions};
+use meta_plugin_protocol::{CommandResult, ExecutionPlan, PlannedCommand, PluginRequestOptions};
+use rayon::prelude::*;
+use std::path::Path;
 use std::process::Command;
 
+/// Conventional Commits types accepted by `--type` without `--no-verify`.
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// The `type(scope): ` prefix from `--type`/`--scope`, checked against
+/// [`CONVENTIONAL_TYPES`] unless `no_verify`.
+pub(crate) fn conventional_prefix(
+    kind: Option<&str>,
+    scope: Option<&str>,
+    no_verify: bool,
+) -> Result<Option<String>, String> {
+    let Some(kind) = kind else {
+        return match scope {
+            Some(_) => Err("--scope requires --type".to_string()),
+            None => Ok(None),
+        };
+    };
+    if !no_verify && !CONVENTIONAL_TYPES.contains(&kind) {
+        return Err(format!(
+            "Unknown commit type '{kind}' (expected one of: {}; --no-verify allows any)",
+            CONVENTIONAL_TYPES.join(", ")
+        ));
+    }
+    Ok(Some(match scope {
+        Some(scope) => format!("{kind}({scope}): "),
+        None => format!("{kind}: "),
+    }))
+}
+
 /// Execute git commit with optional --edit flag for per-repo messages
 pub(crate) fn execute_git_commit(
     args: &[String],
@@ -12,7 +46,20 @@ pub(crate) fn execute_git_commit(
 ) -> anyhow::Result<CommandResult> {
     // Parse arguments
     let mut use_editor = false;
+    let mut all = false;
+    let mut amend = false;
+    let mut dry_run = options.dry_run;
     let mut message: Option<String> = None;
+    let mut from_file: Option<String> = None;
+    let mut commit_type: Option<String> = None;
+    let mut scope: Option<String> = None;
+    let mut no_verify = false;
+    // Flags passed through to every `git commit`, in the order given
+    let mut commit_flags: Vec<String> = Vec::new();
+    // Commit order can matter (hooks, shared lockfiles), so the -m plan stays
+    // sequential unless --parallel is given. --parallel=N caps concurrency.
+    let mut parallel = options.parallel;
+    let mut max_parallel: Option<usize> = None;
     let mut idx = 0;
 
     while idx < args.len() {
@@ -21,6 +68,30 @@ pub(crate) fn execute_git_commit(
                 use_editor = true;
                 idx += 1;
             }
+            "--amend" => {
+                amend = true;
+                commit_flags.push("--amend".to_string());
+                idx += 1;
+            }
+            "--all" | "-a" => {
+                all = true;
+                commit_flags.push("-a".to_string());
+                idx += 1;
+            }
+            s if s == "-S"
+                || s == "--gpg-sign"
+                || s.starts_with("--gpg-sign=")
+                || (s.starts_with("-S") && s.len() > 2)
+                || s == "--signoff"
+                || s == "-s" =>
+            {
+                commit_flags.push(s.to_string());
+                idx += 1;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                idx += 1;
+            }
             "-m" | "--message" => {
                 if idx + 1 < args.len() {
                     message = Some(args[idx + 1].clone());
@@ -29,11 +100,66 @@ pub(crate) fn execute_git_commit(
                     idx += 1;
                 }
             }
+            "--from-file" => {
+                let Some(path) = args.get(idx + 1) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--from-file requires a path ('-' reads stdin)".to_string(),
+                    )
+                    .into());
+                };
+                from_file = Some(path.clone());
+                idx += 2;
+            }
+            "--type" | "--scope" => {
+                let flag = args[idx].as_str();
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(
+                        MetaGitError::InvalidArgument(format!("{flag} requires a value")).into(),
+                    );
+                };
+                if flag == "--type" {
+                    commit_type = Some(value.clone());
+                } else {
+                    scope = Some(value.clone());
+                }
+                idx += 2;
+            }
+            "--no-verify" => {
+                // Also relaxes --type checking
+                no_verify = true;
+                commit_flags.push("--no-verify".to_string());
+                idx += 1;
+            }
+            "--parallel" => {
+                parallel = true;
+                idx += 1;
+            }
+            s if s.starts_with("--parallel=") => {
+                let value = &s["--parallel=".len()..];
+                match value.parse::<usize>() {
+                    Ok(n) if n >= 1 => {
+                        parallel = true;
+                        max_parallel = Some(n);
+                    }
+                    _ => {
+                        return Err(MetaGitError::InvalidArgument(format!(
+                            "Invalid --parallel value '{value}' (expected a number >= 1)"
+                        ))
+                        .into());
+                    }
+                }
+                idx += 1;
+            }
             // Skip other args like "git", "commit"
             _ => idx += 1,
         }
     }
 
+    let prefix = match conventional_prefix(commit_type.as_deref(), scope.as_deref(), no_verify) {
+        Ok(prefix) => prefix,
+        Err(e) => return Err(MetaGitError::InvalidArgument(e).into()),
+    };
+
     // Get list of directories to check for staged changes
     let dirs_to_check: Vec<String> = if !projects.is_empty() {
         // Use projects from meta_cli (supports --recursive)
@@ -53,35 +179,73 @@ pub(crate) fn execute_git_commit(
         dirs
     };
 
-    // Find repos with staged changes
-    let mut repos_with_changes: Vec<(String, String, Vec<String>)> = Vec::new();
+    // Find repos with staged changes (with -a, tracked modifications count too)
+    let mut repos_with_changes = find_repos_with_staged_changes(&dirs_to_check, cwd, all);
+    if amend {
+        // Nothing to amend before the first commit
+        repos_with_changes.retain(|(name, path, _)| {
+            let has_head = has_commits(path);
+            if !has_head {
+                eprintln!(
+                    "{} {} has no commits yet, not amending it",
+                    style("⚠").yellow(),
+                    name
+                );
+            }
+            has_head
+        });
+    }
 
-    for dir in &dirs_to_check {
-        let path = if dir == "." {
-            cwd.to_path_buf()
+    if repos_with_changes.is_empty() {
+        return Ok(CommandResult::Message(if all {
+            "No staged or tracked changes found in any repository.".to_string()
         } else {
-            cwd.join(dir)
-        };
-
-        if path.exists() && has_staged_changes(&path.to_string_lossy()) {
-            let staged = get_staged_files(&path.to_string_lossy());
-            repos_with_changes.push((dir.clone(), path.to_string_lossy().to_string(), staged));
-        }
+            "No staged changes found in any repository.".to_string()
+        }));
     }
 
-    if repos_with_changes.is_empty() {
-        return Ok(CommandResult::Message(
-            "No staged changes found in any repository.".to_string(),
-        ));
+    if let Some(path) = from_file {
+        // Same per-repo format as --edit, prepared ahead of time
+        let content = if path == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(cwd.join(&path))
+                .map_err(|e| anyhow::anyhow!("Failed to read {path}: {e}"))?
+        };
+        let (commits, unmatched) =
+            match_commits_to_staged(&repos_with_changes, parse_multi_commit_file(&content));
+        for repo in &unmatched {
+            eprintln!(
+                "{} Section '{}' in {path} matches no repo with staged changes, skipping",
+                style("⚠").yellow(),
+                repo
+            );
+        }
+        if commits.is_empty() {
+            println!("No commits to make (no section matches a repo with staged changes).");
+            return Ok(CommandResult::Message(String::new()));
+        }
+        return apply_multi_commits(&repos_with_changes, &commits, dry_run, &commit_flags);
     }
 
     if use_editor {
         // Open editor for per-repo messages (interactive, cannot use ExecutionPlan)
-        execute_editor_commit(&repos_with_changes)?;
+        return execute_editor_commit(
+            &repos_with_changes,
+            dry_run,
+            all,
+            prefix.as_deref(),
+            &commit_flags,
+        );
     } else if let Some(msg) = message {
+        let msg = format!("{}{msg}", prefix.unwrap_or_default());
         // Apply same message to all repos - use ExecutionPlan for proper dry-run support
         // Escape the message for shell (replace single quotes)
         let escaped_msg = msg.replace('\'', "'\\''");
+        let flags: String = commit_flags
+            .iter()
+            .map(|flag| format!(" {}", shell_quote(flag)))
+            .collect();
         let commands: Vec<PlannedCommand> = repos_with_changes
             .iter()
             .map(|(name, path, _files)| {
@@ -93,13 +257,45 @@ pub(crate) fn execute_git_commit(
                 };
                 PlannedCommand {
                     dir,
-                    cmd: format!("git commit -m '{escaped_msg}'"),
+                    cmd: format!("git commit{flags} -m '{escaped_msg}'"),
                     env: None,
                 }
             })
             .collect();
 
-        return Ok(CommandResult::Plan(commands, Some(options.parallel)));
+        if !parallel {
+            return Ok(CommandResult::Plan(commands, Some(false)));
+        }
+
+        // Bounded pool: loop_lib runs at most `max_parallel` commits at once and
+        // still prefixes each repo's output with its directory.
+        return Ok(CommandResult::FullPlan(ExecutionPlan {
+            pre_commands: vec![],
+            commands,
+            post_commands: vec![],
+            parallel: Some(true),
+            max_parallel: Some(max_parallel.unwrap_or_else(crate::user_config::default_jobs)),
+            spawn_stagger_ms: None,
+        }));
+    } else if amend {
+        // --amend without a new message keeps each repo's existing one
+        let flags: String = commit_flags
+            .iter()
+            .map(|flag| format!(" {}", shell_quote(flag)))
+            .collect();
+        let commands: Vec<PlannedCommand> = repos_with_changes
+            .iter()
+            .map(|(name, path, _files)| PlannedCommand {
+                dir: if name == "." {
+                    ".".to_string()
+                } else {
+                    path.clone()
+                },
+                cmd: format!("git commit{flags} --no-edit"),
+                env: None,
+            })
+            .collect();
+        return Ok(CommandResult::Plan(commands, Some(false)));
     } else {
         // No message provided, show what would be committed
         println!("Repositories with staged changes:");
@@ -115,11 +311,58 @@ pub(crate) fn execute_git_commit(
             "Use {} to apply the same message to all",
             style("-m \"message\"").yellow()
         );
+        println!(
+            "Use {} to read per-repo messages from a file",
+            style("--from-file <path>").yellow()
+        );
     }
 
     Ok(CommandResult::Message(String::new()))
 }
 
+/// Scan `dirs` for staged changes in parallel, one git process per repo.
+/// With `all` (`commit -a`), unstaged modifications to tracked files count
+/// as well; untracked files never do.
+///
+/// Returns (dir, absolute path, files to commit) tuples sorted by dir so the
+/// display order doesn't depend on scheduling.
+pub(crate) fn find_repos_with_staged_changes(
+    dirs: &[String],
+    cwd: &Path,
+    all: bool,
+) -> Vec<(String, String, Vec<String>)> {
+    let mut repos: Vec<(String, String, Vec<String>)> = dirs
+        .par_iter()
+        .filter_map(|dir| {
+            let path = if dir == "." {
+                cwd.to_path_buf()
+            } else {
+                cwd.join(dir)
+            };
+            let path_str = path.to_string_lossy().to_string();
+            if !path.exists() {
+                return None;
+            }
+            let staged = has_staged_changes(&path_str);
+            let modified = all && has_tracked_modifications(&path_str);
+            if !staged && !modified {
+                return None;
+            }
+            let mut files = get_staged_files(&path_str);
+            if modified {
+                for file in get_modified_files(&path_str) {
+                    if !files.contains(&file) {
+                        files.push(file);
+                    }
+                }
+            }
+            Some((dir.clone(), path_str, files))
+        })
+        .collect();
+    repos.sort_by(|a, b| a.0.cmp(&b.0));
+    repos
+}
+
 /// Check if a repo has staged changes
 fn has_staged_changes(path: &str) -> bool {
     let output = Command::new("git")
@@ -134,13 +377,54 @@ fn has_staged_changes(path: &str) -> bool {
     }
 }
 
-/// Get list of staged files in a repo
-fn get_staged_files(path: &str) -> Vec<String> {
-    let output = Command::new("git")
+/// Check if a repo has at least one commit (so `--amend` has something to amend)
+fn has_commits(path: &str) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .stdout(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Short SHA of `HEAD`, for reporting what a commit produced
+fn head_short_sha(path: &str) -> Option<String> {
+    let out = Command::new("git")
         .arg("-C")
         .arg(path)
-        .args(["diff", "--cached", "--name-only"])
-        .output();
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Check if a repo has unstaged changes to tracked files
+fn has_tracked_modifications(path: &str) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["diff", "--quiet"])
+        .status()
+        .map(|status| !status.success())
+        .unwrap_or(false)
+}
+
+/// Get list of staged files in a repo
+fn get_staged_files(path: &str) -> Vec<String> {
+    list_diff_names(path, &["diff", "--cached", "--name-only"])
+}
+
+/// Get list of tracked files with unstaged changes in a repo
+fn get_modified_files(path: &str) -> Vec<String> {
+    list_diff_names(path, &["diff", "--name-only"])
+}
+
+fn list_diff_names(path: &str, args: &[&str]) -> Vec<String> {
+    let output = Command::new("git").arg("-C").arg(path).args(args).output();
 
     match output {
         Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
@@ -151,8 +435,19 @@ fn get_staged_files(path: &str) -> Vec<String> {
     }
 }
 
-/// Execute commit with editor for per-repo messages
-fn execute_editor_commit(repos: &[(String, String, Vec<String>)]) -> anyhow::Result<()> {
+/// Execute commit with editor for per-repo messages.
+///
+/// With `dry_run`, the edited file is parsed and the resulting commits are
+/// printed, but no `git commit` runs. A `prefix` from `--type`/`--scope`
+/// pre-fills every section; sections left at just the prefix are skipped.
+/// `commit_flags` are passed to every `git commit`.
+fn execute_editor_commit(
+    repos: &[(String, String, Vec<String>)],
+    dry_run: bool,
+    all: bool,
+    prefix: Option<&str>,
+    commit_flags: &[String],
+) -> anyhow::Result<CommandResult> {
     use std::io::Write;
 
     // Create temp file with commit template
@@ -167,7 +462,9 @@ fn execute_editor_commit(repos: &[(String, String, Vec<String>)]) -> anyhow::Res
         template.push_str(&format!("========== {name} ==========\n"));
         let file_count = files.len();
         let file_list = files.join(", ");
-        template.push_str(&format!("# {file_count} file(s) staged: {file_list}\n"));
+        let state = if all { "to commit" } else { "staged" };
+        template.push_str(&format!("# {file_count} file(s) {state}: {file_list}\n"));
+        template.push_str(prefix.unwrap_or_default());
         template.push('\n');
         template.push_str("# Enter commit message above this line\n\n");
     }
@@ -184,36 +481,64 @@ fn execute_editor_commit(repos: &[(String, String, Vec<String>)]) -> anyhow::Res
         .or_else(|_| std::env::var("VISUAL"))
         .unwrap_or_else(|_| "vi".to_string());
 
-    // Open editor
-    let status = Command::new(&editor).arg(&temp_file).status()?;
-
-    if !status.success() {
-        anyhow::bail!("Editor exited with non-zero status");
-    }
-
-    // Read and parse the edited file
-    let content = std::fs::read_to_string(&temp_file)?;
-    let commits = parse_multi_commit_file(&content);
+    // Open editor and read the edited file back
+    let content = match Command::new(&editor).arg(&temp_file).status() {
+        Ok(status) if status.success() => {
+            std::fs::read_to_string(&temp_file).map_err(anyhow::Error::from)
+        }
+        Ok(_) => Err(anyhow::anyhow!("Editor exited with non-zero status")),
+        Err(e) => Err(e.into()),
+    };
 
-    // Clean up temp file
+    // Clean up temp file, whether or not editing worked (dry runs included)
     let _ = std::fs::remove_file(&temp_file);
 
+    let mut commits = parse_multi_commit_file(&content?);
+    if let Some(prefix) = prefix {
+        commits.retain(|(_, message)| message != prefix.trim_end());
+    }
+
     if commits.is_empty() {
         println!("No commits to make (all messages were empty or deleted).");
-        return Ok(());
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    apply_multi_commits(repos, &commits, dry_run, commit_flags)
+}
+
+/// Split parsed `(repo, message)` sections into those naming a repo with
+/// staged changes and the names of the ones that don't.
+pub(crate) fn match_commits_to_staged(
+    repos: &[(String, String, Vec<String>)],
+    commits: Vec<(String, String)>,
+) -> (Vec<(String, String)>, Vec<String>) {
+    let (matched, unmatched): (Vec<_>, Vec<_>) = commits
+        .into_iter()
+        .partition(|(repo, _)| repos.iter().any(|(name, _, _)| name == repo));
+    (
+        matched,
+        unmatched.into_iter().map(|(repo, _)| repo).collect(),
+    )
+}
+
+/// Commit each `(repo, message)` pair from `--edit` or `--from-file`, passing
+/// `commit_flags` (`-a`, `-S`, `--no-verify`, ...) to each `git commit`.
+fn apply_multi_commits(
+    repos: &[(String, String, Vec<String>)],
+    commits: &[(String, String)],
+    dry_run: bool,
+    commit_flags: &[String],
+) -> anyhow::Result<CommandResult> {
+    if dry_run {
+        print_editor_commit_plan(repos, commits, commit_flags);
+        return Ok(CommandResult::Message(String::new()));
     }
 
     // Execute commits
-    let mut succeeded = 0;
-    let mut failed = 0;
+    let mut summary = BatchSummary::default();
 
-    for (repo_name, message) in &commits {
-        // Find the path for this repo
-        let path = repos
-            .iter()
-            .find(|(name, _, _)| name == repo_name)
-            .map(|(_, path, _)| path.as_str())
-            .unwrap_or(repo_name);
+    for (repo_name, message) in commits {
+        let path = repo_path(repos, repo_name);
 
         println!(
             "{} Committing {}...",
@@ -225,38 +550,86 @@ fn execute_editor_commit(repos: &[(String, String, Vec<String>)]) -> anyhow::Res
             .arg("-C")
             .arg(path)
             .arg("commit")
+            .args(commit_flags)
             .arg("-m")
             .arg(message)
             .status();
 
         match status {
             Ok(s) if s.success() => {
+                let sha = head_short_sha(path);
                 println!(
-                    "  {} {}",
+                    "  {} {} {}",
                     style("✓").green(),
+                    style(sha.as_deref().unwrap_or("")).dim(),
                     message.lines().next().unwrap_or("")
                 );
-                succeeded += 1;
+                summary.push(repo_name, BatchStatus::Ok, Some(sha));
             }
             _ => {
                 println!("  {} Failed to commit", style("✗").red());
-                failed += 1;
+                summary.push(repo_name, BatchStatus::Failed, None);
             }
         }
     }
 
     println!();
-    if failed > 0 {
+    summary.finish("Committed", false)
+}
+
+/// The path to commit in for `repo_name`, falling back to the name itself
+fn repo_path<'a>(repos: &'a [(String, String, Vec<String>)], repo_name: &'a str) -> &'a str {
+    repos
+        .iter()
+        .find(|(name, _, _)| name == repo_name)
+        .map(|(_, path, _)| path.as_str())
+        .unwrap_or(repo_name)
+}
+
+/// The `git -C <path> commit ...` line a per-repo commit runs, shell-quoted
+pub(crate) fn commit_command_line(path: &str, commit_flags: &[String], message: &str) -> String {
+    let mut line = format!("git -C {} commit", shell_quote(path));
+    for flag in commit_flags {
+        line.push(' ');
+        line.push_str(&shell_quote(flag));
+    }
+    line.push_str(" -m ");
+    line.push_str(&shell_quote(message));
+    line
+}
+
+/// Show the commands an `--edit` session would run, including repos left without a message.
+fn print_editor_commit_plan(
+    repos: &[(String, String, Vec<String>)],
+    commits: &[(String, String)],
+    commit_flags: &[String],
+) {
+    println!(
+        "{} Would run {} commit(s):",
+        style("[DRY RUN]").cyan(),
+        commits.len()
+    );
+    for (repo_name, message) in commits {
+        println!();
+        println!("  {}", style(repo_name).bold());
         println!(
-            "Committed {} repo(s), {} failed",
-            style(succeeded).green(),
-            style(failed).red()
+            "    {}",
+            commit_command_line(repo_path(repos, repo_name), commit_flags, message)
         );
-    } else {
-        println!("Committed {} repo(s)", style(succeeded).green());
     }
 
-    Ok(())
+    let skipped: Vec<&str> = repos
+        .iter()
+        .map(|(name, _, _)| name.as_str())
+        .filter(|name| !commits.iter().any(|(repo, _)| repo == name))
+        .collect();
+    if !skipped.is_empty() {
+        println!();
+        println!(
+            "Skipped (empty or deleted section): {}",
+            style(skipped.join(", ")).yellow()
+        );
+    }
 }
 
 /// Parse the multi-commit file content
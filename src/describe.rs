@@ -0,0 +1,107 @@
+//! `meta git describe` - a version fingerprint of every repo in the workspace.
+//!
+//! Runs `git describe --tags --always` per repo, so untagged repos fall back
+//! to their abbreviated commit instead of failing.
+
+use crate::helpers::get_project_directories_with_fallback;
+use console::style;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Serialize)]
+struct DescribeEntry {
+    dir: String,
+    /// `git describe` output, or `None` when git failed (e.g. no commits yet)
+    description: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DescribeOutput {
+    repos: Vec<DescribeEntry>,
+}
+
+pub(crate) fn execute_git_describe(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let dirty = args.iter().any(|a| a == "--dirty");
+
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+    let repos: Vec<DescribeEntry> = dirs
+        .par_iter()
+        .map(|dir| DescribeEntry {
+            dir: dir.clone(),
+            description: describe(&cwd.join(dir), dirty),
+        })
+        .collect();
+
+    if options.json_output {
+        let output = DescribeOutput { repos };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    let width = repos.iter().map(|r| r.dir.len()).max().unwrap_or(0);
+    for repo in &repos {
+        match &repo.description {
+            Some(desc) => println!("{:width$}  {}", style(&repo.dir).bold(), desc),
+            None => println!(
+                "{:width$}  {}",
+                style(&repo.dir).bold(),
+                style("(no commits)").dim()
+            ),
+        }
+    }
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+/// `git describe --tags --always`, with a `-dirty` suffix when requested.
+fn describe(repo: &Path, dirty: bool) -> Option<String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C")
+        .arg(repo)
+        .args(["describe", "--tags", "--always"]);
+    if dirty {
+        cmd.arg("--dirty");
+    }
+    let out = cmd.output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn describe_falls_back_to_short_sha_and_marks_dirty() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        std::fs::write(dir.path().join("file"), "one").unwrap();
+        git(dir.path(), &["add", "file"]);
+        git(dir.path(), &["commit", "--quiet", "-m", "base"]);
+
+        let untagged = describe(dir.path(), false).unwrap();
+        assert!(untagged.chars().all(|c| c.is_ascii_hexdigit()));
+
+        git(dir.path(), &["tag", "v1.0.0"]);
+        git(
+            dir.path(),
+            &["commit", "--quiet", "--allow-empty", "-m", "next"],
+        );
+        let tagged = describe(dir.path(), false).unwrap();
+        assert!(tagged.starts_with("v1.0.0-1-g"), "{tagged}");
+
+        std::fs::write(dir.path().join("file"), "two").unwrap();
+        assert!(describe(dir.path(), true).unwrap().ends_with("-dirty"));
+    }
+}
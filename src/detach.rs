@@ -0,0 +1,189 @@
+//! `meta git switch-detach` / `meta git reattach` - park every repo on a
+//! detached HEAD and later return each to its branch.
+//!
+//! Detaching keeps branch refs from moving during coordinated rewrites.
+//! Reattaching picks the branch pointing at the current commit; when several
+//! do, the branch checked out just before detaching (`@{-1}`) breaks the tie.
+
+use crate::git_env;
+use crate::helpers::{get_project_directories_with_fallback, git_line, git_output};
+use crate::MetaGitError;
+use console::style;
+use meta_plugin_protocol::{CommandResult, PlannedCommand, PluginRequestOptions};
+use rayon::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+pub(crate) fn execute_git_switch_detach(
+    projects: &[String],
+    parallel: bool,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+    let git_env = Some(git_env::git_env());
+
+    // Plain plan: loop_lib handles dry-run, parallelism and per-repo output
+    let commands: Vec<PlannedCommand> = dirs
+        .into_iter()
+        .map(|dir| PlannedCommand {
+            dir,
+            cmd: "git checkout --detach".to_string(),
+            env: git_env.clone(),
+        })
+        .collect();
+
+    Ok(CommandResult::Plan(commands, Some(parallel)))
+}
+
+/// What `reattach` decided for one repo.
+enum Reattach {
+    AlreadyAttached(String),
+    Switch(String),
+    NoBranch(String),
+    Ambiguous(String, Vec<String>),
+}
+
+pub(crate) fn execute_git_reattach(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let dry_run = options.dry_run || args.iter().any(|a| a == "--dry-run");
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+
+    let plans: Vec<(String, Reattach)> = dirs
+        .par_iter()
+        .map(|dir| (dir.clone(), plan_reattach(&cwd.join(dir))))
+        .collect();
+
+    let mut unresolved = 0;
+    for (dir, plan) in &plans {
+        match plan {
+            Reattach::AlreadyAttached(branch) => {
+                println!(
+                    "  {} {} {}",
+                    style("-").dim(),
+                    style(dir).bold(),
+                    style(format!("(already on {branch})")).dim()
+                );
+            }
+            Reattach::Switch(branch) if dry_run => {
+                println!(
+                    "  {} {}: git switch {branch}",
+                    style("[DRY RUN]").cyan(),
+                    style(dir).bold()
+                );
+            }
+            Reattach::Switch(branch) => {
+                let ok = Command::new("git")
+                    .arg("-C")
+                    .arg(cwd.join(dir))
+                    .args(["switch", "--quiet", branch])
+                    .status()
+                    .map(|s| s.success())
+                    .unwrap_or(false);
+                if ok {
+                    println!("  {} {} -> {branch}", style("✓").green(), style(dir).bold());
+                } else {
+                    unresolved += 1;
+                    println!(
+                        "  {} {}: git switch {branch} failed",
+                        style("✗").red(),
+                        style(dir).bold()
+                    );
+                }
+            }
+            Reattach::NoBranch(sha) => {
+                unresolved += 1;
+                println!(
+                    "  {} {}: no local branch points at {sha}",
+                    style("⚠").yellow(),
+                    style(dir).bold()
+                );
+            }
+            Reattach::Ambiguous(sha, branches) => {
+                unresolved += 1;
+                println!(
+                    "  {} {}: ambiguous, {} branches point at {sha}: {}",
+                    style("⚠").yellow(),
+                    style(dir).bold(),
+                    branches.len(),
+                    branches.join(", ")
+                );
+            }
+        }
+    }
+
+    if unresolved > 0 {
+        return Err(MetaGitError::GitFailed(format!(
+            "{unresolved} repo(s) could not be reattached; switch them manually"
+        ))
+        .into());
+    }
+    Ok(CommandResult::Message(String::new()))
+}
+
+fn plan_reattach(path: &Path) -> Reattach {
+    if let Some(branch) = git_line(path, &["symbolic-ref", "--quiet", "--short", "HEAD"]) {
+        return Reattach::AlreadyAttached(branch);
+    }
+
+    let sha = git_line(path, &["rev-parse", "--short", "HEAD"]).unwrap_or_default();
+    let candidates: Vec<String> = git_output(
+        path,
+        &[
+            "for-each-ref",
+            "--points-at",
+            "HEAD",
+            "--format=%(refname:short)",
+            "refs/heads",
+        ],
+    )
+    .map(|out| out.lines().map(str::to_string).collect())
+    .unwrap_or_default();
+    // The branch checked out before `switch-detach`, if the reflog remembers it
+    let previous = git_line(path, &["rev-parse", "--abbrev-ref", "@{-1}"]);
+
+    choose_branch(sha, candidates, previous.as_deref())
+}
+
+fn choose_branch(sha: String, candidates: Vec<String>, previous: Option<&str>) -> Reattach {
+    match candidates.len() {
+        0 => Reattach::NoBranch(sha),
+        1 => Reattach::Switch(candidates.into_iter().next().unwrap()),
+        _ => match previous.filter(|p| candidates.iter().any(|c| c == p)) {
+            Some(prev) => Reattach::Switch(prev.to_string()),
+            None => Reattach::Ambiguous(sha, candidates),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn single_candidate_is_chosen() {
+        let plan = choose_branch("abc123".into(), names(&["main"]), None);
+        assert!(matches!(plan, Reattach::Switch(b) if b == "main"));
+    }
+
+    #[test]
+    fn previous_branch_breaks_ties() {
+        let plan = choose_branch("abc123".into(), names(&["main", "feat"]), Some("feat"));
+        assert!(matches!(plan, Reattach::Switch(b) if b == "feat"));
+    }
+
+    #[test]
+    fn unresolvable_cases_are_reported() {
+        let plan = choose_branch("abc123".into(), names(&["main", "feat"]), Some("other"));
+        assert!(matches!(plan, Reattach::Ambiguous(_, b) if b.len() == 2));
+        let plan = choose_branch("abc123".into(), vec![], Some("main"));
+        assert!(matches!(plan, Reattach::NoBranch(sha) if sha == "abc123"));
+    }
+}
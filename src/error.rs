@@ -0,0 +1,132 @@
+//! Structured error codes for machine-readable (`--json`) output.
+//!
+//! Commands pick the category where the failure happens, by returning a
+//! `MetaGitError` (usually through `anyhow`). `execute_command` renders it as
+//! the plain message, or in JSON mode as
+//! `{"error": {"code": ..., "message": ...}}` so the meta CLI frontend can
+//! render errors consistently. Errors nobody categorized (from meta_git_lib,
+//! I/O, ...) are reported as `error`.
+
+use meta_plugin_protocol::CommandResult;
+use serde_json::json;
+
+/// Error categories reported in JSON error output.
+#[derive(Debug, thiserror::Error)]
+pub enum MetaGitError {
+    /// No `.meta` config found where one was required.
+    #[error("{0}")]
+    NotAMetaRepo(String),
+    /// A named worktree, snapshot, repo or project does not exist.
+    #[error("{0}")]
+    NotFound(String),
+    /// Bad or conflicting command-line arguments.
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// An underlying git invocation failed.
+    #[error("{0}")]
+    GitFailed(String),
+    /// Anything that doesn't fit a more specific category.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl MetaGitError {
+    /// The category `error` was raised with, or `Other` when it carries none.
+    ///
+    /// The message is `error`'s own, so context added on the way up is kept.
+    pub fn from_anyhow(error: &anyhow::Error) -> Self {
+        let message = error.to_string();
+        match error.downcast_ref::<MetaGitError>() {
+            Some(MetaGitError::NotAMetaRepo(_)) => MetaGitError::NotAMetaRepo(message),
+            Some(MetaGitError::NotFound(_)) => MetaGitError::NotFound(message),
+            Some(MetaGitError::InvalidArgument(_)) => MetaGitError::InvalidArgument(message),
+            Some(MetaGitError::GitFailed(_)) => MetaGitError::GitFailed(message),
+            Some(MetaGitError::Other(_)) | None => MetaGitError::Other(message),
+        }
+    }
+
+    /// Stable machine-readable code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MetaGitError::NotAMetaRepo(_) => "not_a_meta_repo",
+            MetaGitError::NotFound(_) => "not_found",
+            MetaGitError::InvalidArgument(_) => "invalid_argument",
+            MetaGitError::GitFailed(_) => "git_failed",
+            MetaGitError::Other(_) => "error",
+        }
+    }
+
+    /// Render as `{"error": {"code": ..., "message": ...}}`.
+    pub fn to_json(&self) -> String {
+        json!({
+            "error": {
+                "code": self.code(),
+                "message": self.to_string(),
+            }
+        })
+        .to_string()
+    }
+
+    /// The failed command's result: the message, or with `json` the JSON
+    /// document.
+    pub fn into_result(self, json: bool) -> CommandResult {
+        if json {
+            CommandResult::Error(self.to_json())
+        } else {
+            CommandResult::Error(self.to_string())
+        }
+    }
+}
+
+/// Give errors from meta_git_lib, which carry no category, the one the call
+/// site knows they mean.
+pub(crate) trait Categorize<T> {
+    fn categorize(self, kind: fn(String) -> MetaGitError) -> anyhow::Result<T>;
+}
+
+impl<T> Categorize<T> for anyhow::Result<T> {
+    fn categorize(self, kind: fn(String) -> MetaGitError) -> anyhow::Result<T> {
+        self.map_err(|e| kind(e.to_string()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn category_survives_anyhow_and_context() {
+        let error: anyhow::Error = MetaGitError::NotFound("Snapshot 'x' not found".into()).into();
+        assert_eq!(MetaGitError::from_anyhow(&error).code(), "not_found");
+
+        let wrapped = Err::<(), _>(error).context("Restore failed").unwrap_err();
+        let categorized = MetaGitError::from_anyhow(&wrapped);
+        assert_eq!(categorized.code(), "not_found");
+        assert_eq!(categorized.to_string(), "Restore failed");
+    }
+
+    #[test]
+    fn uncategorized_errors_are_other_whatever_they_say() {
+        let error = anyhow::anyhow!("Worktree 'x' not found");
+        assert_eq!(MetaGitError::from_anyhow(&error).code(), "error");
+
+        let result: anyhow::Result<()> = Err(error);
+        let error = result.categorize(MetaGitError::NotFound).unwrap_err();
+        assert_eq!(MetaGitError::from_anyhow(&error).code(), "not_found");
+    }
+
+    #[test]
+    fn json_wraps_code_and_message() {
+        let result = MetaGitError::NotFound("Snapshot 'before' not found".into()).into_result(true);
+        let CommandResult::Error(rendered) = result else {
+            panic!("not an error");
+        };
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["error"]["code"], "not_found");
+        assert_eq!(value["error"]["message"], "Snapshot 'before' not found");
+
+        let result = MetaGitError::InvalidArgument("bad".into()).into_result(false);
+        assert!(matches!(result, CommandResult::Error(m) if m == "bad"));
+    }
+}
@@ -0,0 +1,103 @@
+//! `meta git fetch` - refresh remote-tracking branches everywhere, touching no working tree.
+
+use crate::git_env;
+use crate::helpers::{get_all_repo_directories, shell_quote};
+use meta_plugin_protocol::{CommandResult, PlannedCommand};
+use std::path::Path;
+
+/// `git fetch` options that take their value as the next arg.
+const VALUE_FLAGS: &[&str] = &[
+    "--depth",
+    "--deepen",
+    "--shallow-since",
+    "--shallow-exclude",
+    "--refmap",
+    "--negotiation-tip",
+    "--upload-pack",
+    "-j",
+    "--jobs",
+    "-o",
+    "--server-option",
+];
+
+/// Plan `git fetch --all --prune [<flags>]` in every repo, nested ones included.
+///
+/// Flags (`--tags`, `--no-tags`, `--depth 1`, ...) are forwarded. Naming a
+/// remote or refspec (`meta git fetch origin main`) runs `git fetch <args>`
+/// as given instead, since `--all` can't be combined with one. Returned as a
+/// plan so loop_lib handles execution, dry-run and JSON output.
+pub(crate) fn execute_git_fetch(
+    args: &[String],
+    projects: &[String],
+    parallel: bool,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let mut cmd = if names_remote(args) {
+        "git fetch".to_string()
+    } else {
+        "git fetch --all --prune".to_string()
+    };
+    for arg in args {
+        cmd.push(' ');
+        cmd.push_str(&shell_quote(arg));
+    }
+
+    let git_env = Some(git_env::git_env());
+    let commands: Vec<PlannedCommand> = get_all_repo_directories(projects, cwd)?
+        .into_iter()
+        .map(|dir| PlannedCommand {
+            dir,
+            cmd: cmd.clone(),
+            env: git_env.clone(),
+        })
+        .collect();
+
+    Ok(crate::remote_plan(commands, parallel, cwd))
+}
+
+/// Whether `args` include a positional remote or refspec.
+fn names_remote(args: &[String]) -> bool {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(args: &[&str]) -> Vec<PlannedCommand> {
+        let projects = vec![".".to_string(), "api".to_string()];
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let result = execute_git_fetch(&args, &projects, false, Path::new("/ws")).unwrap();
+        let CommandResult::Plan(commands, _) = result else {
+            panic!("expected a plan");
+        };
+        commands
+    }
+
+    #[test]
+    fn fetch_plans_every_repo_with_tags_passthrough() {
+        let commands = plan(&["--tags"]);
+        let dirs: Vec<&str> = commands.iter().map(|c| c.dir.as_str()).collect();
+        assert_eq!(dirs, vec![".", "api"]);
+        assert!(commands
+            .iter()
+            .all(|c| c.cmd == "git fetch --all --prune --tags"));
+    }
+
+    #[test]
+    fn fetch_forwards_flags_and_explicit_remotes() {
+        assert_eq!(
+            plan(&["--depth", "1", "--no-tags"])[0].cmd,
+            "git fetch --all --prune --depth 1 --no-tags"
+        );
+        assert_eq!(plan(&["origin", "main"])[0].cmd, "git fetch origin main");
+    }
+}
@@ -0,0 +1,310 @@
+//! `meta git fix-remotes` - reconcile child repo remotes with `.meta`.
+//!
+//! Each project in `.meta` declares a single `repo` URL, which maps to the
+//! `origin` remote of the checked-out repo. This command adds `origin` where
+//! it is missing and rewrites it where it points somewhere else. Repos whose
+//! `origin` URL can't be read are reported as skipped rather than touched.
+
+use crate::helpers::{BatchStatus, BatchSummary};
+use console::style;
+use dialoguer::Confirm;
+use meta_core::config;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Remote name that `.meta` project URLs are reconciled against.
+const REMOTE_NAME: &str = "origin";
+
+/// A single remote change needed to bring a repo in line with `.meta`.
+struct RemoteFix {
+    /// Path relative to cwd, for display and `git -C`.
+    display_path: String,
+    repo_path: PathBuf,
+    expected: String,
+    /// Current URL, or `None` when the remote doesn't exist yet.
+    actual: Option<String>,
+}
+
+impl RemoteFix {
+    fn git_args(&self) -> Vec<&str> {
+        match self.actual {
+            Some(_) => vec!["remote", "set-url", REMOTE_NAME, &self.expected],
+            None => vec!["remote", "add", REMOTE_NAME, &self.expected],
+        }
+    }
+
+    /// Run the fix, returning git's exit status or spawn error as the failure.
+    fn apply(&self) -> Result<(), String> {
+        match Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_path)
+            .args(self.git_args())
+            .status()
+        {
+            Ok(s) if s.success() => Ok(()),
+            Ok(s) => Err(s.to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// A repo whose remote couldn't be checked, with the reason.
+struct SkippedRepo {
+    display_path: String,
+    reason: String,
+}
+
+/// What one meta root needs: fixes to apply and repos left alone.
+#[derive(Default)]
+struct RemoteScan {
+    fixes: Vec<RemoteFix>,
+    skipped: Vec<SkippedRepo>,
+}
+
+pub(crate) fn execute_git_fix_remotes(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let mut dry_run = options.dry_run;
+    let mut yes = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--yes" | "-y" => yes = true,
+            _ => {}
+        }
+    }
+
+    // Same meta-root discovery as update: nested .meta dirs in recursive mode
+    let meta_roots: Vec<PathBuf> = if projects.is_empty() {
+        vec![cwd.to_path_buf()]
+    } else {
+        projects
+            .iter()
+            .map(|p| {
+                if p == "." {
+                    cwd.to_path_buf()
+                } else {
+                    cwd.join(p)
+                }
+            })
+            .filter(|path| config::find_meta_config_in(path).is_some())
+            .collect()
+    };
+
+    let mut fixes = Vec::new();
+    let mut skipped = Vec::new();
+    for root in &meta_roots {
+        let scan = find_remote_fixes(root, cwd)?;
+        fixes.extend(scan.fixes);
+        skipped.extend(scan.skipped);
+    }
+
+    for skip in &skipped {
+        println!(
+            "{} {}: {}, skipped",
+            style("⚠").yellow(),
+            skip.display_path,
+            skip.reason
+        );
+    }
+
+    if fixes.is_empty() {
+        if skipped.is_empty() {
+            println!("{} All remotes match .meta", style("✓").green());
+        } else {
+            println!("{} All other remotes match .meta", style("✓").green());
+        }
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    println!(
+        "Found {} remote{} to fix:",
+        fixes.len(),
+        if fixes.len() == 1 { "" } else { "s" }
+    );
+    for fix in &fixes {
+        match &fix.actual {
+            Some(actual) => {
+                println!("  {}", style(&fix.display_path).bold());
+                println!("    actual:   {}", style(actual).red());
+                println!("    expected: {}", style(&fix.expected).green());
+            }
+            None => {
+                println!(
+                    "  {} (no '{REMOTE_NAME}' remote, will add {})",
+                    style(&fix.display_path).bold(),
+                    style(&fix.expected).green()
+                );
+            }
+        }
+    }
+    println!();
+
+    if dry_run {
+        println!("{} Would run:", style("[DRY RUN]").cyan());
+        for fix in &fixes {
+            println!(
+                "  git -C '{}' {}",
+                fix.display_path,
+                fix.git_args().join(" ")
+            );
+        }
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    if !yes {
+        let proceed = Confirm::new()
+            .with_prompt("Apply these remote fixes?")
+            .default(false)
+            .interact()?;
+        if !proceed {
+            println!("Aborted.");
+            return Ok(CommandResult::Message(String::new()));
+        }
+    }
+
+    let mut summary = BatchSummary::default();
+    for skip in &skipped {
+        summary.push(
+            &skip.display_path,
+            BatchStatus::Skipped,
+            Some(skip.reason.clone()),
+        );
+    }
+    for fix in &fixes {
+        match fix.apply() {
+            Ok(()) => {
+                println!("  {} {}", style("✓").green(), fix.display_path);
+                summary.push(&fix.display_path, BatchStatus::Ok, None);
+            }
+            Err(e) => {
+                println!("  {} {}", style("✗").red(), fix.display_path);
+                summary.push(&fix.display_path, BatchStatus::Failed, Some(e));
+            }
+        }
+    }
+
+    println!();
+    summary.finish("Fixed remotes in", false)
+}
+
+/// Collect the remote changes needed for the projects of one meta root.
+fn find_remote_fixes(meta_root: &Path, cwd: &Path) -> anyhow::Result<RemoteScan> {
+    let mut scan = RemoteScan::default();
+    let Some((meta_path, _format)) = config::find_meta_config_in(meta_root) else {
+        return Ok(scan);
+    };
+    let (projects, _) = config::parse_meta_config(&meta_path)?;
+
+    for project in &projects {
+        let Some(expected) = &project.repo else {
+            continue;
+        };
+        let repo_path = meta_root.join(&project.path);
+        if !repo_path.join(".git").exists() {
+            continue; // Not cloned yet - `meta git update` handles that
+        }
+
+        let display_path = repo_path
+            .strip_prefix(cwd)
+            .unwrap_or(&repo_path)
+            .to_string_lossy()
+            .to_string();
+
+        let actual = if has_remote(&repo_path, REMOTE_NAME) {
+            match meta_git_lib::get_remote_url(&repo_path) {
+                Some(url) if meta_git_lib::urls_match(&url, expected) => continue,
+                Some(url) => Some(url),
+                None => {
+                    scan.skipped.push(SkippedRepo {
+                        display_path,
+                        reason: format!("could not read the '{REMOTE_NAME}' URL"),
+                    });
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        scan.fixes.push(RemoteFix {
+            display_path,
+            repo_path,
+            expected: expected.clone(),
+            actual,
+        });
+    }
+
+    Ok(scan)
+}
+
+/// Check whether a repo has a remote with the given name.
+fn has_remote(repo_path: &Path, name: &str) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .arg("remote")
+        .output()
+        .map(|out| {
+            out.status.success()
+                && String::from_utf8_lossy(&out.stdout)
+                    .lines()
+                    .any(|line| line.trim() == name)
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn fixes_wrong_and_missing_origins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let url = |name: &str| format!("https://github.com/org/{name}.git");
+        for name in ["api", "web", "docs"] {
+            init_repo(&root.join(name));
+        }
+        git(
+            &root.join("api"),
+            &["remote", "add", "origin", "https://github.com/old/api.git"],
+        );
+        git(
+            &root.join("docs"),
+            &["remote", "add", "origin", &url("docs")],
+        );
+        let projects = serde_json::json!({"projects": {"api": url("api"), "web": url("web"), "docs": url("docs")}});
+        std::fs::write(root.join(".meta"), projects.to_string()).unwrap();
+
+        let scan = find_remote_fixes(root, root).unwrap();
+        assert!(scan.skipped.is_empty());
+        let mut fixes = scan.fixes;
+        fixes.sort_by(|a, b| a.display_path.cmp(&b.display_path));
+        let found: Vec<(&str, Option<&str>)> = fixes
+            .iter()
+            .map(|f| (f.display_path.as_str(), f.actual.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("api", Some("https://github.com/old/api.git")),
+                ("web", None),
+            ]
+        );
+
+        for fix in &fixes {
+            fix.apply().unwrap();
+        }
+        for name in ["api", "web"] {
+            let actual = git(&root.join(name), &["remote", "get-url", "origin"]);
+            assert_eq!(actual.trim(), url(name));
+        }
+        assert!(find_remote_fixes(root, root).unwrap().fixes.is_empty());
+    }
+}
@@ -1,5 +1,11 @@
+use crate::MetaGitError;
+use console::style;
 use meta_core::config;
+use meta_plugin_protocol::CommandResult;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::path::Path;
+use std::sync::OnceLock;
 
 /// Get project directories - uses passed-in list if non-empty, otherwise reads local .meta
 pub(crate) fn get_project_directories_with_fallback(
@@ -44,3 +50,406 @@ pub(crate) fn get_all_repo_directories(
     dirs.extend(config::flatten_meta_tree(&tree));
     Ok(dirs)
 }
+
+/// `--repo <alias>` / `--exclude <alias>` selection, shared by the snapshot
+/// commands and `meta git push`.
+///
+/// An alias is a repo's path relative to cwd, a project name from the
+/// top-level `.meta`, or a unique trailing path segment (`core` for
+/// `libs/core`).
+#[derive(Debug, Default)]
+pub(crate) struct RepoScope {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl RepoScope {
+    /// Pull the scope flags out of `args`, returning the remaining args.
+    pub(crate) fn extract(args: &[String]) -> anyhow::Result<(Self, Vec<String>)> {
+        let mut scope = Self::default();
+        let mut rest = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let list = match arg.as_str() {
+                "--repo" => &mut scope.include,
+                "--exclude" => &mut scope.exclude,
+                _ => {
+                    rest.push(arg.clone());
+                    continue;
+                }
+            };
+            let alias = iter.next().ok_or_else(|| {
+                MetaGitError::InvalidArgument(format!("{arg} requires a repo alias"))
+            })?;
+            list.push(alias.clone());
+        }
+        Ok((scope, rest))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// The subset of `dirs` in scope; every alias must match one of `dirs`
+    /// (`place` names them in the error).
+    pub(crate) fn apply(
+        &self,
+        dirs: &[String],
+        cwd: &Path,
+        place: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        if self.is_empty() {
+            return Ok(dirs.to_vec());
+        }
+        let names = project_names(cwd);
+        let resolve = |aliases: &[String]| -> anyhow::Result<HashSet<String>> {
+            aliases
+                .iter()
+                .map(|alias| match_alias(alias, dirs, &names, place).map(str::to_string))
+                .collect()
+        };
+        let include = resolve(&self.include)?;
+        let exclude = resolve(&self.exclude)?;
+        Ok(dirs
+            .iter()
+            .filter(|dir| self.include.is_empty() || include.contains(*dir))
+            .filter(|dir| !exclude.contains(*dir))
+            .cloned()
+            .collect())
+    }
+}
+
+/// (name, path) of each project in the top-level `.meta`.
+fn project_names(cwd: &Path) -> Vec<(String, String)> {
+    let Some((meta_path, _format)) = config::find_meta_config_in(cwd) else {
+        return Vec::new();
+    };
+    config::parse_meta_config(&meta_path)
+        .map(|(projects, _)| projects.into_iter().map(|p| (p.name, p.path)).collect())
+        .unwrap_or_default()
+}
+
+/// Resolve one `--repo`/`--exclude` alias to the entry of `dirs` it names.
+fn match_alias<'a>(
+    alias: &str,
+    dirs: &'a [String],
+    names: &[(String, String)],
+    place: &str,
+) -> anyhow::Result<&'a str> {
+    let alias = alias.trim_end_matches('/');
+    let exact = dirs
+        .iter()
+        .find(|dir| *dir == alias || names.iter().any(|(n, p)| n == alias && p == *dir));
+    if let Some(dir) = exact {
+        return Ok(dir.as_str());
+    }
+
+    let suffix = format!("/{alias}");
+    let matches: Vec<&str> = dirs
+        .iter()
+        .filter(|dir| dir.ends_with(&suffix))
+        .map(String::as_str)
+        .collect();
+    match matches.as_slice() {
+        [dir] => Ok(*dir),
+        [] => Err(MetaGitError::NotFound(format!("Repo '{alias}' not found in {place}")).into()),
+        _ => Err(MetaGitError::InvalidArgument(format!(
+            "Repo alias '{alias}' is ambiguous - matches: {}. Use the full path.",
+            matches.join(", ")
+        ))
+        .into()),
+    }
+}
+
+/// Quote `value` for a POSIX shell command line, leaving plain words as-is.
+pub(crate) fn shell_quote(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ':'))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Whether `git lfs` is installed, checked once per run.
+///
+/// Shared by `meta git clone --lfs` and snapshot capture.
+pub(crate) fn git_lfs_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        std::process::Command::new("git")
+            .args(["lfs", "version"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    })
+}
+
+/// Run `git -C <dir> <args>` non-interactively: no credential prompts, no
+/// stdin. For commands whose stderr and exit status the caller reports.
+pub(crate) fn run_git(dir: &Path, args: &[&str]) -> std::io::Result<std::process::Output> {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .output()
+}
+
+/// Stdout of `git -C <dir> <args>`, or `None` when git fails.
+///
+/// The pager is disabled and stderr dropped, for read-only queries whose
+/// failure just means "no answer".
+pub(crate) fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_PAGER", "cat")
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// [`git_output`] trimmed, with empty output counted as no answer.
+pub(crate) fn git_line(dir: &Path, args: &[&str]) -> Option<String> {
+    git_output(dir, args)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// The branch origin's HEAD points at (e.g. `origin/main`), from the local
+/// `refs/remotes/origin/HEAD`; `None` when it isn't set.
+pub(crate) fn origin_default_branch(repo: &Path) -> Option<String> {
+    git_line(
+        repo,
+        &[
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ],
+    )
+}
+
+/// Commits `HEAD` has that its upstream doesn't, and the reverse, as
+/// `(ahead, behind)`; `None` when the branch has no upstream.
+///
+/// Shared by `meta git update` and `meta git worktree status`.
+pub(crate) fn git_ahead_behind(repo: &Path) -> Option<(usize, usize)> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-list", "--left-right", "--count", "@{u}...HEAD"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    parse_left_right_counts(&String::from_utf8_lossy(&out.stdout))
+        .map(|(behind, ahead)| (ahead, behind))
+}
+
+/// Parse `git rev-list --left-right --count` output (`"<left>\t<right>"`).
+fn parse_left_right_counts(output: &str) -> Option<(usize, usize)> {
+    let mut counts = output.split_whitespace().map(str::parse);
+    match (counts.next()?, counts.next()?) {
+        (Ok(left), Ok(right)) => Some((left, right)),
+        _ => None,
+    }
+}
+
+/// Outcome of one item in a batch operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BatchStatus {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BatchItem {
+    pub name: String,
+    pub status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Per-item results of a command that runs across many repos, with one
+/// human summary line, one JSON shape and one exit-code rule: any failed
+/// item fails the command.
+///
+/// ```json
+/// {"ok": 2, "skipped": 0, "failed": 1, "items": [{"name": "api", "status": "failed", "message": "..."}]}
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct BatchSummary {
+    items: Vec<BatchItem>,
+}
+
+#[derive(Serialize)]
+struct BatchSummaryJson<'a> {
+    ok: usize,
+    skipped: usize,
+    failed: usize,
+    items: &'a [BatchItem],
+}
+
+impl BatchSummary {
+    pub(crate) fn push(
+        &mut self,
+        name: impl Into<String>,
+        status: BatchStatus,
+        message: Option<String>,
+    ) {
+        self.items.push(BatchItem {
+            name: name.into(),
+            status,
+            message,
+        });
+    }
+
+    pub(crate) fn count(&self, status: BatchStatus) -> usize {
+        self.items.iter().filter(|i| i.status == status).count()
+    }
+
+    pub(crate) fn succeeded(&self) -> bool {
+        self.count(BatchStatus::Failed) == 0
+    }
+
+    /// `"<verb> 3 repo(s), 1 skipped, 2 failed"`; zero counts are left out.
+    pub(crate) fn render(&self, verb: &str) -> String {
+        let mut line = format!("{verb} {} repo(s)", self.count(BatchStatus::Ok));
+        for (status, label) in [
+            (BatchStatus::Skipped, "skipped"),
+            (BatchStatus::Failed, "failed"),
+        ] {
+            let n = self.count(status);
+            if n > 0 {
+                line.push_str(&format!(", {n} {label}"));
+            }
+        }
+        line
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&BatchSummaryJson {
+            ok: self.count(BatchStatus::Ok),
+            skipped: self.count(BatchStatus::Skipped),
+            failed: self.count(BatchStatus::Failed),
+            items: &self.items,
+        })
+    }
+
+    /// Report the batch and turn it into the command's result. With `json`
+    /// the document goes to stdout; otherwise a successful batch prints its
+    /// summary line and a failed one returns it as the error.
+    pub(crate) fn finish(&self, verb: &str, json: bool) -> anyhow::Result<CommandResult> {
+        if json {
+            println!("{}", self.to_json()?);
+        } else if self.succeeded() {
+            println!("{} {}", style("✓").green(), self.render(verb));
+        }
+        if self.succeeded() {
+            Ok(CommandResult::Message(String::new()))
+        } else {
+            Err(MetaGitError::GitFailed(self.render(verb)).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn left_right_counts_parse() {
+        assert_eq!(parse_left_right_counts("2\t5\n"), Some((2, 5)));
+        assert_eq!(parse_left_right_counts(""), None);
+        assert_eq!(parse_left_right_counts("x\t1"), None);
+    }
+
+    #[test]
+    fn scope_aliases_match_paths_names_and_suffixes() {
+        let dirs: Vec<String> = [".", "api", "libs/core", "libs/web", "apps/web"]
+            .map(String::from)
+            .to_vec();
+        let names = vec![("backend".to_string(), "api".to_string())];
+        let place = "the workspace";
+
+        assert_eq!(
+            match_alias("libs/core", &dirs, &names, place).unwrap(),
+            "libs/core"
+        );
+        assert_eq!(match_alias("backend", &dirs, &names, place).unwrap(), "api");
+        assert_eq!(
+            match_alias("core", &dirs, &names, place).unwrap(),
+            "libs/core"
+        );
+        assert!(match_alias("web", &dirs, &names, place)
+            .unwrap_err()
+            .to_string()
+            .contains("ambiguous"));
+        assert_eq!(
+            match_alias("nope", &dirs, &names, place)
+                .unwrap_err()
+                .to_string(),
+            "Repo 'nope' not found in the workspace"
+        );
+    }
+
+    #[test]
+    fn shell_quote_only_quotes_when_needed() {
+        assert_eq!(shell_quote("--no-verify"), "--no-verify");
+        assert_eq!(shell_quote("--gpg-sign=ABC123"), "'--gpg-sign=ABC123'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn batch_summary_counts_and_fails_on_any_failure() {
+        let mut summary = BatchSummary::default();
+        summary.push("api", BatchStatus::Ok, None);
+        summary.push("web", BatchStatus::Skipped, Some("no changes".into()));
+        assert!(summary.succeeded());
+        assert_eq!(
+            summary.render("Committed"),
+            "Committed 1 repo(s), 1 skipped"
+        );
+
+        summary.push("db", BatchStatus::Failed, Some("hook rejected".into()));
+        assert!(!summary.succeeded());
+        assert_eq!(
+            summary.render("Committed"),
+            "Committed 1 repo(s), 1 skipped, 1 failed"
+        );
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["items"][2]["status"], "failed");
+        assert!(json["items"][0].get("message").is_none());
+    }
+
+    #[test]
+    fn origin_default_branch_follows_origin_head() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        let clone = tmp.path().join("clone");
+        crate::test_support::init_repo(&origin);
+        crate::test_support::git(&origin, &["branch", "-m", "main", "develop"]);
+        crate::test_support::clone_repo(&origin, &clone);
+        assert_eq!(
+            origin_default_branch(&clone).as_deref(),
+            Some("origin/develop")
+        );
+        assert_eq!(origin_default_branch(&origin), None);
+    }
+}
@@ -2,19 +2,40 @@
 //!
 //! Provides git operations optimized for meta repositories.
 
+mod cherry_pick;
 mod clone;
+mod clone_events;
+mod clone_state;
 mod clone_worker;
 mod commit;
+mod describe;
+mod detach;
+mod error;
+mod fetch;
+mod fix_remotes;
 mod git_env;
 mod helpers;
+mod lock;
+mod mirror;
+mod pull;
+mod push;
 mod snapshot;
 mod ssh;
 mod ssh_setup;
 mod status;
+mod tags_list;
 mod update;
+mod url_rewrite;
+mod user_config;
 
 mod commands;
 
+#[cfg(test)]
+mod test_support;
+
+pub use error::MetaGitError;
+pub use user_config::{init_user_config, ColorMode, UserConfig};
+
 use log::debug;
 use meta_plugin_protocol::{CommandResult, PlannedCommand, PluginRequestOptions};
 use std::path::Path;
@@ -69,11 +90,29 @@ pub fn execute_command(
         return CommandResult::ShowHelp(None);
     }
 
+    // --parallel / --sequential beat `parallel` from the user config
+    let parallel = user_config::parallel_or(user_config::cli_parallel(options.parallel, args));
+    let args: Vec<String> = args
+        .iter()
+        .filter(|a| *a != "--sequential")
+        .cloned()
+        .collect();
+    let args = &args[..];
+
     let result = match command {
-        "git status" => status::execute_git_status(projects, options, cwd),
+        "git status" => status::execute_git_status(args, projects, options, parallel, cwd),
         "git clone" => clone::execute_git_clone(args, options, cwd),
-        "git update" => update::execute_git_update(projects, options.dry_run, cwd),
+        "git update" => update::execute_git_update(args, projects, options.dry_run, cwd),
         "git commit" => commit::execute_git_commit(args, projects, options, cwd),
+        "git fix-remotes" => fix_remotes::execute_git_fix_remotes(args, projects, options, cwd),
+        "git switch-detach" => detach::execute_git_switch_detach(projects, parallel, cwd),
+        "git reattach" => detach::execute_git_reattach(args, projects, options, cwd),
+        "git cherry-pick" => cherry_pick::execute_git_cherry_pick(args, projects, options, cwd),
+        "git tags-list" => tags_list::execute_git_tags_list(args, projects, options, cwd),
+        "git describe" => describe::execute_git_describe(args, projects, options, cwd),
+        "git lock" => lock::execute_git_lock(projects, options, cwd),
+        "git fetch" => fetch::execute_git_fetch(args, projects, parallel, cwd),
+        "git push" => push::execute_git_push(args, projects, options, parallel, cwd),
         "git snapshot" => snapshot::execute_snapshot_help(),
         "git snapshot create" => snapshot::execute_snapshot_create(args, projects, cwd),
         "git snapshot list" => snapshot::execute_snapshot_list(cwd),
@@ -81,14 +120,17 @@ pub fn execute_command(
         "git snapshot restore" => {
             snapshot::execute_snapshot_restore(args, projects, options.dry_run, cwd)
         }
+        "git snapshot diff" => snapshot::execute_snapshot_diff(args, projects, options, cwd),
         "git snapshot delete" => snapshot::execute_snapshot_delete(args, cwd),
+        "git snapshot prune" => snapshot::execute_snapshot_prune(args, options.dry_run, cwd),
         // Fallback: run raw git command across all repos
-        _ => return execute_raw_git_command(command, args, projects, options, cwd),
+        _ => execute_raw_git_command(command, args, projects, parallel, cwd),
     };
 
     match result {
         Ok(cmd_result) => cmd_result,
-        Err(e) => CommandResult::Error(format!("{e}")),
+        // The category comes from where the error was raised
+        Err(e) => MetaGitError::from_anyhow(&e).into_result(options.json_output),
     }
 }
 
@@ -122,7 +164,21 @@ fn build_passthrough_git_help(
     }
 
     let adapted_commands = [
-        "clone", "status", "update", "commit", "snapshot", "worktree",
+        "clone",
+        "status",
+        "update",
+        "commit",
+        "fix-remotes",
+        "switch-detach",
+        "reattach",
+        "cherry-pick",
+        "tags-list",
+        "describe",
+        "lock",
+        "fetch",
+        "push",
+        "snapshot",
+        "worktree",
     ];
     let git_args: Vec<String> = command_words
         .iter()
@@ -172,7 +228,8 @@ Meta options:\n\
   --exclude <REPOS>     Skip specified repos/directories\n\
   --tag <TAGS>          Filter by project tag(s), comma-separated\n\
   --parallel            Run repo commands concurrently\n\
-  --sequential          Run repo commands one at a time\n\n\
+  --sequential          Run repo commands one at a time, even with\n\
+                        `parallel = true` in the user config\n\n\
 Examples:\n\
   meta git {subcommand}\n\
   meta git {subcommand} --dry-run\n\
@@ -186,16 +243,12 @@ fn execute_raw_git_command(
     command: &str,
     args: &[String],
     projects: &[String],
-    options: &PluginRequestOptions,
+    parallel: bool,
     cwd: &Path,
-) -> CommandResult {
-    use meta_plugin_protocol::ExecutionPlan;
-
+) -> anyhow::Result<CommandResult> {
     // Get project directories
-    let dirs = match get_project_directories_with_fallback(projects, cwd) {
-        Ok(d) => d,
-        Err(e) => return CommandResult::Error(format!("Failed to get project directories: {e}")),
-    };
+    let dirs = get_project_directories_with_fallback(projects, cwd)
+        .map_err(|e| anyhow::anyhow!("Failed to get project directories: {e}"))?;
 
     // Build the full git command string
     // command is e.g. "git add ." and args contains any additional arguments
@@ -217,59 +270,70 @@ fn execute_raw_git_command(
         })
         .collect();
 
-    // For remote commands running in parallel, establish SSH ControlMaster
-    // connections and inject GIT_SSH_COMMAND into each planned command.
-    if options.parallel && is_remote_command(command) {
-        let urls = ssh::discover_ssh_urls(cwd);
+    if is_remote_command(command) {
+        Ok(remote_plan(commands, parallel, cwd))
+    } else {
+        Ok(CommandResult::Plan(commands, Some(parallel)))
+    }
+}
 
-        // HTTPS-only workspaces don't need SSH multiplexing
-        if urls.is_empty() {
-            return CommandResult::Plan(commands, Some(true));
-        }
+/// Plan commands that talk to remotes.
+///
+/// In parallel, establish SSH ControlMaster connections first and inject
+/// GIT_SSH_COMMAND into each planned command. Shared by the raw passthrough
+/// and the `fetch`/`push` implementations.
+pub(crate) fn remote_plan(
+    commands: Vec<PlannedCommand>,
+    parallel: bool,
+    cwd: &Path,
+) -> CommandResult {
+    use meta_plugin_protocol::ExecutionPlan;
 
-        let url_refs: Vec<&str> = urls.iter().map(|s| s.as_str()).collect();
+    if !parallel {
+        return CommandResult::Plan(commands, Some(false));
+    }
+    let urls = ssh::discover_ssh_urls(cwd);
 
-        let (ssh_env, parallel_ok) = match ssh_setup::establish_ssh_masters(&url_refs) {
-            ssh_setup::SshMasters::OurSockets(dir) => {
-                (Some(ssh_setup::git_ssh_command(&dir)), true)
-            }
-            ssh_setup::SshMasters::UserManaged => (None, true),
-            ssh_setup::SshMasters::Failed => (None, false),
-        };
+    // HTTPS-only workspaces don't need SSH multiplexing
+    if urls.is_empty() {
+        return CommandResult::Plan(commands, Some(true));
+    }
 
-        // Inject GIT_SSH_COMMAND into every planned command's env
-        let commands: Vec<PlannedCommand> = commands
-            .into_iter()
-            .map(|mut cmd| {
-                if let Some(ref ssh) = ssh_env {
-                    let env = cmd.env.get_or_insert_with(Default::default);
-                    env.insert("GIT_SSH_COMMAND".to_string(), ssh.clone());
-                }
-                cmd
-            })
-            .collect();
+    let url_refs: Vec<&str> = urls.iter().map(|s| s.as_str()).collect();
 
-        // SSH ControlMaster has a default MaxSessions limit of 10 (server-side).
-        const SSH_MAX_SESSIONS: usize = 10;
+    let (ssh_env, parallel_ok) = match ssh_setup::establish_ssh_masters(&url_refs) {
+        ssh_setup::SshMasters::OurSockets(dir) => (Some(ssh_setup::git_ssh_command(&dir)), true),
+        ssh_setup::SshMasters::UserManaged => (None, true),
+        ssh_setup::SshMasters::Failed => (None, false),
+    };
 
-        // Stagger spawns by 25ms to prevent SSH socket saturation.
-        const SSH_SPAWN_STAGGER_MS: u64 = 25;
+    // Inject GIT_SSH_COMMAND into every planned command's env
+    let commands: Vec<PlannedCommand> = commands
+        .into_iter()
+        .map(|mut cmd| {
+            if let Some(ref ssh) = ssh_env {
+                let env = cmd.env.get_or_insert_with(Default::default);
+                env.insert("GIT_SSH_COMMAND".to_string(), ssh.clone());
+            }
+            cmd
+        })
+        .collect();
 
-        if parallel_ok {
-            CommandResult::FullPlan(ExecutionPlan {
-                pre_commands: vec![],
-                commands,
-                post_commands: vec![],
-                parallel: Some(true),
-                max_parallel: Some(SSH_MAX_SESSIONS),
-                spawn_stagger_ms: Some(SSH_SPAWN_STAGGER_MS),
-            })
-        } else {
-            // SSH setup failed — fall back to sequential
-            CommandResult::Plan(commands, Some(false))
-        }
+    // Stagger spawns by 25ms to prevent SSH socket saturation.
+    const SSH_SPAWN_STAGGER_MS: u64 = 25;
+
+    if parallel_ok {
+        CommandResult::FullPlan(ExecutionPlan {
+            pre_commands: vec![],
+            commands,
+            post_commands: vec![],
+            parallel: Some(true),
+            max_parallel: Some(ssh_setup::SSH_MAX_SESSIONS),
+            spawn_stagger_ms: Some(SSH_SPAWN_STAGGER_MS),
+        })
     } else {
-        CommandResult::Plan(commands, Some(options.parallel))
+        // SSH setup failed — fall back to sequential
+        CommandResult::Plan(commands, Some(false))
     }
 }
 
@@ -288,35 +352,196 @@ SPECIAL COMMANDS:
       --meta-depth N    Limit recursive meta-repo discovery to N levels deep
       --parallel N      Clone up to N repositories in parallel
       --depth N         Create a shallow clone with truncated history
+      --filter SPEC     Partial clone (e.g. blob:none) for the meta repo and all
+                        children; combines with --depth
+      --branch, -b NAME Clone this branch of the meta repo (children keep their defaults)
+      --share-objects   Reuse objects from an already-cloned fork (with --dissociate);
+                        a fork counts when it has one of the remote's branch or tag tips
+      --verify          Check each clone (rev-parse HEAD, status) and fail broken ones
+      --lfs             Run 'git lfs pull' in each clone (needs git-lfs installed)
+      --preserve-permissions
+                        Clone with core.fileMode=true and core.autocrlf=false,
+                        regardless of global git config. A `git_config` map in
+                        .meta sets other keys for every child clone (also used
+                        by update); by default nothing is overridden.
+      --mirror          Back up as bare mirrors: <dir>/<meta>.git plus one
+                        <project-path>.git per child (re-run to refresh)
+      --into DIR        With --dry-run, plan against an existing meta checkout
+      --retries N       Retry a failed child clone up to N times with
+                        exponential backoff (default: 3, 0 disables)
+      --event-log FILE  Append one JSON line per repo outcome to FILE:
+                        {repo, url, status, duration_ms, attempts}
+      --protocol ssh|https
+                        Rewrite the meta and child URLs before cloning, e.g.
+                        https://github.com/org/repo.git -> git@github.com:org/repo.git
+                        (unrecognized URLs are cloned unchanged)
+      --include GLOB,...
+                        Only clone repos whose path (relative to the meta root)
+                        matches a glob, e.g. 'services/*'. Nested repos need
+                        their parent path included too.
+      --exclude GLOB,...
+                        Skip repos whose path matches a glob (applied after --include)
+      --timeout DURATION
+                        Kill any single child clone still running after DURATION
+                        and mark it failed ("timed out"); not retried
+      --global-timeout DURATION
+                        Abort the whole clone after DURATION (30s, 10m, 1h),
+                        killing running clones and listing what was left
+      --resume-from-state
+                        Continue an interrupted clone from
+                        <dir>/.git/meta-clone-state.json: half-written checkouts
+                        are removed, and failed, interrupted and not-started
+                        repos are queued again from the recorded task list. The
+                        file is kept until a run finishes without failures.
+
+  meta git status --since <ref>
+    Reports, per repo, how many commits HEAD has moved past <ref> and lists the
+    latest ones. Repos that don't have <ref> are skipped with a note.
 
   meta git update
     Updates all repositories by cloning any missing repos and pulling the latest
     changes. Runs in parallel for efficiency.
 
-  meta git commit --edit
+    Options:
+      --prune           Remove remote-tracking branches deleted upstream (in
+                        the same fetch as the pull), and offer to remove repos
+                        that are no longer in .meta.
+                        Repos with uncommitted, ignored, unpushed or stashed
+                        files, or with tracked children, are always kept
+      --force           With --prune, remove orphaned repos without asking
+      --no-auto-snapshot
+                        Don't snapshot the workspace (auto-before-prune-<timestamp>)
+                        before --prune removes anything. The snapshot records
+                        removed repos' SHAs; restore doesn't re-clone them
+      --event-log FILE  Append one JSON line per cloned repo (see clone)
+      --include GLOB,... / --exclude GLOB,...
+                        Limit which missing repos are cloned (see clone)
+      --no-pull         Only clone missing repos. By default every existing repo
+                        is fetched in parallel, then fast-forwarded to its
+                        upstream; diverged repos are reported (without failing
+                        the run) and repos with uncommitted changes are skipped
+      --ff-only         Only fast-forward (default)
+      --rebase          Rebase diverged branches onto upstream; a conflicting
+                        rebase is aborted and the repo reported as conflicted
+      --merge           Merge upstream into diverged branches (aborted on conflict)
+
+  meta git fix-remotes [--dry-run] [--yes]
+    Reconciles each repo's origin remote with the URL declared in .meta. Adds
+    origin where it is missing and rewrites it where it points elsewhere.
+
+  meta git switch-detach
+    Detaches HEAD at the current commit in every repo so no branch ref moves
+    during a coordinated rewrite.
+
+  meta git reattach [--dry-run]
+    Switches each detached repo back to the branch pointing at its commit,
+    preferring the branch checked out before detaching. Repos with no or
+    several candidate branches are reported and left detached.
+
+  meta git cherry-pick <sha-or-ref> [--dry-run]
+    Cherry-picks a commit into every repo where the ref resolves, skipping the
+    rest. Conflicts are reported per repo and left for resolution.
+
+  meta git cherry-pick --abort-all
+    Aborts every cherry-pick still in progress across the workspace.
+
+  meta git tags-list <tag> [--missing]
+    Reports which repos have <tag> and the commit it points at. --missing
+    lists only the repos without it. Supports --json.
+
+  meta git describe [--dirty]
+    Shows `git describe --tags --always` for every repo, falling back to the
+    short SHA where there are no tags. Supports --json.
+
+  meta git lock [--dry-run]
+    Writes .meta.lock pinning every cloned repo to its current HEAD. `meta git
+    clone` checks pinned repos out at those commits and fails any it can't.
+
+  meta git fetch [<git fetch args>]
+    Runs `git fetch --all --prune` in every repo (nested ones included) without
+    touching working trees, to refresh remote-tracking branches before pulling.
+    Flags such as --tags or --depth 1 are passed on; naming a remote or refspec
+    runs `git fetch <args>` as given instead. Supports --dry-run and --json.
+
+  meta git push [--repo <alias>]... [--exclude <alias>]... [<git push args>]
+    Pushes every repo whose branch is ahead of its upstream; a branch without
+    one is pushed with -u to its push remote (origin by default). Repos with
+    nothing to push are reported as up to date. Extra flags are added to each
+    push; a remote, refspec or --tags/--all/--delete runs `git push <args>` as
+    given in every repo instead. Supports --dry-run and --json.
+
+  meta git commit --edit [--dry-run]
     Opens an editor to create different commit messages for each repo.
+    --dry-run prints the git -C <path> commit -m ... command each repo would
+    run, without committing.
+
+  meta git commit --from-file <path> [--dry-run]
+    Like --edit, but reads the per-repo "========== repo ==========" sections
+    from a file ('-' for stdin) without opening an editor. Sections naming a
+    repo without staged changes are skipped with a warning.
+
+  meta git commit -m <message> [--parallel[=N]]
+    Commits staged changes in every repo with the same message. Runs
+    sequentially by default; --parallel commits up to N repos at once
+    (defaults to the number of CPUs).
+
+  meta git commit --type <type> [--scope <scope>] ...
+    Prefixes messages Conventional Commits style, as "type(scope): message",
+    for -m; with --edit every section starts out pre-filled with the prefix.
+    type must be one of feat, fix, docs, style, refactor, perf, test, build,
+    ci, chore, revert unless --no-verify is given.
+
+  meta git commit [-S[<keyid>]|--gpg-sign[=<keyid>]] [--signoff] [--no-verify] ...
+    Passed to every repo's git commit, with -m, --edit and --from-file alike.
+    --no-verify also allows any --type.
+
+  meta git commit --amend [-m <message>|--edit|--from-file <path>]
+    Amends the last commit of every repo with staged changes, keeping its
+    message unless a new one is given. Repos without any commit are skipped.
+
+  meta git commit -a ...
+    Also commits modified tracked files, like git commit -a; untracked files
+    are still left out. Works with -m, --edit and --from-file: the editor
+    sections list the tracked changes too, and every repo commits with -a.
 
 SNAPSHOT COMMANDS (EXPERIMENTAL - file format subject to change):
   Capture and restore workspace state for safe batch operations:
 
-  meta git snapshot create <name>
+  meta git snapshot create <name> [-m <message>]
     Record the current git state (SHA, branch, dirty status) of ALL repos.
     Snapshots are recursive by default - they capture the entire workspace.
+    --only-dirty captures just the repos with uncommitted changes; restoring
+    it leaves clean repos untouched. -m records why the snapshot was taken.
+    create, show and restore accept --repo <alias> and --exclude <alias>
+    (repeatable) to work on just some repos.
 
   meta git snapshot list
-    List all available snapshots with creation date and repo count.
+    List all available snapshots with creation date and repo count. Automatic
+    safety snapshots (auto-*) are dimmed; only the newest 10 are kept.
 
   meta git snapshot show <name>
     Display details of a snapshot including per-repo state.
 
   meta git snapshot restore <name> [--force] [--dry-run]
     Restore all repos to the recorded snapshot state. Prompts for confirmation.
-    Dirty repos are automatically stashed before restore.
-    Use --force to skip confirmation, --dry-run to preview changes.
+    Dirty repos are automatically stashed before restore; --no-stash skips
+    them instead and --discard resets them (git reset --hard) after a second
+    confirmation. Use --force to skip confirmation, --dry-run to preview changes.
+    The current state is first saved as auto-before-restore-<timestamp> unless
+    --no-auto-snapshot is given.
+
+  meta git snapshot diff <name> [other]
+    Compare a snapshot with the current workspace, or with another snapshot:
+    SHA, branch and dirty changes per repo, plus repos added or removed.
+    Supports --json.
 
   meta git snapshot delete <name>
     Delete a snapshot file.
 
+  meta git snapshot prune [--keep-last N] [--older-than DURATION]
+    Delete snapshots that are neither among the newest N nor younger than
+    DURATION. Prompts for confirmation; --force skips it, --dry-run previews.
+
 PASS-THROUGH COMMANDS:
   All other git commands are passed through to each repository:
 
@@ -349,7 +574,10 @@ Examples:
 #[cfg(test)]
 mod tests {
     use super::*;
-    use commit::parse_multi_commit_file;
+    use commit::{
+        commit_command_line, conventional_prefix, find_repos_with_staged_changes,
+        match_commits_to_staged, parse_multi_commit_file,
+    };
     use helpers::get_project_directories;
     use meta_plugin_protocol::{ExecutionPlan, PlanResponse, PlannedCommand};
     use tempfile::TempDir;
@@ -365,6 +593,27 @@ mod tests {
         assert!(!matches!(result, CommandResult::Error(_)));
     }
 
+    #[test]
+    fn json_errors_carry_the_category_of_their_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = PluginRequestOptions {
+            json_output: true,
+            ..Default::default()
+        };
+        let args = vec![
+            "https://example.com/meta.git".to_string(),
+            "--depth".to_string(),
+        ];
+
+        let result = execute_command("git clone", &args, &[], &options, temp_dir.path());
+        let CommandResult::Error(rendered) = result else {
+            panic!("--depth without a value was accepted");
+        };
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["error"]["code"], "invalid_argument");
+        assert_eq!(value["error"]["message"], "--depth requires a value");
+    }
+
     #[test]
     fn test_meta_config_parsing() {
         let dir = tempfile::tempdir().unwrap();
@@ -391,6 +640,70 @@ mod tests {
         assert!(matches!(result, CommandResult::Plan(_, _)));
     }
 
+    #[test]
+    fn test_find_repos_with_staged_changes_matches_sequential_scan() {
+        use crate::test_support::{git, init_repo};
+
+        let temp_dir = TempDir::new().unwrap();
+        let dirs: Vec<String> = ["web", "api", "docs", "missing"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        for name in ["web", "api", "docs"] {
+            init_repo(&temp_dir.path().join(name));
+        }
+        for name in ["web", "api"] {
+            let repo = temp_dir.path().join(name);
+            std::fs::write(repo.join("file.txt"), name).unwrap();
+            git(&repo, &["add", "file.txt"]);
+        }
+
+        // Reference: the original one-repo-at-a-time scan
+        let mut sequential = Vec::new();
+        for dir in &dirs {
+            let path = temp_dir.path().join(dir);
+            if !path.exists() {
+                continue;
+            }
+            let files: Vec<String> = git(&path, &["diff", "--cached", "--name-only"])
+                .lines()
+                .map(String::from)
+                .collect();
+            if !files.is_empty() {
+                sequential.push((dir.clone(), path.to_string_lossy().to_string(), files));
+            }
+        }
+        sequential.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let parallel = find_repos_with_staged_changes(&dirs, temp_dir.path(), false);
+        assert_eq!(parallel, sequential);
+        let names: Vec<&str> = parallel.iter().map(|(d, _, _)| d.as_str()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+    }
+
+    #[test]
+    fn test_find_repos_with_staged_changes_all_includes_tracked_modifications() {
+        use crate::test_support::{git, init_repo};
+
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["modified", "untracked"] {
+            let repo = temp_dir.path().join(name);
+            init_repo(&repo);
+            std::fs::write(repo.join("file.txt"), "one").unwrap();
+            git(&repo, &["add", "file.txt"]);
+            git(&repo, &["commit", "-qm", "init"]);
+        }
+        std::fs::write(temp_dir.path().join("modified/file.txt"), "two").unwrap();
+        std::fs::write(temp_dir.path().join("untracked/new.txt"), "new").unwrap();
+
+        let dirs = vec!["modified".to_string(), "untracked".to_string()];
+        assert!(find_repos_with_staged_changes(&dirs, temp_dir.path(), false).is_empty());
+        let repos = find_repos_with_staged_changes(&dirs, temp_dir.path(), true);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].0, "modified");
+        assert_eq!(repos[0].2, vec!["file.txt".to_string()]);
+    }
+
     #[test]
     fn test_get_help_text() {
         let help = get_help_text();
@@ -429,6 +742,77 @@ fix: fix bug in MCP server
         assert_eq!(commits[1].1, "fix: fix bug in MCP server");
     }
 
+    #[test]
+    fn test_match_commits_to_staged_reports_unmatched_sections() {
+        let repos = vec![
+            (
+                "api".to_string(),
+                "/ws/api".to_string(),
+                vec!["a.rs".to_string()],
+            ),
+            (
+                ".".to_string(),
+                "/ws".to_string(),
+                vec![".meta".to_string()],
+            ),
+        ];
+        let commits = parse_multi_commit_file(
+            "========== api ==========\nfeat: api\n\
+             ========== web ==========\nfix: web\n\
+             ========== . ==========\nchore: bump\n",
+        );
+
+        let (matched, unmatched) = match_commits_to_staged(&repos, commits);
+        assert_eq!(
+            matched,
+            vec![
+                ("api".to_string(), "feat: api".to_string()),
+                (".".to_string(), "chore: bump".to_string())
+            ]
+        );
+        assert_eq!(unmatched, vec!["web".to_string()]);
+    }
+
+    #[test]
+    fn test_commit_command_line_quotes_message_and_flags() {
+        assert_eq!(
+            commit_command_line("/ws/api", &[], "feat: add login"),
+            "git -C /ws/api commit -m 'feat: add login'"
+        );
+        assert_eq!(
+            commit_command_line(
+                "/ws/my repo",
+                &["-a".to_string(), "--gpg-sign=ABC".to_string()],
+                "fix: don't crash\n\nDetails"
+            ),
+            "git -C '/ws/my repo' commit -a '--gpg-sign=ABC' -m 'fix: don'\\''t crash\n\nDetails'"
+        );
+    }
+
+    #[test]
+    fn test_conventional_prefix() {
+        assert_eq!(conventional_prefix(None, None, false), Ok(None));
+        assert_eq!(
+            conventional_prefix(Some("feat"), Some("api"), false),
+            Ok(Some("feat(api): ".to_string()))
+        );
+        assert_eq!(
+            conventional_prefix(Some("fix"), None, false),
+            Ok(Some("fix: ".to_string()))
+        );
+        assert!(conventional_prefix(Some("feature"), None, false)
+            .unwrap_err()
+            .starts_with("Unknown commit type 'feature'"));
+        assert_eq!(
+            conventional_prefix(Some("wip"), None, true),
+            Ok(Some("wip: ".to_string()))
+        );
+        assert_eq!(
+            conventional_prefix(None, Some("api"), false),
+            Err("--scope requires --type".to_string())
+        );
+    }
+
     #[test]
     fn test_parse_multi_commit_file_multiline_message() {
         let content = r#"========== repo1 ==========
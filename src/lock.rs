@@ -0,0 +1,159 @@
+//! `.meta.lock` - pinned commits for reproducible clones.
+//!
+//! `meta git lock` records the current `HEAD` of every cloned repo, keyed by
+//! its path relative to the meta root (nested repos included):
+//!
+//! ```json
+//! {
+//!   "api": "3f2c9a1e...",
+//!   "libs/core": "b71d04c2..."
+//! }
+//! ```
+//!
+//! `meta git clone` reads the file from the freshly cloned meta repo and
+//! checks each pinned child out at its SHA (detached). A pin that can't be
+//! checked out fails that repo. The meta repo itself is never pinned, since
+//! it is the one carrying the lockfile.
+
+use crate::helpers::get_all_repo_directories;
+use console::style;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub(crate) const LOCK_FILE: &str = ".meta.lock";
+
+/// Read `<meta_root>/.meta.lock`; a missing file means nothing is pinned.
+pub(crate) fn read_lock(meta_root: &Path) -> anyhow::Result<BTreeMap<String, String>> {
+    let path = meta_root.join(LOCK_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid lockfile {}: {e}", path.display()))
+}
+
+/// Pins from `<meta_root>/.meta.lock`, keyed by each repo's absolute target path.
+pub(crate) fn pinned_targets(meta_root: &Path) -> anyhow::Result<BTreeMap<PathBuf, String>> {
+    Ok(read_lock(meta_root)?
+        .into_iter()
+        .map(|(path, sha)| (meta_root.join(path), sha))
+        .collect())
+}
+
+pub(crate) fn execute_git_lock(
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let dirs: Vec<String> = get_all_repo_directories(projects, cwd)?
+        .into_iter()
+        .filter(|d| d != ".")
+        .collect();
+
+    let heads: Vec<(String, Option<String>)> = dirs
+        .par_iter()
+        .map(|dir| (dir.clone(), head_sha(&cwd.join(dir))))
+        .collect();
+
+    let mut lock = BTreeMap::new();
+    let mut missing = Vec::new();
+    for (dir, sha) in heads {
+        match sha {
+            Some(sha) => {
+                lock.insert(dir, sha);
+            }
+            None => missing.push(dir),
+        }
+    }
+
+    let path = cwd.join(LOCK_FILE);
+    if options.dry_run {
+        println!(
+            "{} Would write {} pin(s) to {}",
+            style("[DRY RUN]").cyan(),
+            lock.len(),
+            path.display()
+        );
+    } else {
+        std::fs::write(&path, serde_json::to_string_pretty(&lock)? + "\n")?;
+        println!(
+            "{} Pinned {} repo(s) in {}",
+            style("✓").green(),
+            lock.len(),
+            LOCK_FILE
+        );
+    }
+    for dir in &missing {
+        println!(
+            "  {} {} {}",
+            style("-").dim(),
+            dir,
+            style("(not cloned, skipped)").dim()
+        );
+    }
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+/// Check a fresh clone out at its pinned commit.
+pub(crate) fn checkout_pin(repo: &Path, sha: &str) -> Result<(), String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["checkout", "--quiet", "--detach", sha])
+        .output()
+        .map_err(|e| format!("failed to spawn git: {e}"))?;
+    if out.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    Err(stderr
+        .lines()
+        .last()
+        .unwrap_or("git checkout failed")
+        .trim()
+        .to_string())
+}
+
+fn head_sha(repo: &Path) -> Option<String> {
+    if !repo.join(".git").exists() {
+        return None;
+    }
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_targets_resolve_against_meta_root() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(pinned_targets(dir.path()).unwrap().is_empty());
+
+        std::fs::write(
+            dir.path().join(LOCK_FILE),
+            r#"{"api": "abc123", "libs/core": "def456"}"#,
+        )
+        .unwrap();
+        let pins = pinned_targets(dir.path()).unwrap();
+        assert_eq!(
+            pins.get(&dir.path().join("libs/core")).map(String::as_str),
+            Some("def456")
+        );
+        assert_eq!(pins.len(), 2);
+    }
+}
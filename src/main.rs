@@ -4,12 +4,16 @@
 //! Use RUST_LOG=meta_git_cli=debug for debug output.
 
 use indexmap::IndexMap;
+use meta_git_cli::MetaGitError;
 use meta_plugin_protocol::{
     run_plugin, CommandResult, PluginDefinition, PluginHelp, PluginInfo, PluginRequest,
 };
 use std::path::PathBuf;
 
 fn main() {
+    // Machine-wide defaults (~/.config/meta-git/config.toml); CLI flags still win
+    meta_git_cli::init_user_config();
+
     // Build command sections for help display
     let mut command_sections = IndexMap::new();
 
@@ -27,6 +31,42 @@ fn main() {
         "update".to_string(),
         "Pull existing repos and clone any missing repos".to_string(),
     );
+    adapted.insert(
+        "fix-remotes".to_string(),
+        "Reset origin remotes to the URLs declared in .meta".to_string(),
+    );
+    adapted.insert(
+        "switch-detach".to_string(),
+        "Detach HEAD at the current commit in every repo".to_string(),
+    );
+    adapted.insert(
+        "reattach".to_string(),
+        "Switch detached repos back to their branches".to_string(),
+    );
+    adapted.insert(
+        "cherry-pick".to_string(),
+        "Cherry-pick a commit into every repo that has it".to_string(),
+    );
+    adapted.insert(
+        "tags-list".to_string(),
+        "Show which repos have a tag, and at what commit".to_string(),
+    );
+    adapted.insert(
+        "describe".to_string(),
+        "Show a tag-based version description for every repo".to_string(),
+    );
+    adapted.insert(
+        "lock".to_string(),
+        "Pin every repo's current commit in .meta.lock".to_string(),
+    );
+    adapted.insert(
+        "fetch".to_string(),
+        "Fetch all remotes in every repo without merging".to_string(),
+    );
+    adapted.insert(
+        "push".to_string(),
+        "Push every repo that is ahead of its upstream".to_string(),
+    );
     adapted.insert(
         "snapshot".to_string(),
         "Save and restore workspace state across all repos".to_string(),
@@ -60,12 +100,23 @@ fn main() {
                 "git status".to_string(),
                 "git update".to_string(),
                 "git commit".to_string(),
+                "git fix-remotes".to_string(),
+                "git switch-detach".to_string(),
+                "git reattach".to_string(),
+                "git cherry-pick".to_string(),
+                "git tags-list".to_string(),
+                "git describe".to_string(),
+                "git lock".to_string(),
+                "git fetch".to_string(),
+                "git push".to_string(),
                 "git snapshot".to_string(),
                 "git snapshot create".to_string(),
                 "git snapshot list".to_string(),
                 "git snapshot show".to_string(),
                 "git snapshot restore".to_string(),
+                "git snapshot diff".to_string(),
                 "git snapshot delete".to_string(),
+                "git snapshot prune".to_string(),
                 "git worktree".to_string(),
                 "git worktree create".to_string(),
                 "git worktree add".to_string(),
@@ -76,6 +127,12 @@ fn main() {
                 "git worktree diff".to_string(),
                 "git worktree exec".to_string(),
                 "git worktree prune".to_string(),
+                "git worktree path".to_string(),
+                "git worktree rename".to_string(),
+                "git worktree extend".to_string(),
+                "git worktree sync".to_string(),
+                "git worktree store".to_string(),
+                "git worktree store rebase".to_string(),
             ],
             description: Some("Git operations for meta repositories".to_string()),
             help: Some(PluginHelp {
@@ -104,7 +161,10 @@ fn execute(request: PluginRequest) -> CommandResult {
     let cwd = if request.cwd.is_empty() {
         match std::env::current_dir() {
             Ok(d) => d,
-            Err(e) => return CommandResult::Error(format!("Failed to get working directory: {e}")),
+            Err(e) => {
+                return MetaGitError::Other(format!("Failed to get working directory: {e}"))
+                    .into_result(request.options.json_output)
+            }
         }
     } else {
         PathBuf::from(&request.cwd)
@@ -0,0 +1,352 @@
+//! `meta git clone --mirror` - back up a meta repo and its children as bare mirrors.
+//!
+//! Unlike a normal clone, the output is a flat directory of bare repos:
+//!
+//! ```text
+//! <out>/
+//!   <meta-name>.git        # mirror of the meta repo itself
+//!   <project-path>.git     # one mirror per .meta project (nested paths kept)
+//! ```
+//!
+//! Bare mirrors have no working tree, so nested `.meta` files are read
+//! straight from each mirror's `HEAD` into a scratch directory for parsing.
+//! Re-running against an existing output directory refreshes the mirrors
+//! with `git remote update --prune` instead of cloning again.
+//!
+//! `--protocol` rewrites every child URL and `--include`/`--exclude` match
+//! project paths, the same as for a normal clone.
+
+use crate::clone_worker::RepoPathFilter;
+use crate::url_rewrite::{apply_protocol, Protocol};
+use crate::MetaGitError;
+use console::style;
+use meta_core::config;
+use meta_plugin_protocol::CommandResult;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Config filenames tried, in order, when reading `.meta` out of a mirror.
+const META_CONFIG_NAMES: [&str; 4] = [".meta", ".meta.json", ".meta.yaml", ".meta.yml"];
+
+pub(crate) struct MirrorRequest<'a> {
+    pub url: &'a str,
+    /// Output directory, relative to `cwd`
+    pub out_dir: &'a str,
+    pub recursive: bool,
+    pub meta_depth: Option<usize>,
+    pub parallel: usize,
+    pub ssh_cmd: Option<&'a str>,
+    pub protocol: Option<Protocol>,
+    /// `--include`/`--exclude`, rooted at the output directory
+    pub path_filter: Option<&'a RepoPathFilter>,
+}
+
+/// One repo to mirror: where it comes from and where it lands under `out_dir`.
+struct MirrorTask {
+    url: String,
+    /// Path of the repo relative to the meta root ("" for the meta repo itself)
+    rel_path: String,
+    level: usize,
+}
+
+enum MirrorOutcome {
+    Cloned,
+    Updated,
+    Failed(String),
+}
+
+pub(crate) fn execute_mirror_clone(
+    request: &MirrorRequest<'_>,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let out_dir = cwd.join(request.out_dir);
+    std::fs::create_dir_all(&out_dir)?;
+
+    let meta_name = request
+        .url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or("meta")
+        .to_string();
+    let meta_mirror = out_dir.join(format!("{meta_name}.git"));
+
+    println!("Mirroring meta repository: {}", request.url);
+    match mirror_repo(request.url, &meta_mirror, request.ssh_cmd) {
+        MirrorOutcome::Failed(e) => {
+            return Err(
+                MetaGitError::GitFailed(format!("Failed to mirror meta repository: {e}")).into(),
+            );
+        }
+        outcome => print_outcome(&meta_name, &outcome),
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(request.parallel.max(1))
+        .build()?;
+
+    let mut level_tasks = read_mirror_children(&meta_mirror, "", 0, request.protocol);
+    let mut mirrored = 1_usize;
+    let mut failed = 0_usize;
+    let mut filtered = 0_usize;
+
+    while !level_tasks.is_empty() {
+        // Filtered-out repos aren't mirrored, so their children aren't either
+        if let Some(filter) = request.path_filter {
+            let before = level_tasks.len();
+            level_tasks.retain(|task| filter.matches(&out_dir.join(&task.rel_path)));
+            filtered += before - level_tasks.len();
+        }
+        let results: Vec<(MirrorTask, PathBuf, MirrorOutcome)> = pool.install(|| {
+            level_tasks
+                .into_par_iter()
+                .map(|task| {
+                    let dest = out_dir.join(format!("{}.git", task.rel_path));
+                    let outcome = mirror_repo(&task.url, &dest, request.ssh_cmd);
+                    (task, dest, outcome)
+                })
+                .collect()
+        });
+
+        let mut next = Vec::new();
+        for (task, dest, outcome) in results {
+            print_outcome(&task.rel_path, &outcome);
+            if matches!(outcome, MirrorOutcome::Failed(_)) {
+                failed += 1;
+                continue;
+            }
+            mirrored += 1;
+
+            // Same depth semantics as a normal clone: non-recursive stops at level 0
+            let descend = request.recursive
+                && match request.meta_depth {
+                    Some(max) => task.level < max,
+                    None => true,
+                };
+            if descend {
+                next.extend(read_mirror_children(
+                    &dest,
+                    &task.rel_path,
+                    task.level + 1,
+                    request.protocol,
+                ));
+            }
+        }
+        level_tasks = next;
+    }
+
+    println!();
+    let mut notes = String::new();
+    if filtered > 0 {
+        notes.push_str(&format!(", {filtered} filtered out"));
+    }
+    if failed > 0 {
+        notes.push_str(&format!(", {failed} failed"));
+    }
+    println!(
+        "Mirror completed ({} repos in {}{notes})",
+        mirrored,
+        out_dir.display()
+    );
+
+    if failed > 0 {
+        return Err(
+            MetaGitError::GitFailed(format!("{failed} repositories failed to mirror")).into(),
+        );
+    }
+    Ok(CommandResult::Message(String::new()))
+}
+
+fn print_outcome(name: &str, outcome: &MirrorOutcome) {
+    match outcome {
+        MirrorOutcome::Cloned => println!("  {} {name}.git", style("✓").green()),
+        MirrorOutcome::Updated => {
+            println!(
+                "  {} {name}.git {}",
+                style("✓").green(),
+                style("(updated)").dim()
+            )
+        }
+        MirrorOutcome::Failed(e) => println!("  {} {name}.git: {e}", style("✗").red()),
+    }
+}
+
+/// Clone `url` as a bare mirror at `dest`, or refresh it if it already exists.
+fn mirror_repo(url: &str, dest: &Path, ssh_cmd: Option<&str>) -> MirrorOutcome {
+    let updating = dest.join("HEAD").exists();
+
+    let mut cmd = Command::new("git");
+    if updating {
+        cmd.arg("--git-dir")
+            .arg(dest)
+            .args(["remote", "update", "--prune"]);
+    } else {
+        if let Some(parent) = dest.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                return MirrorOutcome::Failed(e.to_string());
+            }
+        }
+        cmd.args(["clone", "--mirror", "--quiet", url]).arg(dest);
+    }
+    cmd.env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if let Some(ssh) = ssh_cmd {
+        cmd.env("GIT_SSH_COMMAND", ssh);
+    }
+
+    match cmd.output() {
+        Ok(out) if out.status.success() && updating => MirrorOutcome::Updated,
+        Ok(out) if out.status.success() => MirrorOutcome::Cloned,
+        Ok(out) => MirrorOutcome::Failed(
+            String::from_utf8_lossy(&out.stderr)
+                .lines()
+                .last()
+                .unwrap_or("git exited with an error")
+                .to_string(),
+        ),
+        Err(e) => MirrorOutcome::Failed(format!("failed to spawn git: {e}")),
+    }
+}
+
+/// Read the `.meta` committed at a mirror's `HEAD` and list its child repos,
+/// with their URLs rewritten for `protocol`.
+fn read_mirror_children(
+    mirror: &Path,
+    parent_rel: &str,
+    level: usize,
+    protocol: Option<Protocol>,
+) -> Vec<MirrorTask> {
+    let Some((name, contents)) = META_CONFIG_NAMES
+        .iter()
+        .find_map(|name| show_head_file(mirror, name).map(|c| (*name, c)))
+    else {
+        return vec![];
+    };
+
+    // parse_meta_config works on files, so stage the blob in a scratch dir
+    // (keeping the filename, which selects JSON vs YAML parsing)
+    let scratch = std::env::temp_dir().join(format!(
+        "meta-mirror-{}-{}",
+        std::process::id(),
+        mirror.display().to_string().replace(['/', '\\', ':'], "_")
+    ));
+    let meta_path = scratch.join(name);
+    let staged =
+        std::fs::create_dir_all(&scratch).and_then(|_| std::fs::write(&meta_path, contents));
+    if let Err(e) = staged {
+        log::warn!("Failed to stage {name} from {}: {e}", mirror.display());
+        return vec![];
+    }
+    let parsed = config::parse_meta_config(&meta_path);
+    let _ = std::fs::remove_dir_all(&scratch);
+
+    let projects = match parsed {
+        Ok((projects, _)) => projects,
+        Err(e) => {
+            log::warn!("Failed to parse {name} in {}: {e}", mirror.display());
+            return vec![];
+        }
+    };
+
+    projects
+        .into_iter()
+        .filter_map(|p| {
+            let url = apply_protocol(&p.repo?, protocol);
+            let rel_path = if parent_rel.is_empty() {
+                p.path
+            } else {
+                format!("{parent_rel}/{}", p.path)
+            };
+            Some(MirrorTask {
+                url,
+                rel_path,
+                level,
+            })
+        })
+        .collect()
+}
+
+fn show_head_file(git_dir: &Path, file: &str) -> Option<String> {
+    let out = Command::new("git")
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args(["show", &format!("HEAD:{file}")])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    /// A meta repo whose `.meta` lists `api` and `web`, both local repos.
+    fn fixture(root: &Path) -> PathBuf {
+        for child in ["api", "web"] {
+            init_repo(&root.join("src").join(child));
+        }
+        let meta = root.join("src/meta");
+        init_repo(&meta);
+        let url = |child: &str| root.join("src").join(child).display().to_string();
+        let projects = serde_json::json!({"projects": {"api": url("api"), "web": url("web")}});
+        std::fs::write(meta.join(".meta"), projects.to_string()).unwrap();
+        git(&meta, &["add", ".meta"]);
+        git(&meta, &["commit", "--quiet", "-m", "meta"]);
+        meta
+    }
+
+    #[test]
+    fn mirrors_children_that_pass_the_path_filter() {
+        let tmp = tempfile::tempdir().unwrap();
+        let meta = fixture(tmp.path());
+        let out = tmp.path().join("backup");
+        let exclude = vec!["web".to_string()];
+        let filter = RepoPathFilter::new(&out, &[], &exclude).unwrap();
+        let url = meta.display().to_string();
+        let request = MirrorRequest {
+            url: &url,
+            out_dir: "backup",
+            recursive: false,
+            meta_depth: None,
+            parallel: 2,
+            ssh_cmd: None,
+            protocol: None,
+            path_filter: filter.as_ref(),
+        };
+
+        let result = execute_mirror_clone(&request, tmp.path()).unwrap();
+        assert!(matches!(result, CommandResult::Message(_)));
+        assert!(out.join("meta.git/HEAD").exists());
+        assert!(out.join("api.git/HEAD").exists());
+        assert!(!out.join("web.git").exists());
+
+        // A second run refreshes the existing mirrors
+        let result = execute_mirror_clone(&request, tmp.path()).unwrap();
+        assert!(matches!(result, CommandResult::Message(_)));
+    }
+
+    #[test]
+    fn child_urls_follow_the_protocol() {
+        let tmp = tempfile::tempdir().unwrap();
+        let meta = tmp.path().join("meta");
+        init_repo(&meta);
+        let projects = r#"{"projects": {"api": "git@github.com:org/api.git"}}"#;
+        std::fs::write(meta.join(".meta"), projects).unwrap();
+        git(&meta, &["add", ".meta"]);
+        git(&meta, &["commit", "--quiet", "-m", "meta"]);
+
+        let children = read_mirror_children(&meta.join(".git"), "", 0, Some(Protocol::Https));
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].url, "https://github.com/org/api.git");
+        let children = read_mirror_children(&meta.join(".git"), "", 0, None);
+        assert_eq!(children[0].url, "git@github.com:org/api.git");
+    }
+}
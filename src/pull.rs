@@ -0,0 +1,488 @@
+//! Bring already-cloned repos up to date for `meta git update`.
+//!
+//! Runs in two phases: every repo is fetched in parallel first (over shared
+//! SSH connections, at most `jobs` at a time), then each one is
+//! fast-forwarded locally. Network work is batched up front, so a slow or
+//! failing remote never leaves half the workspace merged and half not, and
+//! merge problems are only reported once all fetches have finished.
+//!
+//! Repos with uncommitted changes are fetched but never merged into; they are
+//! reported and skipped rather than counted as failures.
+
+use crate::helpers::{git_ahead_behind, run_git as git};
+use crate::ssh_setup;
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// How `meta git update` integrates upstream commits into a branch that is behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum PullMode {
+    /// Only fast-forward; diverged branches are reported (`--ff-only`, the default)
+    #[default]
+    FfOnly,
+    /// Replay local commits on top of upstream, aborting on conflict (`--rebase`)
+    Rebase,
+    /// Create a merge commit, aborting on conflict (`--merge`)
+    Merge,
+}
+
+impl PullMode {
+    fn command(self) -> &'static str {
+        match self {
+            PullMode::FfOnly => "merge --ff-only @{u}",
+            PullMode::Rebase => "rebase @{u}",
+            PullMode::Merge => "merge --no-edit @{u}",
+        }
+    }
+}
+
+/// What happened to one repo during the merge phase.
+enum PullOutcome {
+    /// Nothing to pull; carries the number of local commits not yet pushed
+    UpToDate(usize),
+    FastForwarded(usize),
+    /// Local commits replayed on top of `behind` upstream commits
+    Rebased {
+        ahead: usize,
+        behind: usize,
+    },
+    Merged {
+        behind: usize,
+    },
+    /// Uncommitted changes to tracked files; left alone
+    Dirty,
+    /// The current branch has no upstream to merge from
+    NoUpstream,
+    /// A rebase/merge hit conflicts and was aborted
+    Conflicted(String),
+    /// Both ahead of and behind upstream under `--ff-only`; left untouched
+    Diverged {
+        ahead: usize,
+        behind: usize,
+    },
+    FetchFailed(String),
+    /// git itself failed
+    Failed(String),
+}
+
+impl PullOutcome {
+    /// Summary group, in display order.
+    fn group(&self) -> usize {
+        match self {
+            PullOutcome::UpToDate(_) => 0,
+            PullOutcome::FastForwarded(_) => 1,
+            PullOutcome::Rebased { .. } => 2,
+            PullOutcome::Merged { .. } => 3,
+            PullOutcome::Dirty => 4,
+            PullOutcome::NoUpstream => 5,
+            PullOutcome::Conflicted(_) => 6,
+            PullOutcome::FetchFailed(_) | PullOutcome::Failed(_) => 7,
+            PullOutcome::Diverged { .. } => 8,
+        }
+    }
+
+    /// Conflicts and errors; a diverged branch is reported but not a failure.
+    fn is_failure(&self) -> bool {
+        matches!(self.group(), 6 | 7)
+    }
+
+    fn detail(&self) -> Option<String> {
+        let commits = |n: usize| format!("{n} commit{}", if n == 1 { "" } else { "s" });
+        match self {
+            PullOutcome::UpToDate(0) | PullOutcome::Dirty | PullOutcome::NoUpstream => None,
+            PullOutcome::UpToDate(ahead) => Some(format!("{ahead} ahead")),
+            PullOutcome::FastForwarded(n) | PullOutcome::Merged { behind: n } => Some(commits(*n)),
+            PullOutcome::Rebased { ahead, behind } => {
+                Some(format!("{} onto {}", commits(*ahead), commits(*behind)))
+            }
+            PullOutcome::Conflicted(e) | PullOutcome::Failed(e) => Some(e.clone()),
+            PullOutcome::FetchFailed(e) => Some(format!("fetch failed: {e}")),
+            PullOutcome::Diverged { ahead, behind } => {
+                Some(format!("{ahead} ahead, {behind} behind"))
+            }
+        }
+    }
+}
+
+/// Diverged repos come last so they're the first thing seen after the run.
+const GROUP_LABELS: [&str; 9] = [
+    "Up to date",
+    "Fast-forwarded",
+    "Rebased",
+    "Merged",
+    "Skipped (uncommitted changes)",
+    "Skipped (no upstream branch)",
+    "Conflicted (needs manual attention)",
+    "Failed",
+    "Diverged from upstream (use --rebase or --merge)",
+];
+
+/// Fetch, then integrate upstream per `mode`, every repo in `repos` (display path, path).
+///
+/// With `prune` the same fetch also drops remote-tracking refs whose
+/// upstream branch is gone (`--prune`), so there is one network pass. A
+/// `mode` of `None` (`--no-pull --prune`) only fetches.
+///
+/// Returns the number of repos that could not be updated.
+pub(crate) fn pull_existing_repos(
+    repos: &[(String, PathBuf)],
+    mode: Option<PullMode>,
+    prune: bool,
+    dry_run: bool,
+    cwd: &Path,
+) -> anyhow::Result<usize> {
+    if repos.is_empty() {
+        return Ok(0);
+    }
+
+    let fetch_cmd = if prune { "fetch --prune" } else { "fetch" };
+    if dry_run {
+        let verb = if mode.is_some() {
+            "fetch and update"
+        } else {
+            "fetch"
+        };
+        println!(
+            "{} Would {verb} {} existing repositories:",
+            style("[DRY RUN]").cyan(),
+            repos.len()
+        );
+        for (display, _) in repos {
+            match mode {
+                Some(mode) => println!(
+                    "  git -C {display} {fetch_cmd} && git -C {display} {}",
+                    mode.command()
+                ),
+                None => println!("  git -C {display} {fetch_cmd}"),
+            }
+        }
+        if mode.is_some() {
+            println!("  (repos with uncommitted changes are fetched but not merged)");
+        }
+        return Ok(0);
+    }
+
+    // Phase 1: fetch everything in parallel
+    let (ssh_cmd, jobs) = fetch_concurrency(cwd);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let pb = ProgressBar::new(repos.len() as u64);
+    pb.set_style(
+        ProgressStyle::with_template("Fetching [{bar:30}] {pos}/{len} {wide_msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    let fetched: Vec<Result<Vec<String>, String>> = pool.install(|| {
+        repos
+            .par_iter()
+            .map(|(display, path)| {
+                let result = fetch(path, ssh_cmd.as_deref(), prune);
+                pb.set_message(display.clone());
+                pb.inc(1);
+                result
+            })
+            .collect()
+    });
+    pb.finish_and_clear();
+    if prune {
+        print_pruned(repos, &fetched);
+    }
+
+    let Some(mode) = mode else {
+        let mut failures = 0;
+        for ((display, _), result) in repos.iter().zip(&fetched) {
+            if let Err(e) = result {
+                failures += 1;
+                eprintln!(
+                    "{} Failed to fetch {}: {e}",
+                    style("✗").red(),
+                    style(display).bold()
+                );
+            }
+        }
+        return Ok(failures);
+    };
+
+    // Phase 2: integrate now that all network work is done. This is local
+    // work, so it also runs in parallel.
+    let outcomes: Vec<PullOutcome> = repos
+        .par_iter()
+        .zip(fetched)
+        .map(|((_, path), fetch_result)| match fetch_result {
+            Err(e) => PullOutcome::FetchFailed(e),
+            Ok(_) if is_dirty(path) => PullOutcome::Dirty,
+            Ok(_) => integrate(path, mode),
+        })
+        .collect();
+
+    print_summary(repos, &outcomes);
+    Ok(outcomes.iter().filter(|o| o.is_failure()).count())
+}
+
+/// `GIT_SSH_COMMAND` and fetch concurrency for the workspace at `cwd`.
+///
+/// Like the passthrough and clone paths, ControlMasters are set up for its
+/// SSH remotes first and fetches share them, capped at `jobs` and the
+/// masters' session limit; if setup fails, repos are fetched one at a time.
+fn fetch_concurrency(cwd: &Path) -> (Option<String>, usize) {
+    let jobs = crate::user_config::default_jobs();
+    let urls = crate::ssh::discover_ssh_urls(cwd);
+    if urls.is_empty() {
+        return (None, jobs);
+    }
+    let url_refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+    let capped = jobs.min(ssh_setup::SSH_MAX_SESSIONS);
+    match ssh_setup::establish_ssh_masters(&url_refs) {
+        ssh_setup::SshMasters::OurSockets(dir) => (Some(ssh_setup::git_ssh_command(&dir)), capped),
+        ssh_setup::SshMasters::UserManaged => (None, capped),
+        ssh_setup::SshMasters::Failed => {
+            log::warn!("SSH multiplexing setup failed, falling back to serial fetching");
+            (None, 1)
+        }
+    }
+}
+
+/// Print repos grouped by outcome, each group in repo order.
+fn print_summary(repos: &[(String, PathBuf)], outcomes: &[PullOutcome]) {
+    for (group, label) in GROUP_LABELS.iter().enumerate() {
+        let members: Vec<(&str, &PullOutcome)> = repos
+            .iter()
+            .zip(outcomes)
+            .filter(|(_, o)| o.group() == group)
+            .map(|((display, _), o)| (display.as_str(), o))
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        let icon = match group {
+            0 => style("✓").green(),
+            1..=3 => style("↓").cyan(),
+            4 | 5 => style("-").yellow(),
+            8 => style("⚠").yellow(),
+            _ => style("✗").red(),
+        };
+        println!("{icon} {label} ({})", members.len());
+        for (display, outcome) in members {
+            match outcome.detail() {
+                Some(detail) => println!("    {display} {}", style(format!("({detail})")).dim()),
+                None => println!("    {display}"),
+            }
+        }
+    }
+}
+
+/// Fetch `repo`; with `prune`, also drop stale remote-tracking refs and
+/// return their names.
+fn fetch(repo: &Path, ssh_cmd: Option<&str>, prune: bool) -> Result<Vec<String>, String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo).arg("fetch");
+    if prune {
+        // --quiet would hide the "[deleted]" lines; LC_ALL keeps them untranslated
+        cmd.arg("--prune").env("LC_ALL", "C");
+    } else {
+        cmd.arg("--quiet");
+    }
+    cmd.env("GIT_TERMINAL_PROMPT", "0").stdin(Stdio::null());
+    if let Some(ssh) = ssh_cmd {
+        cmd.env("GIT_SSH_COMMAND", ssh);
+    }
+    let out = cmd.output().map_err(|e| e.to_string())?;
+    if out.status.success() {
+        Ok(parse_deleted_refs(&String::from_utf8_lossy(&out.stderr)))
+    } else {
+        Err(last_line(&out.stderr))
+    }
+}
+
+/// Refs from `git fetch --prune`'s ` - [deleted]  (none) -> origin/x` lines.
+fn parse_deleted_refs(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("- [deleted]")?;
+            let (_, name) = rest.rsplit_once("->")?;
+            Some(name.trim().to_string())
+        })
+        .collect()
+}
+
+/// Per-repo list of the remote-tracking refs `fetch --prune` dropped.
+fn print_pruned(repos: &[(String, PathBuf)], fetched: &[Result<Vec<String>, String>]) {
+    let mut total = 0;
+    for ((display, _), result) in repos.iter().zip(fetched) {
+        let Ok(refs) = result else { continue };
+        if refs.is_empty() {
+            continue;
+        }
+        total += refs.len();
+        println!("Pruned {}:", style(display).bold());
+        for r in refs {
+            println!("  {} {r}", style("-").red());
+        }
+    }
+    if total == 0 {
+        println!("No stale remote branches to prune.");
+    } else {
+        println!("Pruned {total} stale remote branch(es)");
+    }
+}
+
+/// Uncommitted changes to tracked files (untracked files don't block a merge).
+fn is_dirty(repo: &Path) -> bool {
+    match git(repo, &["status", "--porcelain", "--untracked-files=no"]) {
+        Ok(out) if out.status.success() => !out.stdout.is_empty(),
+        _ => false,
+    }
+}
+
+/// Bring the current branch up to its upstream. A branch that is only behind
+/// is always fast-forwarded; `mode` decides what happens when it diverged.
+fn integrate(repo: &Path, mode: PullMode) -> PullOutcome {
+    let Some((ahead, behind)) = git_ahead_behind(repo) else {
+        return PullOutcome::NoUpstream;
+    };
+    if behind == 0 {
+        return PullOutcome::UpToDate(ahead);
+    }
+    if ahead == 0 {
+        return match git(repo, &["merge", "--ff-only", "--quiet", "@{u}"]) {
+            Ok(out) if out.status.success() => PullOutcome::FastForwarded(behind),
+            Ok(out) => PullOutcome::Failed(last_line(&out.stderr)),
+            Err(e) => PullOutcome::Failed(e.to_string()),
+        };
+    }
+
+    let (args, abort, done): (&[&str], &str, PullOutcome) = match mode {
+        PullMode::FfOnly => return PullOutcome::Diverged { ahead, behind },
+        PullMode::Rebase => (
+            &["rebase", "--quiet", "@{u}"],
+            "rebase",
+            PullOutcome::Rebased { ahead, behind },
+        ),
+        PullMode::Merge => (
+            &["merge", "--no-edit", "--quiet", "@{u}"],
+            "merge",
+            PullOutcome::Merged { behind },
+        ),
+    };
+    match git(repo, args) {
+        Ok(out) if out.status.success() => done,
+        Ok(_) => {
+            // Never leave a repo mid-rebase or mid-merge
+            let _ = git(repo, &[abort, "--abort"]);
+            PullOutcome::Conflicted(format!("{abort} hit conflicts and was aborted"))
+        }
+        Err(e) => PullOutcome::Failed(e.to_string()),
+    }
+}
+
+fn last_line(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .rfind(|l| !l.trim().is_empty())
+        .unwrap_or("git exited with an error")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{clone_repo, git as run, init_repo};
+
+    #[test]
+    fn integrate_follows_upstream() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        init_repo(&upstream);
+        clone_repo(&upstream, &clone);
+        assert!(matches!(
+            integrate(&clone, PullMode::FfOnly),
+            PullOutcome::UpToDate(0)
+        ));
+
+        run(
+            &upstream,
+            &["commit", "--quiet", "--allow-empty", "-m", "two"],
+        );
+        fetch(&clone, None, false).unwrap();
+        assert!(matches!(
+            integrate(&clone, PullMode::FfOnly),
+            PullOutcome::FastForwarded(1)
+        ));
+
+        run(
+            &clone,
+            &["commit", "--quiet", "--allow-empty", "-m", "local"],
+        );
+        run(
+            &upstream,
+            &["commit", "--quiet", "--allow-empty", "-m", "three"],
+        );
+        fetch(&clone, None, false).unwrap();
+        assert!(matches!(
+            integrate(&clone, PullMode::FfOnly),
+            PullOutcome::Diverged {
+                ahead: 1,
+                behind: 1
+            }
+        ));
+        assert!(matches!(
+            integrate(&clone, PullMode::Rebase),
+            PullOutcome::Rebased {
+                ahead: 1,
+                behind: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn only_tracked_changes_count_as_dirty() {
+        let tmp = tempfile::tempdir().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("file"), "one").unwrap();
+        run(tmp.path(), &["add", "file"]);
+        run(tmp.path(), &["commit", "--quiet", "-m", "one"]);
+        std::fs::write(tmp.path().join("untracked"), "").unwrap();
+        assert!(!is_dirty(tmp.path()));
+        std::fs::write(tmp.path().join("file"), "two").unwrap();
+        assert!(is_dirty(tmp.path()));
+    }
+
+    #[test]
+    fn fetch_prune_reports_deleted_refs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let clone = tmp.path().join("clone");
+        init_repo(&upstream);
+        run(&upstream, &["branch", "gone"]);
+        clone_repo(&upstream, &clone);
+        run(&upstream, &["branch", "-D", "gone"]);
+
+        assert!(fetch(&clone, None, false).unwrap().is_empty());
+        assert_eq!(fetch(&clone, None, true).unwrap(), vec!["origin/gone"]);
+        assert!(fetch(&clone, None, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_deleted_refs_skips_other_fetch_output() {
+        let stderr = "From github.com:org/api\n - [deleted]         (none)     -> origin/feat-a\n   1234567..89abcde  main       -> origin/main\n";
+        assert_eq!(parse_deleted_refs(stderr), vec!["origin/feat-a"]);
+        assert!(parse_deleted_refs("").is_empty());
+    }
+
+    #[test]
+    fn diverged_is_reported_but_not_a_failure() {
+        let diverged = PullOutcome::Diverged {
+            ahead: 1,
+            behind: 2,
+        };
+        assert!(!diverged.is_failure());
+        assert!(PullOutcome::Conflicted("rebase".to_string()).is_failure());
+        assert!(PullOutcome::FetchFailed("offline".to_string()).is_failure());
+        assert!(!PullOutcome::Dirty.is_failure());
+    }
+}
@@ -0,0 +1,220 @@
+//! `meta git push` - push every repo that has commits its upstream lacks.
+//!
+//! A branch tracking an upstream is pushed when `HEAD` is ahead of it. A
+//! branch with no upstream yet is pushed with `-u` to its push remote
+//! (`branch.<name>.pushRemote`, then `remote.pushDefault`, then `origin`, then
+//! the only remote), so later pushes and pulls track it. Repos with nothing
+//! to push are reported and left out of the plan.
+//!
+//! Extra flags (`--force-with-lease`, `--no-verify`, ...) are added to every
+//! planned push. Args that choose what to push themselves (a remote or
+//! refspec, `--tags`, `--all`, `--delete`, ...) skip the planning: `git push
+//! <args>` runs as given in every repo in scope, as the plain passthrough did.
+
+use crate::git_env;
+use crate::helpers::{
+    get_all_repo_directories, git_ahead_behind, git_line, shell_quote, RepoScope,
+};
+use console::style;
+use meta_plugin_protocol::{CommandResult, PlannedCommand, PluginRequestOptions};
+use rayon::prelude::*;
+use std::path::Path;
+
+/// What `push` decided for one repo.
+#[derive(Debug)]
+enum PushPlan {
+    /// Ahead of its upstream by this many commits
+    Push(usize),
+    /// No upstream yet: `git push -u <remote> <branch>`
+    SetUpstream {
+        remote: String,
+        branch: String,
+    },
+    UpToDate,
+    Skip(String),
+}
+
+pub(crate) fn execute_git_push(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    parallel: bool,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let (scope, rest) = RepoScope::extract(args)?;
+    let dirs = scope.apply(
+        &get_all_repo_directories(projects, cwd)?,
+        cwd,
+        "the workspace",
+    )?;
+    let git_env = Some(git_env::git_env());
+    let extra: String = rest
+        .iter()
+        .map(|a| format!(" {}", shell_quote(a)))
+        .collect();
+
+    if picks_own_refs(&rest) {
+        let commands = dirs
+            .into_iter()
+            .map(|dir| PlannedCommand {
+                dir,
+                cmd: format!("git push{extra}"),
+                env: git_env.clone(),
+            })
+            .collect();
+        return Ok(crate::remote_plan(commands, parallel, cwd));
+    }
+
+    let plans: Vec<(String, PushPlan)> = dirs
+        .par_iter()
+        .map(|dir| {
+            let path = if dir == "." {
+                cwd.to_path_buf()
+            } else {
+                cwd.join(dir)
+            };
+            (dir.clone(), plan_push(&path))
+        })
+        .collect();
+
+    let mut commands = Vec::new();
+    for (dir, plan) in plans {
+        let note = match &plan {
+            PushPlan::Push(ahead) => format!("{ahead} commit(s) to push"),
+            PushPlan::SetUpstream { remote, branch } => {
+                format!("no upstream, will set {remote}/{branch}")
+            }
+            PushPlan::UpToDate => "up to date".to_string(),
+            PushPlan::Skip(reason) => reason.clone(),
+        };
+        if !options.json_output {
+            let icon = match plan {
+                PushPlan::Push(_) | PushPlan::SetUpstream { .. } => style("↑").cyan(),
+                PushPlan::UpToDate => style("✓").green(),
+                PushPlan::Skip(_) => style("-").yellow(),
+            };
+            println!("{icon} {} {}", style(&dir).bold(), style(note).dim());
+        }
+        let cmd = match plan {
+            PushPlan::Push(_) => format!("git push{extra}"),
+            PushPlan::SetUpstream { remote, branch } => format!(
+                "git push -u{extra} {} {}",
+                shell_quote(&remote),
+                shell_quote(&branch)
+            ),
+            PushPlan::UpToDate | PushPlan::Skip(_) => continue,
+        };
+        commands.push(PlannedCommand {
+            dir,
+            cmd,
+            env: git_env.clone(),
+        });
+    }
+
+    if commands.is_empty() {
+        return Ok(CommandResult::Message("Nothing to push.".to_string()));
+    }
+    Ok(crate::remote_plan(commands, parallel, cwd))
+}
+
+/// `git push` options that take their value as the next arg.
+const VALUE_FLAGS: &[&str] = &["-o", "--push-option", "--repo", "--receive-pack", "--exec"];
+
+/// Whether `args` say what to push (a remote, a refspec, or a flag like
+/// `--tags`), so per-repo planning would second-guess them.
+fn picks_own_refs(args: &[String]) -> bool {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--all" | "--branches" | "--mirror" | "--tags" | "--follow-tags" | "-d"
+            | "--delete" | "--prune" => return true,
+            flag if VALUE_FLAGS.contains(&flag) => {
+                iter.next();
+            }
+            flag if flag.starts_with('-') => {}
+            _ => return true,
+        }
+    }
+    false
+}
+
+fn plan_push(repo: &Path) -> PushPlan {
+    if !repo.join(".git").exists() {
+        return PushPlan::Skip("not cloned".to_string());
+    }
+    let Some(branch) = git_line(repo, &["symbolic-ref", "--quiet", "--short", "HEAD"]) else {
+        return PushPlan::Skip("detached HEAD".to_string());
+    };
+    if let Some((ahead, _behind)) = git_ahead_behind(repo) {
+        return if ahead > 0 {
+            PushPlan::Push(ahead)
+        } else {
+            PushPlan::UpToDate
+        };
+    }
+
+    let remotes: Vec<String> = git_line(repo, &["remote"])
+        .map(|out| out.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    let configured = git_line(
+        repo,
+        &["config", "--get", &format!("branch.{branch}.pushRemote")],
+    )
+    .or_else(|| git_line(repo, &["config", "--get", "remote.pushDefault"]));
+    match pick_push_remote(configured, &remotes) {
+        Some(remote) => PushPlan::SetUpstream { remote, branch },
+        None => PushPlan::Skip("no remote to push to".to_string()),
+    }
+}
+
+/// The configured push remote, else `origin`, else the only remote.
+fn pick_push_remote(configured: Option<String>, remotes: &[String]) -> Option<String> {
+    if configured.is_some() {
+        return configured;
+    }
+    if remotes.iter().any(|r| r == "origin") {
+        return Some("origin".to_string());
+    }
+    match remotes {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_remote_prefers_config_then_origin_then_only_remote() {
+        let remotes = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            pick_push_remote(Some("fork".to_string()), &remotes(&["origin", "fork"])),
+            Some("fork".to_string())
+        );
+        assert_eq!(
+            pick_push_remote(None, &remotes(&["upstream", "origin"])),
+            Some("origin".to_string())
+        );
+        assert_eq!(
+            pick_push_remote(None, &remotes(&["upstream"])),
+            Some("upstream".to_string())
+        );
+        assert_eq!(pick_push_remote(None, &remotes(&["a", "b"])), None);
+        assert_eq!(pick_push_remote(None, &[]), None);
+    }
+
+    #[test]
+    fn refspecs_and_ref_selecting_flags_skip_planning() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(!picks_own_refs(&args(&[])));
+        assert!(!picks_own_refs(&args(&[
+            "--force-with-lease",
+            "--no-verify"
+        ])));
+        assert!(!picks_own_refs(&args(&["-o", "ci.skip"])));
+        assert!(picks_own_refs(&args(&["origin", "feature"])));
+        assert!(picks_own_refs(&args(&["--tags"])));
+        assert!(picks_own_refs(&args(&["--delete", "origin", "old"])));
+    }
+}
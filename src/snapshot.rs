@@ -1,12 +1,147 @@
-use crate::helpers::get_all_repo_directories;
-use chrono::Utc;
+use crate::helpers::{get_all_repo_directories, git_lfs_available, RepoScope};
+use crate::MetaGitError;
+use chrono::{DateTime, Utc};
 use console::style;
 use dialoguer::Confirm;
+use indicatif::{ProgressBar, ProgressStyle};
 use meta_git_lib::snapshot::{self, RepoState, Snapshot};
-use meta_plugin_protocol::CommandResult;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
 use rayon::prelude::*;
-use std::collections::HashMap;
-use std::path::Path;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Submodule checkouts per repo, as (submodule path, SHA) pairs.
+///
+/// `RepoState` is defined in meta_git_lib, so these live in a sidecar file,
+/// `.meta-snapshots/submodules/<name>.json`. Snapshots without one (older
+/// ones, or workspaces without submodules) restore no submodules.
+type SubmoduleStates = BTreeMap<String, Vec<(String, String)>>;
+
+fn submodules_path(cwd: &Path, name: &str) -> PathBuf {
+    cwd.join(".meta-snapshots")
+        .join("submodules")
+        .join(format!("{name}.json"))
+}
+
+fn load_submodules(cwd: &Path, name: &str) -> anyhow::Result<SubmoduleStates> {
+    let path = submodules_path(cwd, name);
+    if !path.exists() {
+        return Ok(SubmoduleStates::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(&path)?)?)
+}
+
+/// Per repo tracking Git LFS files: whether every LFS object was downloaded
+/// (`false` when some are still pointer files). Sidecar
+/// `.meta-snapshots/lfs/<name>.json`, only recorded when `git lfs` is installed.
+type LfsStates = BTreeMap<String, bool>;
+
+fn lfs_path(cwd: &Path, name: &str) -> PathBuf {
+    cwd.join(".meta-snapshots")
+        .join("lfs")
+        .join(format!("{name}.json"))
+}
+
+fn load_lfs(cwd: &Path, name: &str) -> anyhow::Result<LfsStates> {
+    let path = lfs_path(cwd, name);
+    if !path.exists() {
+        return Ok(LfsStates::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(&path)?)?)
+}
+
+/// `None` when `repo` tracks no LFS files or `git lfs` isn't installed.
+fn capture_lfs(repo: &Path) -> Option<bool> {
+    if !git_lfs_available() {
+        return None;
+    }
+    parse_lfs_ls_files(&git_in(repo, &["lfs", "ls-files"]).ok()?)
+}
+
+/// `git lfs ls-files` lines are `<oid> <*|-> <path>`: `*` when the object is
+/// present, `-` when only the pointer is checked out.
+fn parse_lfs_ls_files(output: &str) -> Option<bool> {
+    let markers: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .collect();
+    (!markers.is_empty()).then(|| markers.iter().all(|m| *m == "*"))
+}
+
+/// Write a sidecar file, or remove a stale one left by an earlier snapshot
+/// of the same name when there's nothing to record.
+fn write_sidecar(path: &Path, contents: Option<String>) -> anyhow::Result<()> {
+    match contents {
+        Some(contents) => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, contents)?;
+        }
+        None if path.exists() => std::fs::remove_file(path)?,
+        None => {}
+    }
+    Ok(())
+}
+
+/// Free-text description from `snapshot create -m`, in a sidecar file
+/// `.meta-snapshots/descriptions/<name>.txt` for the same reason.
+fn description_path(cwd: &Path, name: &str) -> PathBuf {
+    cwd.join(".meta-snapshots")
+        .join("descriptions")
+        .join(format!("{name}.txt"))
+}
+
+fn load_description(cwd: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(description_path(cwd, name))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Initialized submodules of `repo` and their checked-out SHAs.
+fn capture_submodules(repo: &Path) -> Vec<(String, String)> {
+    if !repo.join(".gitmodules").exists() {
+        return vec![];
+    }
+    Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["submodule", "status", "--recursive"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_submodule_status(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse `git submodule status` lines (` <sha> <path> (<describe>)`, prefixed
+/// `+` when moved off the recorded commit, `-` when not initialized).
+fn parse_submodule_status(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('-'))
+        .filter_map(|line| {
+            let mut parts = line.trim_start_matches([' ', '+', 'U']).split_whitespace();
+            let sha = parts.next()?;
+            let path = parts.next()?;
+            Some((path.to_string(), sha.to_string()))
+        })
+        .collect()
+}
+
+/// Initialize submodules, then check each one out at its recorded SHA.
+fn restore_submodules(repo: &Path, submodules: &[(String, String)]) -> Result<(), String> {
+    git_in(repo, &["submodule", "update", "--init", "--recursive"])?;
+    for (path, sha) in submodules {
+        git_in(&repo.join(path), &["checkout", "--quiet", "--detach", sha])
+            .map_err(|e| format!("submodule {path}: {e}"))?;
+    }
+    Ok(())
+}
 
 /// Show snapshot help text
 pub(crate) fn execute_snapshot_help() -> anyhow::Result<CommandResult> {
@@ -22,7 +157,9 @@ Commands:
   {}        List all available snapshots
   {}        Show details of a snapshot
   {}     Restore all repos to a snapshot state
+  {}        Compare a snapshot with another or with the workspace
   {}      Delete a snapshot
+  {}       Delete old snapshots by count or age
 
 Examples:
   meta git snapshot create before-upgrade
@@ -30,7 +167,9 @@ Examples:
   meta git snapshot show before-upgrade
   meta git snapshot restore before-upgrade --dry-run
   meta git snapshot restore before-upgrade --force
+  meta git snapshot diff before-upgrade
   meta git snapshot delete before-upgrade
+  meta git snapshot prune --keep-last 5 --dry-run
 
 Snapshots capture the entire workspace state (recursive by default).
 Use --force to skip confirmation on restore, --dry-run to preview."#,
@@ -40,7 +179,9 @@ Use --force to skip confirmation on restore, --dry-run to preview."#,
         style("list").cyan(),
         style("show <name>").cyan(),
         style("restore <name>").cyan(),
+        style("diff <a> [b]").cyan(),
         style("delete <name>").cyan(),
+        style("prune").cyan(),
     );
     Ok(CommandResult::Message(String::new()))
 }
@@ -61,20 +202,33 @@ pub(crate) fn execute_snapshot_command_help(command: &str) -> CommandResult {
         "create" => {
             r#"meta git snapshot create - Save workspace git state
 
-Usage: meta git snapshot create <NAME>
+Usage: meta git snapshot create <NAME> [--only-dirty] [-m MESSAGE] [--repo ALIAS]... [--exclude ALIAS]...
+
+Records each repo's current SHA, branch, and dirty status, plus the checked-out
+SHA of every initialized submodule.
 
-Records each repo's current SHA, branch, and dirty status.
+Options:
+  --only-dirty          Capture only repos with uncommitted changes. Restoring
+                        such a snapshot leaves every other repo untouched.
+  -m, --message TEXT    Describe why the snapshot was taken; shown by list
+                        and show
+  --repo ALIAS          Capture only this repo (repeatable); ALIAS is a path,
+                        a .meta project name, or a unique last path segment
+  --exclude ALIAS       Leave this repo out (repeatable)
 
 Examples:
   meta git snapshot create before-refactor
-  meta git snapshot create before-upgrade"#
+  meta git snapshot create before-upgrade -m "before migrating to tokio 1.40"
+  meta git snapshot create wip --only-dirty
+  meta git snapshot create api-only --repo api"#
         }
         "list" => {
             r#"meta git snapshot list - List saved workspace snapshots
 
 Usage: meta git snapshot list
 
-Shows snapshot names, creation times, repo counts, and dirty repo counts.
+Shows snapshot names, creation times, repo counts, dirty repo counts, and
+descriptions.
 
 Examples:
   meta git snapshot list"#
@@ -82,27 +236,59 @@ Examples:
         "show" => {
             r#"meta git snapshot show - Display one snapshot
 
-Usage: meta git snapshot show <NAME>
+Usage: meta git snapshot show <NAME> [--repo ALIAS]... [--exclude ALIAS]...
 
-Shows per-repo branch, SHA, and dirty state recorded in a snapshot.
+Shows the description and per-repo branch, SHA, and dirty state recorded in a
+snapshot. --repo/--exclude limit the listing to some repos.
 
 Examples:
-  meta git snapshot show before-refactor"#
+  meta git snapshot show before-refactor
+  meta git snapshot show before-refactor --repo api"#
         }
         "restore" => {
             r#"meta git snapshot restore - Restore workspace git state
 
-Usage: meta git snapshot restore <NAME> [--force] [--dry-run]
+Usage: meta git snapshot restore <NAME> [--force] [--dry-run] [--no-stash|--discard]
+                                 [--repo ALIAS]... [--exclude ALIAS]...
 
-Restores repos to the recorded branches/SHAs. Dirty repos are stashed before restore.
+Restores repos to the recorded branches/SHAs. A recorded branch is checked out
+again when it still points at the SHA; with --force a moved branch is reset to
+it, and a deleted branch is recreated (after asking, unless --force). Detached
+snapshots restore to a detached HEAD. Dirty repos are stashed before restore.
+Submodules are initialized and checked out at their recorded SHAs.
 
 Options:
-  --force     Skip confirmation
+  --force     Skip confirmation (including the --discard one)
   --dry-run   Preview restore actions without changing repos
+  --no-stash  Leave repos with uncommitted changes untouched
+  --discard   Throw away uncommitted changes (git reset --hard) before restoring;
+              asks for a second confirmation
+  --no-auto-snapshot
+              Don't save the current state as auto-before-restore-<timestamp>
+              first (the newest 10 auto-snapshots are kept)
+  --repo ALIAS
+              Restore only this repo (repeatable); other repos in the snapshot
+              are left alone
+  --exclude ALIAS
+              Don't restore this repo (repeatable)
 
 Examples:
   meta git snapshot restore before-refactor --dry-run
-  meta git snapshot restore before-refactor --force"#
+  meta git snapshot restore before-refactor --force
+  meta git snapshot restore before-refactor --repo api --repo web"#
+        }
+        "diff" => {
+            r#"meta git snapshot diff - Compare snapshots
+
+Usage: meta git snapshot diff <NAME> [OTHER]
+
+With one name, compares the snapshot against the current workspace; with two,
+compares NAME (before) to OTHER (after). Lists each repo whose SHA, branch, or
+dirty state changed, and repos added or removed. Supports --json.
+
+Examples:
+  meta git snapshot diff before-upgrade
+  meta git snapshot diff before-upgrade after-upgrade"#
         }
         "delete" => {
             r#"meta git snapshot delete - Delete a saved snapshot
@@ -111,6 +297,25 @@ Usage: meta git snapshot delete <NAME>
 
 Examples:
   meta git snapshot delete before-refactor"#
+        }
+        "prune" => {
+            r#"meta git snapshot prune - Delete old snapshots
+
+Usage: meta git snapshot prune [--keep-last N] [--older-than DURATION] [--dry-run] [--force]
+
+Deletes snapshots, auto-snapshots included, that no retention rule keeps.
+With both options, a snapshot survives if it is among the newest N or is
+younger than DURATION.
+
+Options:
+  --keep-last N            Keep the N most recently created snapshots
+  --older-than DURATION    Keep snapshots younger than DURATION (e.g. 30d, 12h)
+  --dry-run                List what would be deleted without deleting
+  --force                  Skip confirmation
+
+Examples:
+  meta git snapshot prune --keep-last 5 --dry-run
+  meta git snapshot prune --older-than 30d --force"#
         }
         _ => {
             return CommandResult::ShowHelp(Some(format!(
@@ -122,31 +327,26 @@ Examples:
     CommandResult::Message(help.to_string())
 }
 
-/// Create a snapshot of the current workspace state
-pub(crate) fn execute_snapshot_create(
-    args: &[String],
-    projects: &[String],
-    cwd: &Path,
-) -> anyhow::Result<CommandResult> {
-    // Parse snapshot name from args
-    let name = args
-        .iter()
-        .find(|a| !a.starts_with('-'))
-        .ok_or_else(|| anyhow::anyhow!("Usage: meta git snapshot create <name>"))?;
+/// Name prefix of snapshots taken automatically before destructive commands.
+pub(crate) const AUTO_SNAPSHOT_PREFIX: &str = "auto-";
 
-    // Get all repos (recursive by default)
-    let dirs = get_all_repo_directories(projects, cwd)?;
+/// Auto-snapshots kept; older ones are deleted whenever a new one is taken.
+const AUTO_SNAPSHOT_KEEP: usize = 10;
 
-    println!(
-        "Creating snapshot '{}' of {} repos...",
-        style(name).cyan(),
-        dirs.len()
-    );
+/// What a snapshot records about one repo.
+struct RepoCapture {
+    state: RepoState,
+    submodules: Vec<(String, String)>,
+    lfs: Option<bool>,
+}
 
-    // Capture repo states in parallel
-    let results: Vec<_> = dirs
-        .par_iter()
-        .map(|dir| {
+/// One directory's capture: `None` when it isn't a git repo.
+type Capture = Option<Result<RepoCapture, String>>;
+
+/// Capture each dir's state in parallel; `only_dirty` drops clean repos and non-repos.
+fn capture_workspace(dirs: &[String], cwd: &Path, only_dirty: bool) -> Vec<(String, Capture)> {
+    dirs.par_iter()
+        .filter_map(|dir| {
             let path = if dir == "." {
                 cwd.to_path_buf()
             } else {
@@ -154,18 +354,204 @@ pub(crate) fn execute_snapshot_create(
             };
 
             if !path.exists() || !snapshot::is_git_repo(&path) {
-                return (dir.clone(), None);
+                return (!only_dirty).then(|| (dir.clone(), None));
             }
 
-            let state = snapshot::capture_repo_state(&path);
-            (dir.clone(), Some(state))
+            let state = snapshot::capture_repo_state(&path)
+                .map(|state| RepoCapture {
+                    state,
+                    submodules: capture_submodules(&path),
+                    lfs: capture_lfs(&path),
+                })
+                .map_err(|e| e.to_string());
+            if only_dirty && matches!(&state, Ok(c) if !c.state.dirty) {
+                return None;
+            }
+            Some((dir.clone(), Some(state)))
         })
+        .collect()
+}
+
+/// Save the repos captured successfully as snapshot `name`, with its
+/// submodule and LFS sidecars. `None` when nothing was captured.
+fn save_captured(
+    name: &str,
+    results: &[(String, Capture)],
+    cwd: &Path,
+) -> anyhow::Result<Option<Snapshot>> {
+    let mut repos = HashMap::new();
+    let mut submodules = SubmoduleStates::new();
+    let mut lfs = LfsStates::new();
+    for (dir, result) in results {
+        if let Some(Ok(capture)) = result {
+            if !capture.submodules.is_empty() {
+                submodules.insert(dir.clone(), capture.submodules.clone());
+            }
+            if let Some(complete) = capture.lfs {
+                lfs.insert(dir.clone(), complete);
+            }
+            repos.insert(dir.clone(), capture.state.clone());
+        }
+    }
+    if repos.is_empty() {
+        return Ok(None);
+    }
+
+    let snap = Snapshot {
+        name: name.to_string(),
+        created: Utc::now(),
+        repos,
+    };
+    snapshot::save_snapshot(cwd, &snap)?;
+    write_sidecar(
+        &submodules_path(cwd, name),
+        (!submodules.is_empty())
+            .then(|| serde_json::to_string_pretty(&submodules))
+            .transpose()?,
+    )?;
+    write_sidecar(
+        &lfs_path(cwd, name),
+        (!lfs.is_empty())
+            .then(|| serde_json::to_string_pretty(&lfs))
+            .transpose()?,
+    )?;
+    Ok(Some(snap))
+}
+
+/// `snapshot::load_snapshot`, with an unknown name reported as not found.
+pub(crate) fn load_named(cwd: &Path, name: &str) -> anyhow::Result<Snapshot> {
+    require_snapshot(cwd, name)?;
+    snapshot::load_snapshot(cwd, name)
+}
+
+fn require_snapshot(cwd: &Path, name: &str) -> anyhow::Result<()> {
+    if !snapshot::list_snapshots(cwd)?
+        .iter()
+        .any(|info| info.name == name)
+    {
+        return Err(MetaGitError::NotFound(format!("Snapshot '{name}' not found")).into());
+    }
+    Ok(())
+}
+
+/// Delete a snapshot and its sidecar files.
+fn remove_snapshot(cwd: &Path, name: &str) -> anyhow::Result<()> {
+    snapshot::delete_snapshot(cwd, name)?;
+    for sidecar in [
+        submodules_path(cwd, name),
+        lfs_path(cwd, name),
+        description_path(cwd, name),
+    ] {
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar)?;
+        }
+    }
+    Ok(())
+}
+
+/// `base`, or `base-2`, `base-3`, ... when a snapshot already has that name.
+fn unique_name(base: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|name| !taken.contains(name))
+        .expect("unbounded range")
+}
+
+/// Quietly snapshot `dirs` as `auto-before-<operation>-<timestamp>` (with a
+/// `-N` suffix when that second is taken) before a destructive command, then
+/// drop auto-snapshots beyond the newest [`AUTO_SNAPSHOT_KEEP`]. Returns the
+/// snapshot name.
+pub(crate) fn auto_snapshot(
+    operation: &str,
+    dirs: &[String],
+    cwd: &Path,
+) -> anyhow::Result<String> {
+    let base = format!(
+        "{AUTO_SNAPSHOT_PREFIX}before-{operation}-{}",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let taken: HashSet<String> = snapshot::list_snapshots(cwd)?
+        .into_iter()
+        .map(|info| info.name)
+        .collect();
+    let name = unique_name(&base, &taken);
+    let results = capture_workspace(dirs, cwd, false);
+    if save_captured(&name, &results, cwd)?.is_none() {
+        anyhow::bail!(
+            "Auto-snapshot failed: no repos could be captured (use --no-auto-snapshot to skip it)"
+        );
+    }
+
+    let mut autos: Vec<_> = snapshot::list_snapshots(cwd)?
+        .into_iter()
+        .filter(|info| info.name.starts_with(AUTO_SNAPSHOT_PREFIX))
         .collect();
+    autos.sort_by(|a, b| b.created.cmp(&a.created));
+    for info in autos.iter().skip(AUTO_SNAPSHOT_KEEP) {
+        remove_snapshot(cwd, &info.name)?;
+    }
+
+    println!(
+        "{} Auto-snapshot saved as {}",
+        style("✓").green(),
+        style(&name).cyan()
+    );
+    Ok(name)
+}
+
+/// Create a snapshot of the current workspace state
+pub(crate) fn execute_snapshot_create(
+    args: &[String],
+    projects: &[String],
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    // Parse snapshot name and options from args
+    let (scope, args) = RepoScope::extract(args)?;
+    let mut name = None;
+    let mut message = None;
+    let mut only_dirty = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-m" | "--message" => {
+                let text = iter.next().ok_or_else(|| {
+                    MetaGitError::InvalidArgument(format!("{arg} requires a message"))
+                })?;
+                message = Some(text.trim().to_string());
+            }
+            "--only-dirty" => only_dirty = true,
+            other if !other.starts_with('-') && name.is_none() => name = Some(other),
+            _ => {}
+        }
+    }
+    let name = name.ok_or_else(|| {
+        MetaGitError::InvalidArgument(
+            "Usage: meta git snapshot create <name> [-m <message>]".to_string(),
+        )
+    })?;
+
+    // Get all repos (recursive by default)
+    let dirs = scope.apply(
+        &get_all_repo_directories(projects, cwd)?,
+        cwd,
+        "the workspace",
+    )?;
+
+    println!(
+        "Creating snapshot '{}' of {} repos{}...",
+        style(name).cyan(),
+        dirs.len(),
+        if only_dirty { " (dirty only)" } else { "" }
+    );
+
+    // Capture repo states in parallel; --only-dirty drops clean repos here
+    let results = capture_workspace(&dirs, cwd, only_dirty);
 
     // Process results sequentially for display
-    let mut repos = HashMap::new();
     let mut dirty_count = 0;
-
     for (dir, result) in &results {
         match result {
             None => {
@@ -175,14 +561,13 @@ pub(crate) fn execute_snapshot_create(
                     dir
                 );
             }
-            Some(Ok(state)) => {
+            Some(Ok(RepoCapture { state, .. })) => {
                 if state.dirty {
                     dirty_count += 1;
                     println!("  {} {} (dirty)", style("○").yellow(), dir);
                 } else {
                     println!("  {} {}", style("✓").green(), dir);
                 }
-                repos.insert(dir.clone(), state.clone());
             }
             Some(Err(e)) => {
                 println!("  {} {} (error: {})", style("✗").red(), dir, e);
@@ -190,17 +575,16 @@ pub(crate) fn execute_snapshot_create(
         }
     }
 
-    if repos.is_empty() {
+    let Some(snap) = save_captured(name, &results, cwd)? else {
+        if only_dirty {
+            anyhow::bail!("No repos have uncommitted changes; nothing to capture");
+        }
         anyhow::bail!("No repos captured");
-    }
-
-    let snap = Snapshot {
-        name: name.clone(),
-        created: Utc::now(),
-        repos,
     };
-
-    snapshot::save_snapshot(cwd, &snap)?;
+    write_sidecar(
+        &description_path(cwd, name),
+        message.filter(|m| !m.is_empty()).map(|m| m + "\n"),
+    )?;
 
     println!();
     println!(
@@ -243,12 +627,22 @@ pub(crate) fn execute_snapshot_list(cwd: &Path) -> anyhow::Result<CommandResult>
         } else {
             String::new()
         };
+        // Auto-snapshots are a safety net, not something the user named
+        let name = if info.name.starts_with(AUTO_SNAPSHOT_PREFIX) {
+            style(format!("{} (auto)", info.name)).dim()
+        } else {
+            style(info.name.clone()).cyan().bold()
+        };
+        let description = load_description(cwd, &info.name)
+            .map(|d| format!(" - {d}"))
+            .unwrap_or_default();
         println!(
-            "  {} - {} repos{} - {}",
-            style(&info.name).cyan().bold(),
+            "  {} - {} repos{} - {}{}",
+            name,
             info.repo_count,
             style(dirty_note).yellow(),
-            style(info.created.format("%Y-%m-%d %H:%M:%S")).dim()
+            style(info.created.format("%Y-%m-%d %H:%M:%S")).dim(),
+            style(description).dim()
         );
     }
     println!();
@@ -258,20 +652,31 @@ pub(crate) fn execute_snapshot_list(cwd: &Path) -> anyhow::Result<CommandResult>
 
 /// Show details of a snapshot
 pub(crate) fn execute_snapshot_show(args: &[String], cwd: &Path) -> anyhow::Result<CommandResult> {
-    let name = args
-        .iter()
-        .find(|a| !a.starts_with('-'))
-        .ok_or_else(|| anyhow::anyhow!("Usage: meta git snapshot show <name>"))?;
+    let (scope, args) = RepoScope::extract(args)?;
+    let name = args.iter().find(|a| !a.starts_with('-')).ok_or_else(|| {
+        MetaGitError::InvalidArgument("Usage: meta git snapshot show <name>".to_string())
+    })?;
 
-    let snap = snapshot::load_snapshot(cwd, name)?;
+    let snap = load_named(cwd, name)?;
+    let submodules = load_submodules(cwd, name)?;
+    let lfs = load_lfs(cwd, name)?;
+    let recorded: Vec<String> = snap.repos.keys().cloned().collect();
+    let shown = scope.apply(&recorded, cwd, &format!("snapshot '{name}'"))?;
 
     println!("Snapshot: {}", style(&snap.name).cyan().bold());
     println!("Created:  {}", snap.created.format("%Y-%m-%d %H:%M:%S UTC"));
     println!("Repos:    {}", snap.repos.len());
+    if let Some(description) = load_description(cwd, name) {
+        println!("Message:  {description}");
+    }
     println!();
 
     // Sort repos by name for consistent output
-    let mut repos: Vec<_> = snap.repos.iter().collect();
+    let mut repos: Vec<_> = snap
+        .repos
+        .iter()
+        .filter(|(repo, _)| shown.contains(*repo))
+        .collect();
     repos.sort_by(|a, b| a.0.cmp(b.0));
 
     for (name, state) in repos {
@@ -286,51 +691,242 @@ pub(crate) fn execute_snapshot_show(args: &[String], cwd: &Path) -> anyhow::Resu
         } else {
             String::new()
         };
+        let lfs_marker = match lfs.get(name) {
+            Some(true) => format!(" {}", style("(lfs)").dim()),
+            Some(false) => format!(" {}", style("(lfs, objects missing)").yellow()),
+            None => String::new(),
+        };
 
         println!(
-            "  {} {}{}{}",
+            "  {} {}{}{}{}",
             style(&state.sha[..8]).dim(),
             name,
             style(branch_info).cyan(),
-            dirty_marker
+            dirty_marker,
+            lfs_marker
         );
+        for (path, sha) in submodules.get(name).into_iter().flatten() {
+            println!(
+                "      {} {}",
+                style(sha.get(..8).unwrap_or(sha)).dim(),
+                style(path).dim()
+            );
+        }
     }
 
     Ok(CommandResult::Message(String::new()))
 }
 
+/// What `snapshot restore` does with a repo that has uncommitted changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirtyPolicy {
+    /// Stash the changes, then restore (default)
+    Stash,
+    /// Leave the repo as it is (`--no-stash`)
+    Skip,
+    /// `git reset --hard`, then restore (`--discard`)
+    Discard,
+}
+
+impl DirtyPolicy {
+    fn preview(self) -> &'static str {
+        match self {
+            DirtyPolicy::Stash => "will be stashed",
+            DirtyPolicy::Skip => "will be skipped",
+            DirtyPolicy::Discard => "changes will be DISCARDED",
+        }
+    }
+}
+
+/// Throw away uncommitted changes to tracked files before a `--discard` restore.
+fn discard_changes(repo: &Path) -> Result<(), String> {
+    git_in(repo, &["reset", "--hard", "--quiet"]).map(|_| ())
+}
+
+/// Run git in `repo`, returning trimmed stdout, or the last stderr line on failure.
+fn git_in(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if out.status.success() {
+        return Ok(String::from_utf8_lossy(&out.stdout).trim().to_string());
+    }
+    Err(String::from_utf8_lossy(&out.stderr)
+        .lines()
+        .last()
+        .unwrap_or("git failed")
+        .trim()
+        .to_string())
+}
+
+/// `restore_repo_state` leaves HEAD detached at the recorded SHA; put the
+/// recorded branch back on top of it where that's safe.
+///
+/// - branch still at the SHA: switch to it
+/// - branch moved: reset it to the SHA with `--force`, otherwise stay detached
+/// - branch gone: recreate it at the SHA (asks first unless `--force`)
+///
+/// Returns how the repo ended up, for the per-repo output.
+fn reattach_branch(
+    repo: &Path,
+    state: &RepoState,
+    force: bool,
+    ask: &(dyn Fn(&str) -> bool + Sync),
+) -> String {
+    let short = state.sha.get(..8).unwrap_or(&state.sha);
+    let detached = format!("detached at {short}");
+    let Some(branch) = &state.branch else {
+        return detached;
+    };
+    let restored = format!("restored to branch {branch}");
+    let tip = git_in(
+        repo,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ],
+    );
+    let result = match tip {
+        Ok(tip) if tip == state.sha => git_in(repo, &["checkout", "--quiet", branch]),
+        Ok(_) if force => git_in(repo, &["checkout", "--quiet", "-B", branch, &state.sha]),
+        Ok(_) => return format!("{detached} ({branch} has moved on; --force resets it)"),
+        Err(_) => {
+            let recreate = force
+                || ask(&format!(
+                    "Branch {branch} no longer exists; recreate it at {short}?"
+                ));
+            if !recreate {
+                return format!("{detached} ({branch} no longer exists)");
+            }
+            git_in(repo, &["checkout", "--quiet", "-b", branch, &state.sha])
+        }
+    };
+    match result {
+        Ok(_) => restored,
+        Err(e) => format!("{detached} (could not check out {branch}: {e})"),
+    }
+}
+
+/// How restoring one repo ended.
+enum RestoreOutcome {
+    Restored {
+        /// From [`reattach_branch`]
+        message: String,
+        stashed: bool,
+        discarded: bool,
+    },
+    /// Dirty, and `--no-stash` left it alone
+    Skipped,
+    Failed(String),
+}
+
+/// Restore one repo to `state`. `dirty` is the policy to apply when the repo
+/// has uncommitted changes, `None` when it is clean; `ask` confirms
+/// recreating a deleted branch.
+fn restore_one(
+    path: &Path,
+    state: &RepoState,
+    dirty: Option<DirtyPolicy>,
+    submodules: Option<&Vec<(String, String)>>,
+    force: bool,
+    ask: &(dyn Fn(&str) -> bool + Sync),
+) -> RestoreOutcome {
+    let mut discarded = false;
+    match dirty {
+        Some(DirtyPolicy::Skip) => return RestoreOutcome::Skipped,
+        Some(DirtyPolicy::Discard) => {
+            if let Err(e) = discard_changes(path) {
+                return RestoreOutcome::Failed(format!("failed to discard changes: {e}"));
+            }
+            discarded = true;
+        }
+        Some(DirtyPolicy::Stash) | None => {}
+    }
+
+    let result = match snapshot::restore_repo_state(path, state, force) {
+        Ok(result) => result,
+        Err(e) => return RestoreOutcome::Failed(e.to_string()),
+    };
+    if !result.success {
+        return RestoreOutcome::Failed(result.message);
+    }
+    let message = reattach_branch(path, state, force, ask);
+    if let Some(subs) = submodules {
+        if let Err(e) = restore_submodules(path, subs) {
+            return RestoreOutcome::Failed(format!("{message} but {e}"));
+        }
+    }
+    RestoreOutcome::Restored {
+        message,
+        stashed: result.stashed,
+        discarded,
+    }
+}
+
 /// Restore workspace to a snapshot state
 pub(crate) fn execute_snapshot_restore(
     args: &[String],
-    _projects: &[String],
+    projects: &[String],
     dry_run: bool,
     cwd: &Path,
 ) -> anyhow::Result<CommandResult> {
     // Parse args
+    let (scope, args) = RepoScope::extract(args)?;
     let mut name: Option<&str> = None;
+    let mut auto_snapshot_enabled = true;
     let mut force = false;
     let mut dry_run = dry_run;
+    let mut no_stash = false;
+    let mut discard = false;
 
-    for arg in args {
+    for arg in &args {
         match arg.as_str() {
             "--force" | "-f" => force = true,
             "--dry-run" => dry_run = true,
+            "--no-stash" => no_stash = true,
+            "--discard" => discard = true,
+            "--no-auto-snapshot" => auto_snapshot_enabled = false,
             s if !s.starts_with('-') => name = Some(s),
             _ => {}
         }
     }
 
     let name = name.ok_or_else(|| {
-        anyhow::anyhow!("Usage: meta git snapshot restore <name> [--force] [--dry-run]")
+        MetaGitError::InvalidArgument(
+            "Usage: meta git snapshot restore <name> [--force] [--dry-run] [--no-stash|--discard]"
+                .to_string(),
+        )
     })?;
+    let policy = match (no_stash, discard) {
+        (true, true) => {
+            return Err(MetaGitError::InvalidArgument(
+                "--no-stash and --discard are mutually exclusive".to_string(),
+            )
+            .into())
+        }
+        (true, false) => DirtyPolicy::Skip,
+        (false, true) => DirtyPolicy::Discard,
+        (false, false) => DirtyPolicy::Stash,
+    };
 
-    let snap = snapshot::load_snapshot(cwd, name)?;
+    let snap = load_named(cwd, name)?;
+    let submodules = load_submodules(cwd, name)?;
+    let recorded: Vec<String> = snap.repos.keys().cloned().collect();
+    let in_scope = scope.apply(&recorded, cwd, &format!("snapshot '{name}'"))?;
 
     // Analyze what would change
     let mut repos_to_restore: Vec<(&str, &RepoState, bool)> = Vec::new();
     let mut missing_repos = Vec::new();
 
     for (repo_name, state) in &snap.repos {
+        if !in_scope.contains(repo_name) {
+            continue;
+        }
         let path = if repo_name == "." {
             cwd.to_path_buf()
         } else {
@@ -361,10 +957,19 @@ pub(crate) fn execute_snapshot_restore(
     );
     if dirty_count > 0 {
         println!(
-            "  - {} repos have uncommitted changes (will be stashed)",
-            style(dirty_count).yellow()
+            "  - {} repos have uncommitted changes ({})",
+            style(dirty_count).yellow(),
+            policy.preview()
         );
     }
+    let submodule_count: usize = repos_to_restore
+        .iter()
+        .filter_map(|(repo, _, _)| submodules.get(*repo))
+        .map(Vec::len)
+        .sum();
+    if submodule_count > 0 {
+        println!("  - {submodule_count} submodules will be updated to their recorded SHA");
+    }
     if !missing_repos.is_empty() {
         println!(
             "  - {} repos missing (will be skipped): {}",
@@ -390,51 +995,314 @@ pub(crate) fn execute_snapshot_restore(
             println!("Aborted.");
             return Ok(CommandResult::Message(String::new()));
         }
-    }
 
-    // Execute restore
-    println!("Restoring...");
-    let mut success_count = 0;
-    let mut fail_count = 0;
+        // Discarding work can't be undone, so it gets its own prompt
+        if policy == DirtyPolicy::Discard && dirty_count > 0 {
+            let dirty: Vec<&str> = repos_to_restore
+                .iter()
+                .filter(|(_, _, d)| *d)
+                .map(|(repo, _, _)| *repo)
+                .collect();
+            let discard = Confirm::new()
+                .with_prompt(format!(
+                    "Permanently discard uncommitted changes in {}?",
+                    dirty.join(", ")
+                ))
+                .default(false)
+                .interact()?;
+            if !discard {
+                println!("Aborted.");
+                return Ok(CommandResult::Message(String::new()));
+            }
+        }
+    }
 
-    for (repo_name, state, _is_dirty) in &repos_to_restore {
-        let path = if *repo_name == "." {
-            cwd.to_path_buf()
+    // Safety net: the state being replaced, restorable the same way
+    if auto_snapshot_enabled {
+        let dirs = if scope.is_empty() {
+            get_all_repo_directories(projects, cwd)?
         } else {
-            cwd.join(repo_name)
+            in_scope.clone()
         };
+        auto_snapshot("restore", &dirs, cwd)?;
+    }
 
-        let result = snapshot::restore_repo_state(&path, state, force)?;
-
-        if result.success {
-            let stash_note = if result.stashed {
-                format!(" {}", style("(stashed changes)").yellow())
+    // Execute restore. Repos are independent (each stash lives in its own
+    // repo), so they run in parallel; results print afterwards in repo order.
+    let pb = ProgressBar::new(repos_to_restore.len() as u64);
+    pb.set_style(
+        ProgressStyle::with_template("Restoring [{bar:30}] {pos}/{len} {wide_msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    // One branch-recreation prompt at a time, with the bar out of the way
+    let prompt_lock = Mutex::new(());
+    let ask = |prompt: &str| {
+        let _guard = prompt_lock.lock().unwrap_or_else(|e| e.into_inner());
+        pb.suspend(|| {
+            Confirm::new()
+                .with_prompt(prompt)
+                .default(true)
+                .interact()
+                .unwrap_or(false)
+        })
+    };
+    let mut outcomes: Vec<(&str, RestoreOutcome)> = repos_to_restore
+        .par_iter()
+        .map(|(repo_name, state, is_dirty)| {
+            let path = if *repo_name == "." {
+                cwd.to_path_buf()
             } else {
-                String::new()
+                cwd.join(repo_name)
             };
-            println!(
-                "  {} {} {}{}",
-                style("✓").green(),
-                repo_name,
-                result.message,
-                stash_note
+            let dirty_policy = is_dirty.then_some(policy);
+            let outcome = restore_one(
+                &path,
+                state,
+                dirty_policy,
+                submodules.get(*repo_name),
+                force,
+                &ask,
             );
-            success_count += 1;
-        } else {
-            println!("  {} {} {}", style("✗").red(), repo_name, result.message);
-            fail_count += 1;
+            pb.set_message(repo_name.to_string());
+            pb.inc(1);
+            (*repo_name, outcome)
+        })
+        .collect();
+    pb.finish_and_clear();
+    outcomes.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut success_count = 0;
+    let mut fail_count = 0;
+    let mut skipped_count = 0;
+    for (repo_name, outcome) in &outcomes {
+        match outcome {
+            RestoreOutcome::Restored {
+                message,
+                stashed,
+                discarded,
+            } => {
+                let stash_note = if *stashed {
+                    format!(" {}", style("(stashed changes)").yellow())
+                } else if *discarded {
+                    format!(" {}", style("(discarded changes)").red())
+                } else {
+                    String::new()
+                };
+                println!(
+                    "  {} {} {}{}",
+                    style("✓").green(),
+                    repo_name,
+                    message,
+                    stash_note
+                );
+                success_count += 1;
+            }
+            RestoreOutcome::Skipped => {
+                println!(
+                    "  {} {} {}",
+                    style("-").yellow(),
+                    repo_name,
+                    style("(skipped: uncommitted changes)").yellow()
+                );
+                skipped_count += 1;
+            }
+            RestoreOutcome::Failed(message) => {
+                println!("  {} {} {}", style("✗").red(), repo_name, message);
+                fail_count += 1;
+            }
         }
     }
 
     println!();
+    let skipped_note = if skipped_count > 0 {
+        format!(", {skipped_count} skipped (uncommitted changes)")
+    } else {
+        String::new()
+    };
     if fail_count > 0 {
         println!(
-            "Restored {} repo(s), {} failed",
+            "Restored {} repo(s), {} failed{skipped_note}",
             style(success_count).green(),
             style(fail_count).red()
         );
     } else {
-        println!("{} Restored {} repo(s)", style("✓").green(), success_count);
+        println!(
+            "{} Restored {} repo(s){skipped_note}",
+            style("✓").green(),
+            success_count
+        );
+    }
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+/// The parts of a repo's state that `snapshot diff` compares.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct DiffSide {
+    sha: String,
+    branch: Option<String>,
+    dirty: bool,
+}
+
+impl From<&RepoState> for DiffSide {
+    fn from(state: &RepoState) -> Self {
+        DiffSide {
+            sha: state.sha.clone(),
+            branch: state.branch.clone(),
+            dirty: state.dirty,
+        }
+    }
+}
+
+/// One repo that differs; `before`/`after` is `None` where the repo is absent.
+#[derive(Debug, Serialize)]
+struct RepoDiff {
+    repo: String,
+    before: Option<DiffSide>,
+    after: Option<DiffSide>,
+}
+
+#[derive(Serialize)]
+struct SnapshotDiffOutput {
+    from: String,
+    to: String,
+    repos: Vec<RepoDiff>,
+}
+
+/// Repos whose state differs between `before` and `after`, sorted by name.
+fn diff_states(
+    before: &BTreeMap<String, DiffSide>,
+    after: &BTreeMap<String, DiffSide>,
+) -> Vec<RepoDiff> {
+    let names: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (b, a) = (before.get(name), after.get(name));
+            (b != a).then(|| RepoDiff {
+                repo: name.clone(),
+                before: b.cloned(),
+                after: a.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Compare a snapshot with another one, or with the current workspace
+pub(crate) fn execute_snapshot_diff(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let names: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+    let (from, to) = match names.as_slice() {
+        [from] => (*from, None),
+        [from, to] => (*from, Some(*to)),
+        _ => {
+            return Err(MetaGitError::InvalidArgument(
+                "Usage: meta git snapshot diff <name> [other]".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let sides = |snap: &Snapshot| -> BTreeMap<String, DiffSide> {
+        snap.repos
+            .iter()
+            .map(|(name, state)| (name.clone(), DiffSide::from(state)))
+            .collect()
+    };
+    let before = sides(&load_named(cwd, from)?);
+    let after = match to {
+        Some(to) => sides(&load_named(cwd, to)?),
+        None => get_all_repo_directories(projects, cwd)?
+            .par_iter()
+            .filter_map(|dir| {
+                let path = if dir == "." {
+                    cwd.to_path_buf()
+                } else {
+                    cwd.join(dir)
+                };
+                if !path.exists() || !snapshot::is_git_repo(&path) {
+                    return None;
+                }
+                let state = snapshot::capture_repo_state(&path).ok()?;
+                Some((dir.clone(), DiffSide::from(&state)))
+            })
+            .collect(),
+    };
+    let to_label = to.cloned().unwrap_or_else(|| "workspace".to_string());
+    let repos = diff_states(&before, &after);
+
+    if options.json_output {
+        let output = SnapshotDiffOutput {
+            from: from.clone(),
+            to: to_label,
+            repos,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    println!(
+        "Comparing '{}' -> '{}':",
+        style(from).cyan(),
+        style(&to_label).cyan()
+    );
+    if repos.is_empty() {
+        println!("  {} No differences", style("✓").green());
+        return Ok(CommandResult::Message(String::new()));
+    }
+    let short = |sha: &str| sha.get(..8).unwrap_or(sha).to_string();
+    let branch = |side: &DiffSide| side.branch.clone().unwrap_or_else(|| "(detached)".into());
+    for diff in &repos {
+        match (&diff.before, &diff.after) {
+            (None, Some(after)) => println!(
+                "  {} {} {}",
+                style("+").green(),
+                diff.repo,
+                style(format!(
+                    "(added at {} {})",
+                    short(&after.sha),
+                    branch(after)
+                ))
+                .dim()
+            ),
+            (Some(_), None) => println!(
+                "  {} {} {}",
+                style("-").red(),
+                diff.repo,
+                style("(removed)").dim()
+            ),
+            (Some(before), Some(after)) => {
+                let mut notes = Vec::new();
+                if before.sha != after.sha {
+                    notes.push(format!("{} -> {}", short(&before.sha), short(&after.sha)));
+                }
+                if before.branch != after.branch {
+                    notes.push(format!("branch {} -> {}", branch(before), branch(after)));
+                }
+                if before.dirty != after.dirty {
+                    notes.push(
+                        if after.dirty {
+                            "now dirty"
+                        } else {
+                            "now clean"
+                        }
+                        .to_string(),
+                    );
+                }
+                println!(
+                    "  {} {} {}",
+                    style("~").yellow(),
+                    diff.repo,
+                    notes.join(", ")
+                );
+            }
+            (None, None) => {}
+        }
     }
 
     Ok(CommandResult::Message(String::new()))
@@ -445,12 +1313,12 @@ pub(crate) fn execute_snapshot_delete(
     args: &[String],
     cwd: &Path,
 ) -> anyhow::Result<CommandResult> {
-    let name = args
-        .iter()
-        .find(|a| !a.starts_with('-'))
-        .ok_or_else(|| anyhow::anyhow!("Usage: meta git snapshot delete <name>"))?;
+    let name = args.iter().find(|a| !a.starts_with('-')).ok_or_else(|| {
+        MetaGitError::InvalidArgument("Usage: meta git snapshot delete <name>".to_string())
+    })?;
 
-    snapshot::delete_snapshot(cwd, name)?;
+    require_snapshot(cwd, name)?;
+    remove_snapshot(cwd, name)?;
 
     println!(
         "{} Deleted snapshot '{}'",
@@ -460,3 +1328,252 @@ pub(crate) fn execute_snapshot_delete(
 
     Ok(CommandResult::Message(String::new()))
 }
+
+/// `secs` before `now`; `None` when that is out of range for a timestamp.
+fn cutoff_before(now: DateTime<Utc>, secs: u64) -> Option<DateTime<Utc>> {
+    let age = chrono::TimeDelta::try_seconds(i64::try_from(secs).ok()?)?;
+    now.checked_sub_signed(age)
+}
+
+/// Names of the snapshots no rule keeps: neither among the newest
+/// `keep_last` nor created after `cutoff`.
+fn select_for_prune<'a>(
+    snapshots: &[(&'a str, DateTime<Utc>)],
+    keep_last: Option<usize>,
+    cutoff: Option<DateTime<Utc>>,
+) -> Vec<&'a str> {
+    let mut newest_first = snapshots.to_vec();
+    newest_first.sort_by(|a, b| b.1.cmp(&a.1));
+    newest_first
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, (_, created))| {
+            let kept_by_count = matches!(keep_last, Some(n) if *idx < n);
+            let kept_by_age = matches!(cutoff, Some(cutoff) if *created > cutoff);
+            !kept_by_count && !kept_by_age
+        })
+        .map(|(_, (name, _))| name)
+        .collect()
+}
+
+/// Delete snapshots beyond a count or age
+pub(crate) fn execute_snapshot_prune(
+    args: &[String],
+    dry_run: bool,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let mut keep_last = None;
+    let mut cutoff = None;
+    let mut force = false;
+    let mut dry_run = dry_run;
+    let mut idx = 0;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--keep-last" => {
+                let Some(n) = args.get(idx + 1).and_then(|v| v.parse::<usize>().ok()) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--keep-last requires a number".to_string(),
+                    )
+                    .into());
+                };
+                keep_last = Some(n);
+                idx += 1;
+            }
+            "--older-than" => {
+                let Some(value) = args.get(idx + 1) else {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--older-than requires a duration (e.g. 30d)".to_string(),
+                    )
+                    .into());
+                };
+                match meta_git_lib::worktree::helpers::parse_duration(value) {
+                    Ok(secs) => match cutoff_before(Utc::now(), secs) {
+                        Some(at) => cutoff = Some(at),
+                        None => {
+                            return Err(MetaGitError::InvalidArgument(format!(
+                                "Invalid --older-than '{value}': duration is too large"
+                            ))
+                            .into())
+                        }
+                    },
+                    Err(e) => {
+                        return Err(MetaGitError::InvalidArgument(format!(
+                            "Invalid --older-than '{value}': {e}"
+                        ))
+                        .into())
+                    }
+                }
+                idx += 1;
+            }
+            "--force" => force = true,
+            "--dry-run" => dry_run = true,
+            _ => {}
+        }
+        idx += 1;
+    }
+    if keep_last.is_none() && cutoff.is_none() {
+        return Err(MetaGitError::InvalidArgument(
+            "Usage: meta git snapshot prune [--keep-last N] [--older-than DURATION]".to_string(),
+        )
+        .into());
+    }
+
+    let infos = snapshot::list_snapshots(cwd)?;
+    let snapshots: Vec<_> = infos
+        .iter()
+        .map(|info| (info.name.as_str(), info.created))
+        .collect();
+    let to_delete = select_for_prune(&snapshots, keep_last, cutoff);
+
+    if to_delete.is_empty() {
+        println!("{} No snapshots to prune", style("✓").green());
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    let prefix = if dry_run {
+        format!("{} Would delete", style("[DRY RUN]").cyan())
+    } else {
+        "Deleting".to_string()
+    };
+    println!(
+        "{prefix} {} of {} snapshot(s):",
+        to_delete.len(),
+        snapshots.len()
+    );
+    for name in &to_delete {
+        let created = snapshots
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, created)| created.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        println!("  {} {}", style(name).cyan(), style(created).dim());
+    }
+    if dry_run {
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    if !force {
+        let proceed = Confirm::new()
+            .with_prompt(format!("Delete {} snapshot(s)?", to_delete.len()))
+            .default(false)
+            .interact()?;
+        if !proceed {
+            println!("Aborted.");
+            return Ok(CommandResult::Message(String::new()));
+        }
+    }
+
+    for name in &to_delete {
+        remove_snapshot(cwd, name)?;
+    }
+    println!(
+        "{} Deleted {} snapshot(s)",
+        style("✓").green(),
+        to_delete.len()
+    );
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_states_reports_changes_additions_and_removals() {
+        let side = |sha: &str, branch: Option<&str>, dirty: bool| DiffSide {
+            sha: sha.to_string(),
+            branch: branch.map(str::to_string),
+            dirty,
+        };
+        let before = BTreeMap::from([
+            ("api".to_string(), side("aaa", Some("main"), false)),
+            ("old".to_string(), side("bbb", Some("main"), false)),
+            ("web".to_string(), side("ccc", Some("main"), false)),
+        ]);
+        let after = BTreeMap::from([
+            ("api".to_string(), side("ddd", Some("feature"), true)),
+            ("new".to_string(), side("eee", None, false)),
+            ("web".to_string(), side("ccc", Some("main"), false)),
+        ]);
+        let diffs = diff_states(&before, &after);
+        let repos: Vec<&str> = diffs.iter().map(|d| d.repo.as_str()).collect();
+        assert_eq!(repos, vec!["api", "new", "old"]);
+        assert!(diffs[1].before.is_none());
+        assert!(diffs[2].after.is_none());
+    }
+
+    #[test]
+    fn auto_snapshot_names_in_the_same_second_get_a_suffix() {
+        let base = "auto-before-prune-20260101-120000";
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name(base, &taken), base);
+        taken.insert(base.to_string());
+        assert_eq!(unique_name(base, &taken), format!("{base}-2"));
+        taken.insert(format!("{base}-2"));
+        assert_eq!(unique_name(base, &taken), format!("{base}-3"));
+    }
+
+    #[test]
+    fn cutoff_before_rejects_out_of_range_ages() {
+        let now = Utc::now();
+        assert_eq!(
+            cutoff_before(now, 60),
+            Some(now - chrono::Duration::seconds(60))
+        );
+        assert_eq!(cutoff_before(now, u64::MAX), None);
+        assert_eq!(cutoff_before(now, i64::MAX as u64), None);
+    }
+
+    #[test]
+    fn prune_keeps_snapshots_matched_by_either_rule() {
+        let now = Utc::now();
+        let days_ago = |d: i64| now - chrono::Duration::days(d);
+        let snapshots = [
+            ("old", days_ago(40)),
+            ("newest", days_ago(1)),
+            ("middle", days_ago(10)),
+            ("older", days_ago(20)),
+        ];
+
+        assert_eq!(
+            select_for_prune(&snapshots, Some(2), None),
+            vec!["older", "old"]
+        );
+        assert_eq!(
+            select_for_prune(&snapshots, None, Some(days_ago(15))),
+            vec!["older", "old"]
+        );
+        // Both rules: a snapshot survives if either keeps it
+        assert_eq!(
+            select_for_prune(&snapshots, Some(3), Some(days_ago(30))),
+            vec!["old"]
+        );
+        assert!(select_for_prune(&snapshots, Some(10), None).is_empty());
+    }
+
+    #[test]
+    fn lfs_ls_files_reports_missing_objects() {
+        assert_eq!(parse_lfs_ls_files(""), None);
+        assert_eq!(
+            parse_lfs_ls_files("4d7a214614 * assets/logo.png\n9f86d08188 * data.bin\n"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_lfs_ls_files("4d7a214614 * assets/logo.png\n9f86d08188 - data.bin\n"),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn submodule_status_skips_uninitialized() {
+        let output = " 1a2b3c4d vendor/lib (v1.2.0)\n+5e6f7a8b tools/gen (heads/main)\n-9c0d1e2f docs/theme\n";
+        assert_eq!(
+            parse_submodule_status(output),
+            vec![
+                ("vendor/lib".to_string(), "1a2b3c4d".to_string()),
+                ("tools/gen".to_string(), "5e6f7a8b".to_string()),
+            ]
+        );
+    }
+}
@@ -2,8 +2,9 @@ use console::style;
 use std::collections::BTreeSet;
 use std::path::Path;
 
-/// Discover unique SSH remote URLs from the .meta config in the current directory.
-/// Returns the full SSH URLs (preserving user, host, and port) so callers can
+/// Discover unique SSH remote URLs from the .meta config in the current directory,
+/// after git's `insteadOf` rewrites (so an https:// entry the user routes over
+/// SSH counts). Returns the full SSH URLs (preserving user, host, and port) so callers can
 /// pass them directly to `establish_ssh_masters` without information loss.
 /// Returns an empty list if no .meta config is found or no SSH URLs exist.
 pub fn discover_ssh_urls(cwd: &Path) -> Vec<String> {
@@ -17,9 +18,9 @@ pub fn discover_ssh_urls(cwd: &Path) -> Vec<String> {
 
     let urls: BTreeSet<String> = projects
         .iter()
-        .filter_map(|p| p.repo.as_ref())
+        .filter_map(|p| p.repo.as_deref())
+        .map(crate::url_rewrite::resolve_insteadof)
         .filter(|repo| meta_git_lib::extract_ssh_host(repo).is_some())
-        .cloned()
         .collect();
 
     urls.into_iter().collect()
@@ -103,4 +104,5 @@ pub(crate) fn warn_remote_mismatches(cwd: &Path) {
             m.path, m.expected
         );
     }
+    eprintln!("  Or run: meta git fix-remotes");
 }
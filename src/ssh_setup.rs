@@ -8,6 +8,9 @@ use log::{debug, warn};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+/// Concurrent sessions one ControlMaster serves (sshd's default MaxSessions).
+pub const SSH_MAX_SESSIONS: usize = 10;
+
 /// Result of SSH multiplexing setup.
 pub enum SshMasters {
     /// We established masters; callers should inject `GIT_SSH_COMMAND`.
@@ -138,7 +141,9 @@ pub fn establish_ssh_masters(urls: &[&str]) -> SshMasters {
     let mut targets: Vec<SshTarget> = Vec::new();
     let mut seen = std::collections::HashSet::new();
     for url in urls {
-        if let Some(target) = parse_ssh_target(url) {
+        // Connect to the host git will use after the user's insteadOf rules
+        let url = crate::url_rewrite::resolve_insteadof(url);
+        if let Some(target) = parse_ssh_target(&url) {
             let key = target.dedup_key();
             if seen.insert(key) {
                 targets.push(target);
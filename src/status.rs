@@ -1,13 +1,26 @@
 use crate::git_env;
-use crate::helpers::get_project_directories_with_fallback;
+use crate::helpers::{get_project_directories_with_fallback, git_output};
+use crate::MetaGitError;
+use console::style;
 use meta_plugin_protocol::{CommandResult, PlannedCommand, PluginRequestOptions};
+use rayon::prelude::*;
+use serde::Serialize;
 use std::path::Path;
 
+/// Commits listed per repo in `--since` human output.
+const SINCE_LOG_LIMIT: usize = 5;
+
 pub(crate) fn execute_git_status(
+    args: &[String],
     projects: &[String],
     options: &PluginRequestOptions,
+    parallel: bool,
     cwd: &Path,
 ) -> anyhow::Result<CommandResult> {
+    if let Some(since) = parse_since(args)? {
+        return execute_status_since(&since, projects, options, cwd);
+    }
+
     // Return an execution plan - let loop_lib handle execution, dry-run, and JSON output
     // Use projects from meta_cli if available (enables --recursive), otherwise read local .meta
     let dirs = get_project_directories_with_fallback(projects, cwd)?;
@@ -24,5 +37,168 @@ pub(crate) fn execute_git_status(
         })
         .collect();
 
-    Ok(CommandResult::Plan(commands, Some(options.parallel)))
+    Ok(CommandResult::Plan(commands, Some(parallel)))
+}
+
+fn parse_since(args: &[String]) -> anyhow::Result<Option<String>> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--since=") {
+            return Ok(Some(value.to_string()));
+        }
+        if arg == "--since" {
+            return match iter.next() {
+                Some(value) => Ok(Some(value.clone())),
+                None => {
+                    return Err(MetaGitError::InvalidArgument(
+                        "--since requires a ref (tag, branch or SHA)".to_string(),
+                    )
+                    .into())
+                }
+            };
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Serialize)]
+struct SinceRepoEntry {
+    dir: String,
+    /// False when the repo doesn't have the ref
+    has_ref: bool,
+    commits_since: usize,
+    /// Most recent commits since the ref as `<short-sha> <subject>`
+    commits: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SinceOutput {
+    since: String,
+    repos: Vec<SinceRepoEntry>,
+}
+
+/// `meta git status --since <ref>`: commits on HEAD past `<ref>` in each repo.
+fn execute_status_since(
+    since: &str,
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+
+    let repos: Vec<SinceRepoEntry> = dirs
+        .par_iter()
+        .map(|dir| {
+            let path = cwd.join(dir);
+            let range = format!("{since}..HEAD");
+            let has_ref = git_output(
+                &path,
+                &[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{since}^{{commit}}"),
+                ],
+            )
+            .is_some();
+            let commits_since = has_ref
+                .then(|| git_output(&path, &["rev-list", "--count", &range]))
+                .flatten()
+                .and_then(|out| out.trim().parse().ok())
+                .unwrap_or(0);
+            let commits = if commits_since > 0 {
+                let limit = SINCE_LOG_LIMIT.to_string();
+                git_output(
+                    &path,
+                    &["log", "--oneline", "--no-decorate", "-n", &limit, &range],
+                )
+                .map(|out| out.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+            } else {
+                vec![]
+            };
+            SinceRepoEntry {
+                dir: dir.clone(),
+                has_ref,
+                commits_since,
+                commits,
+            }
+        })
+        .collect();
+
+    if options.json_output {
+        let output = SinceOutput {
+            since: since.to_string(),
+            repos,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    let mut moved = 0;
+    for repo in &repos {
+        if !repo.has_ref {
+            println!(
+                "{} {} {}",
+                style("-").dim(),
+                style(&repo.dir).bold(),
+                style(format!("(no ref '{since}', skipped)")).dim()
+            );
+            continue;
+        }
+        if repo.commits_since == 0 {
+            println!(
+                "{} {} {}",
+                style("✓").green(),
+                style(&repo.dir).bold(),
+                style("up to date").dim()
+            );
+            continue;
+        }
+        moved += 1;
+        println!(
+            "{} {} {} commit{} since {since}",
+            style("↑").yellow(),
+            style(&repo.dir).bold(),
+            repo.commits_since,
+            if repo.commits_since == 1 { "" } else { "s" }
+        );
+        for commit in &repo.commits {
+            println!("    {commit}");
+        }
+        if repo.commits_since > repo.commits.len() {
+            println!(
+                "    {}",
+                style(format!(
+                    "... and {} more",
+                    repo.commits_since - repo.commits.len()
+                ))
+                .dim()
+            );
+        }
+    }
+    println!();
+    println!("{moved} of {} repos moved past {since}", repos.len());
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_accepts_both_forms() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_since(&args(&["--since", "v1.0"])).unwrap().as_deref(),
+            Some("v1.0")
+        );
+        assert_eq!(
+            parse_since(&args(&["--since=main"])).unwrap().as_deref(),
+            Some("main")
+        );
+        assert_eq!(parse_since(&args(&["-s"])).unwrap(), None);
+        assert!(parse_since(&args(&["--since"])).is_err());
+    }
 }
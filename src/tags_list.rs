@@ -0,0 +1,135 @@
+//! `meta git tags-list <tag>` - report which repos carry a tag, and at what commit.
+
+use crate::helpers::{get_project_directories_with_fallback, git_output};
+use crate::MetaGitError;
+use console::style;
+use meta_plugin_protocol::{CommandResult, PluginRequestOptions};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct TagRepoEntry {
+    dir: String,
+    has_tag: bool,
+    /// Commit the tag points at (annotated tags are peeled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TagsListOutput {
+    tag: String,
+    /// With --missing, only the repos lacking the tag
+    repos: Vec<TagRepoEntry>,
+    tagged: usize,
+    missing: usize,
+}
+
+pub(crate) fn execute_git_tags_list(
+    args: &[String],
+    projects: &[String],
+    options: &PluginRequestOptions,
+    cwd: &Path,
+) -> anyhow::Result<CommandResult> {
+    let missing_only = args.iter().any(|a| a == "--missing");
+    let Some(tag) = args.iter().find(|a| !a.starts_with('-')) else {
+        return Err(MetaGitError::InvalidArgument(
+            "Usage: meta git tags-list <tag> [--missing]".to_string(),
+        )
+        .into());
+    };
+
+    let dirs = get_project_directories_with_fallback(projects, cwd)?;
+    let repos: Vec<TagRepoEntry> = dirs
+        .par_iter()
+        .map(|dir| {
+            let path = cwd.join(dir);
+            let sha = tag_commit(&path, tag);
+            TagRepoEntry {
+                dir: dir.clone(),
+                has_tag: sha.is_some(),
+                sha,
+            }
+        })
+        .collect();
+
+    let tagged = repos.iter().filter(|r| r.has_tag).count();
+    let missing = repos.len() - tagged;
+    let repos: Vec<TagRepoEntry> = if missing_only {
+        repos.into_iter().filter(|r| !r.has_tag).collect()
+    } else {
+        repos
+    };
+
+    if options.json_output {
+        let output = TagsListOutput {
+            tag: tag.clone(),
+            repos,
+            tagged,
+            missing,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(CommandResult::Message(String::new()));
+    }
+
+    for repo in &repos {
+        match &repo.sha {
+            Some(sha) => println!(
+                "{} {} {}",
+                style("✓").green(),
+                style(&repo.dir).bold(),
+                style(&sha[..sha.len().min(12)]).dim()
+            ),
+            None => println!(
+                "{} {} {}",
+                style("✗").red(),
+                style(&repo.dir).bold(),
+                style("(missing)").dim()
+            ),
+        }
+    }
+    if !repos.is_empty() {
+        println!();
+    }
+    println!(
+        "{tagged} of {} repos have tag {}",
+        tagged + missing,
+        style(tag).cyan()
+    );
+
+    Ok(CommandResult::Message(String::new()))
+}
+
+/// Commit a tag points at, or `None` when the repo has no such tag.
+///
+/// `git tag -l` makes sure the name is a tag and not a branch of the same name.
+fn tag_commit(repo: &Path, tag: &str) -> Option<String> {
+    let listed = git_output(repo, &["tag", "-l", tag])?;
+    if listed.trim().is_empty() {
+        return None;
+    }
+    let sha = git_output(repo, &["rev-list", "-n", "1", &format!("refs/tags/{tag}")])?;
+    Some(sha.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+
+    #[test]
+    fn tag_commit_ignores_branches_with_the_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+        git(dir.path(), &["branch", "v1.0"]);
+        assert_eq!(tag_commit(dir.path(), "v1.0"), None);
+
+        git(dir.path(), &["tag", "-a", "-m", "release", "v2.0"]);
+        let head = git_output(dir.path(), &["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(
+            tag_commit(dir.path(), "v2.0"),
+            Some(head.trim().to_string())
+        );
+    }
+}
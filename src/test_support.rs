@@ -0,0 +1,53 @@
+//! Fixtures for tests that need real git repos.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Run git in `dir` with a throwaway identity, failing the test if git
+/// fails. Returns stdout.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr).trim()
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+/// Create `dir` as a repo on `main` with one empty commit.
+///
+/// The identity goes into the repo's config too, so code under test can
+/// commit, merge or rebase there.
+pub(crate) fn init_repo(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "--quiet", "-b", "main"]);
+    set_identity(dir);
+    git(dir, &["commit", "--quiet", "--allow-empty", "-m", "base"]);
+}
+
+/// Clone `origin` to `dest`, with the test identity configured.
+pub(crate) fn clone_repo(origin: &Path, dest: &Path) {
+    let parent = dest.parent().unwrap();
+    git(
+        parent,
+        &[
+            "clone",
+            "--quiet",
+            origin.to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+    );
+    set_identity(dest);
+}
+
+fn set_identity(dir: &Path) {
+    git(dir, &["config", "user.name", "t"]);
+    git(dir, &["config", "user.email", "t@t"]);
+}
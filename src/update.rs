@@ -1,19 +1,61 @@
-use crate::clone_worker::clone_with_queue;
+use crate::clone_events::CloneEventLog;
+use crate::clone_worker::{
+    clone_git_config, clone_with_queue, split_patterns, CloneOptions, RepoPathFilter,
+    DEFAULT_CLONE_RETRIES,
+};
+use crate::helpers::get_all_repo_directories;
+use crate::pull::PullMode;
 use crate::ssh_setup;
+use crate::MetaGitError;
 use console::style;
+use dialoguer::Confirm;
 use indicatif::MultiProgress;
 use meta_core::config;
 use meta_git_lib::clone_queue::CloneQueue;
 use meta_plugin_protocol::CommandResult;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::process::Command;
 use std::sync::Arc;
 
 pub(crate) fn execute_git_update(
+    args: &[String],
     projects: &[String],
     dry_run: bool,
     cwd: &std::path::Path,
 ) -> anyhow::Result<CommandResult> {
+    let prune = args.iter().any(|a| a == "--prune");
+    let force = args.iter().any(|a| a == "--force");
+    let auto_snapshot = !args.iter().any(|a| a == "--no-auto-snapshot");
+    let pull = !args.iter().any(|a| a == "--no-pull");
+    let modes: Vec<PullMode> = args
+        .iter()
+        .filter_map(|a| match a.as_str() {
+            "--ff-only" => Some(PullMode::FfOnly),
+            "--rebase" => Some(PullMode::Rebase),
+            "--merge" => Some(PullMode::Merge),
+            _ => None,
+        })
+        .collect();
+    if modes.len() > 1 {
+        return Err(MetaGitError::InvalidArgument(
+            "--ff-only, --rebase and --merge are mutually exclusive".to_string(),
+        )
+        .into());
+    }
+    let pull_mode = modes.first().copied().unwrap_or_default();
+    let event_log = args
+        .iter()
+        .position(|a| a == "--event-log")
+        .and_then(|i| args.get(i + 1));
+    let patterns = |flag: &str| -> Vec<String> {
+        args.windows(2)
+            .filter(|w| w[0] == flag)
+            .flat_map(|w| split_patterns(&w[1]).collect::<Vec<_>>())
+            .collect()
+    };
+    let path_filter = RepoPathFilter::new(cwd, &patterns("--include"), &patterns("--exclude"))?;
+
     // Determine if we're in recursive mode (projects list provided by meta_cli)
     let recursive = !projects.is_empty();
 
@@ -42,6 +84,7 @@ pub(crate) fn execute_git_update(
     }
 
     // First pass: check for orphaned repos and warn user
+    let mut orphans: Vec<Orphan> = Vec::new();
     for dir in &dirs_to_check {
         let Some((meta_path, _format)) = config::find_meta_config_in(dir) else {
             continue;
@@ -69,34 +112,89 @@ pub(crate) fn execute_git_update(
                         } else {
                             dir.join(name).to_string_lossy().to_string()
                         };
-                        eprintln!(
-                            "{} {} exists locally but is not in .meta. To remove: rm -rf {}",
-                            style("⚠").yellow(),
-                            style(&relative_path).yellow().bold(),
-                            relative_path
-                        );
+                        if !prune {
+                            eprintln!(
+                                "{} {} exists locally but is not in .meta. To remove: rm -rf {}",
+                                style("⚠").yellow(),
+                                style(&relative_path).yellow().bold(),
+                                relative_path
+                            );
+                        }
+                        // A parent of tracked nested paths (e.g. `libs` for `libs/core`)
+                        let parent_of_tracked = config_projects
+                            .iter()
+                            .any(|p| p.starts_with(&format!("{name}/")));
+                        orphans.push(Orphan {
+                            display: relative_path,
+                            path,
+                            parent_of_tracked,
+                        });
                     }
                 }
             }
         }
     }
 
+    // Opt-in: remove orphaned repos that hold no local work. Stale
+    // remote-tracking refs are pruned by the fetch below.
+    if prune {
+        if auto_snapshot && !dry_run && !orphans.is_empty() {
+            // Record the orphans' SHAs too, as a record of what was removed;
+            // `snapshot restore` skips missing repos, so it won't re-clone them
+            let mut dirs = get_all_repo_directories(projects, cwd)?;
+            dirs.extend(orphans.iter().map(|o| o.display.clone()));
+            crate::snapshot::auto_snapshot("prune", &dirs, cwd)?;
+        }
+        prune_orphans(&orphans, force, dry_run)?;
+    }
+
+    // Fetch all existing repos (the meta repo included) in parallel, then
+    // fast-forward each; --no-pull only clones what's missing (and, with
+    // --prune, still fetches to drop stale remote-tracking refs)
+    let pull_failures = if pull || prune {
+        let repos: Vec<(String, PathBuf)> = get_all_repo_directories(projects, cwd)?
+            .into_iter()
+            .map(|dir| {
+                let path = cwd.join(&dir);
+                (dir, path)
+            })
+            .filter(|(_, path)| path.join(".git").exists())
+            .collect();
+        let mode = pull.then_some(pull_mode);
+        crate::pull::pull_existing_repos(&repos, mode, prune, dry_run, cwd)?
+    } else {
+        0
+    };
+
     // Create the clone queue - unlimited depth for recursive mode
     let meta_depth = if recursive { None } else { Some(0) };
-    let queue = Arc::new(CloneQueue::new(None, meta_depth)); // No git depth for update
+    let seeded = CloneQueue::new(None, meta_depth);
 
-    // Seed the queue from all known .meta files
+    // Seed from all known .meta files
     for dir in &dirs_to_check {
         // Determine relative depth based on whether it's the cwd or nested
         let depth_level = if dir.as_path() == cwd { 0 } else { 1 };
-        queue.push_from_meta(dir, depth_level)?;
+        seeded.push_from_meta(dir, depth_level)?;
     }
 
-    let (_, initial_count) = queue.get_counts();
+    // Only queue what --include/--exclude allow, so every count below is of
+    // repos that will actually be cloned
+    let queue = Arc::new(CloneQueue::new(None, meta_depth)); // No git depth for update
+    let mut initial_count = 0;
+    for task in seeded.drain_all() {
+        let wanted = match &path_filter {
+            Some(filter) => filter.matches(&task.target_path),
+            None => true,
+        };
+        if wanted {
+            queue.push(task);
+            initial_count += 1;
+        }
+    }
 
     if initial_count == 0 {
         println!("All repositories are already cloned.");
-        return Ok(CommandResult::Message(String::new()));
+        return update_result(pull_failures);
     }
 
     if dry_run {
@@ -105,8 +203,7 @@ pub(crate) fn execute_git_update(
             style("[DRY RUN]").cyan(),
             initial_count
         );
-        let tasks = queue.drain_all();
-        for task in tasks {
+        for task in queue.drain_all() {
             println!("  git clone {} {}", task.url, task.target_path.display());
         }
         return Ok(CommandResult::Message(String::new()));
@@ -114,7 +211,7 @@ pub(crate) fn execute_git_update(
 
     // Establish SSH multiplexing before parallel clones
     let queue_urls = queue.peek_urls();
-    let mut parallel = 4_usize;
+    let mut parallel = crate::user_config::clone_jobs();
     let ssh_cmd = if !queue_urls.is_empty() {
         let url_refs: Vec<&str> = queue_urls.iter().map(|s| s.as_str()).collect();
         match ssh_setup::establish_ssh_masters(&url_refs) {
@@ -138,18 +235,233 @@ pub(crate) fn execute_git_update(
 
     let mp = MultiProgress::new();
 
-    clone_with_queue(Arc::clone(&queue), parallel, &mp, ssh_cmd.as_deref())?;
+    let clone_options = CloneOptions {
+        ssh_cmd,
+        git_config: clone_git_config(cwd, false),
+        retries: DEFAULT_CLONE_RETRIES,
+        event_log: match event_log {
+            Some(path) => Some(Arc::new(CloneEventLog::open(&cwd.join(path))?)),
+            None => None,
+        },
+        path_filter,
+        ..Default::default()
+    };
+    let report = clone_with_queue(Arc::clone(&queue), parallel, &mp, &clone_options)?;
+    if report.interrupted {
+        return Err(MetaGitError::Other("Update interrupted".to_string()).into());
+    }
 
-    let (completed, total) = queue.get_counts();
+    let (_, total) = queue.get_counts();
     if total > initial_count {
         println!(
             "Update completed ({} repos cloned, {} discovered via nested .meta files)",
-            completed,
+            report.cloned,
             total - initial_count
         );
     } else {
-        println!("Update completed ({completed} repos cloned)");
+        println!("Update completed ({} repos cloned)", report.cloned);
     }
 
+    update_result(pull_failures)
+}
+
+fn update_result(pull_failures: usize) -> anyhow::Result<CommandResult> {
+    if pull_failures > 0 {
+        return Err(MetaGitError::GitFailed(format!(
+            "{pull_failures} repo(s) could not be updated"
+        ))
+        .into());
+    }
     Ok(CommandResult::Message(String::new()))
 }
+
+/// A git repo on disk that no `.meta` lists.
+struct Orphan {
+    display: String,
+    path: PathBuf,
+    parent_of_tracked: bool,
+}
+
+/// Why an orphan must be kept, if anything in it would be lost by removing it.
+fn orphan_keep_reason(orphan: &Orphan) -> Option<&'static str> {
+    if orphan.parent_of_tracked {
+        return Some("contains repos still listed in .meta");
+    }
+    if let Some((meta_path, _)) = config::find_meta_config_in(&orphan.path) {
+        let has_children = config::parse_meta_config(&meta_path)
+            .map(|(projects, _)| {
+                projects
+                    .iter()
+                    .any(|p| orphan.path.join(&p.path).join(".git").exists())
+            })
+            .unwrap_or(true);
+        if has_children {
+            return Some("nested .meta with cloned children");
+        }
+    }
+    let git_output = |args: &[&str]| -> Option<Vec<u8>> {
+        let out = Command::new("git")
+            .arg("-C")
+            .arg(&orphan.path)
+            .args(args)
+            .output()
+            .ok()?;
+        out.status.success().then_some(out.stdout)
+    };
+    // When git can't answer, assume the worst
+    let non_empty = |args: &[&str]| !matches!(git_output(args), Some(out) if out.is_empty());
+    if non_empty(&["status", "--porcelain"]) {
+        return Some("uncommitted changes");
+    }
+    // Ignored files (.env, build output, notes) don't show in plain status
+    if non_empty(&["status", "--porcelain", "--ignored"]) {
+        return Some("ignored files");
+    }
+    if non_empty(&["log", "--branches", "--not", "--remotes", "--oneline"]) {
+        return Some("unpushed commits");
+    }
+    if non_empty(&["stash", "list"]) {
+        return Some("stashed changes");
+    }
+    None
+}
+
+/// `--prune`: remove orphaned repos after confirmation (`--force` skips it),
+/// keeping any that hold uncommitted, ignored, unpushed or still-tracked work.
+fn prune_orphans(orphans: &[Orphan], force: bool, dry_run: bool) -> anyhow::Result<()> {
+    if orphans.is_empty() {
+        return Ok(());
+    }
+    let mut removable = Vec::new();
+    for orphan in orphans {
+        match orphan_keep_reason(orphan) {
+            Some(reason) => eprintln!(
+                "{} Keeping {} (not in .meta): {reason}",
+                style("⚠").yellow(),
+                style(&orphan.display).bold()
+            ),
+            None => removable.push(orphan),
+        }
+    }
+    if removable.is_empty() {
+        return Ok(());
+    }
+
+    println!("Orphaned repos not in .meta:");
+    for orphan in &removable {
+        println!("  {} {}", style("-").red(), orphan.display);
+    }
+    if dry_run {
+        println!(
+            "{} Would remove {} orphaned repo(s)",
+            style("[DRY RUN]").cyan(),
+            removable.len()
+        );
+        return Ok(());
+    }
+    if !force {
+        // No terminal to ask on counts as "no"
+        let proceed = Confirm::new()
+            .with_prompt(format!("Remove {} orphaned repo(s)?", removable.len()))
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+        if !proceed {
+            println!("Kept orphaned repos.");
+            return Ok(());
+        }
+    }
+
+    let mut removed = Vec::new();
+    for orphan in removable {
+        match std::fs::remove_dir_all(&orphan.path) {
+            Ok(()) => removed.push(orphan.display.as_str()),
+            Err(e) => eprintln!(
+                "{} Failed to remove {}: {e}",
+                style("✗").red(),
+                style(&orphan.display).bold()
+            ),
+        }
+    }
+    println!(
+        "{} Removed {} orphaned repo(s){}",
+        style("✓").green(),
+        removed.len(),
+        if removed.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", removed.join(", "))
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{clone_repo, git, init_repo};
+    use std::path::Path;
+
+    /// A clone of a fresh upstream: pushed, clean, nothing local.
+    fn orphan_clone(tmp: &Path, name: &str) -> Orphan {
+        let upstream = tmp.join(format!("{name}-upstream"));
+        let path = tmp.join(name);
+        init_repo(&upstream);
+        clone_repo(&upstream, &path);
+        Orphan {
+            display: name.to_string(),
+            path,
+            parent_of_tracked: false,
+        }
+    }
+
+    #[test]
+    fn orphans_with_local_work_are_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        let dirty = orphan_clone(tmp.path(), "dirty");
+        std::fs::write(dirty.path.join("new.txt"), "").unwrap();
+        assert_eq!(orphan_keep_reason(&dirty), Some("uncommitted changes"));
+
+        let ignored = orphan_clone(tmp.path(), "ignored");
+        std::fs::write(ignored.path.join(".git/info/exclude"), ".env\n").unwrap();
+        std::fs::write(ignored.path.join(".env"), "SECRET=1").unwrap();
+        assert_eq!(orphan_keep_reason(&ignored), Some("ignored files"));
+
+        let unpushed = orphan_clone(tmp.path(), "unpushed");
+        git(
+            &unpushed.path,
+            &["commit", "--quiet", "--allow-empty", "-m", "local"],
+        );
+        assert_eq!(orphan_keep_reason(&unpushed), Some("unpushed commits"));
+
+        let stashed = orphan_clone(tmp.path(), "stashed");
+        std::fs::write(stashed.path.join("wip.txt"), "").unwrap();
+        git(&stashed.path, &["stash", "--quiet", "--include-untracked"]);
+        assert_eq!(orphan_keep_reason(&stashed), Some("stashed changes"));
+
+        let mut parent = orphan_clone(tmp.path(), "parent");
+        parent.parent_of_tracked = true;
+        assert_eq!(
+            orphan_keep_reason(&parent),
+            Some("contains repos still listed in .meta")
+        );
+
+        prune_orphans(&[dirty, ignored, unpushed, stashed, parent], true, false).unwrap();
+        for name in ["dirty", "ignored", "unpushed", "stashed", "parent"] {
+            assert!(tmp.path().join(name).exists(), "{name} was removed");
+        }
+    }
+
+    #[test]
+    fn clean_pushed_orphans_are_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let orphan = orphan_clone(tmp.path(), "old");
+        assert_eq!(orphan_keep_reason(&orphan), None);
+
+        prune_orphans(std::slice::from_ref(&orphan), false, true).unwrap();
+        assert!(orphan.path.exists(), "dry run removed the repo");
+        prune_orphans(&[orphan], true, false).unwrap();
+        assert!(!tmp.path().join("old").exists());
+    }
+}
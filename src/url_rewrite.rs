@@ -0,0 +1,231 @@
+//! `--protocol ssh|https` - rewrite remote URLs between SSH and HTTPS form.
+//!
+//! ```text
+//! https://github.com/org/repo.git  <->  git@github.com:org/repo.git
+//! ```
+//!
+//! Ports don't carry over: an HTTPS port says nothing about where sshd
+//! listens, so `https://git.example.com:8443/org/repo.git` becomes
+//! `git@git.example.com:org/repo.git`, and `ssh://git@host:2222/org/repo.git`
+//! becomes `https://host/org/repo.git`. Anything that isn't a recognizable
+//! remote URL (local paths, `file://`, `git://`) is left alone.
+//!
+//! git applies the user's `url.<base>.insteadOf` rules on top of whatever URL
+//! we hand it, so code that needs the host git will really connect to (SSH
+//! multiplexing) resolves them with [`resolve_insteadof`] first.
+
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The transport `--protocol` asks every clone URL to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    Ssh,
+    Https,
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ssh" => Ok(Protocol::Ssh),
+            "https" => Ok(Protocol::Https),
+            other => Err(format!(
+                "Unknown protocol '{other}' (expected 'ssh' or 'https')"
+            )),
+        }
+    }
+}
+
+/// Host and repo path of a remote URL, e.g. ("github.com", "org/repo.git").
+fn split_remote(url: &str) -> Option<(&str, &str)> {
+    let (authority, path) = if let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("ssh://"))
+    {
+        rest.split_once('/')?
+    } else if url.contains("://") {
+        return None;
+    } else {
+        // SCP-like `user@host:path`; a bare `host:path` is ambiguous with
+        // local paths, so require the user part
+        let (user_host, path) = url.split_once(':')?;
+        if !user_host.contains('@') || user_host.contains('/') {
+            return None;
+        }
+        (user_host, path)
+    };
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = host_port.split_once(':').map_or(host_port, |(h, _)| h);
+    let path = path.trim_start_matches('/');
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host, path))
+}
+
+/// Host of a remote URL, e.g. "gitlab.example.com".
+pub(crate) fn remote_host(url: &str) -> Option<&str> {
+    split_remote(url).map(|(host, _)| host)
+}
+
+/// Repo path of a remote URL without `.git`, e.g. "group/sub/repo".
+pub(crate) fn remote_path(url: &str) -> Option<&str> {
+    split_remote(url).map(|(_, path)| path.trim_end_matches('/').trim_end_matches(".git"))
+}
+
+/// Rewrite `url` to use `protocol`, or `None` if its shape isn't recognized.
+pub(crate) fn rewrite_url(url: &str, protocol: Protocol) -> Option<String> {
+    let (host, path) = split_remote(url)?;
+    let already = match protocol {
+        Protocol::Https => url.starts_with("https://"),
+        Protocol::Ssh => url.starts_with("ssh://") || !url.contains("://"),
+    };
+    if already {
+        return Some(url.to_string());
+    }
+    Some(match protocol {
+        Protocol::Ssh => format!("git@{host}:{path}"),
+        Protocol::Https => format!("https://{host}/{path}"),
+    })
+}
+
+/// [`rewrite_url`] for the clone path: unrecognized URLs are kept as-is, with a warning.
+pub(crate) fn apply_protocol(url: &str, protocol: Option<Protocol>) -> String {
+    let Some(protocol) = protocol else {
+        return url.to_string();
+    };
+    rewrite_url(url, protocol).unwrap_or_else(|| {
+        log::warn!("Can't rewrite '{url}' for --protocol, cloning it unchanged");
+        url.to_string()
+    })
+}
+
+/// `url.<base>.insteadOf <prefix>` rules from git config, as (prefix, base).
+fn insteadof_rules() -> &'static [(String, String)] {
+    static RULES: OnceLock<Vec<(String, String)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        let out = Command::new("git")
+            .args(["config", "--get-regexp", r"^url\..*\.insteadof$"])
+            .output();
+        match out {
+            // Exit status 1 just means no rules are configured
+            Ok(out) if out.status.success() => {
+                parse_insteadof_rules(&String::from_utf8_lossy(&out.stdout))
+            }
+            _ => Vec::new(),
+        }
+    })
+}
+
+/// Parse `git config --get-regexp` lines like
+/// `url.git@github.com:.insteadof https://github.com/`.
+fn parse_insteadof_rules(config: &str) -> Vec<(String, String)> {
+    config
+        .lines()
+        .filter_map(|line| {
+            let (key, prefix) = line.split_once(' ')?;
+            let base = key.strip_prefix("url.")?.strip_suffix(".insteadof")?;
+            Some((prefix.trim().to_string(), base.to_string()))
+        })
+        .collect()
+}
+
+/// Rewrite `url` the way git would: the longest matching prefix wins.
+fn apply_insteadof(url: &str, rules: &[(String, String)]) -> String {
+    rules
+        .iter()
+        .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(prefix, base)| format!("{base}{}", &url[prefix.len()..]))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// The URL git will actually fetch from once `insteadOf` rules are applied.
+pub(crate) fn resolve_insteadof(url: &str) -> String {
+    apply_insteadof(url, insteadof_rules())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insteadof_rewrites_https_to_ssh() {
+        let rules = parse_insteadof_rules(
+            "url.git@github.com:.insteadof https://github.com/\n\
+             url.git@github.com:work/.insteadof https://github.com/work-org/\n",
+        );
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            apply_insteadof("https://github.com/org/repo.git", &rules),
+            "git@github.com:org/repo.git"
+        );
+        // Longest prefix wins, like git
+        assert_eq!(
+            apply_insteadof("https://github.com/work-org/api.git", &rules),
+            "git@github.com:work/api.git"
+        );
+        assert_eq!(
+            apply_insteadof("https://gitlab.com/org/repo.git", &rules),
+            "https://gitlab.com/org/repo.git"
+        );
+    }
+
+    #[test]
+    fn github_urls_round_trip() {
+        let https = "https://github.com/org/repo.git";
+        let ssh = "git@github.com:org/repo.git";
+        assert_eq!(rewrite_url(https, Protocol::Ssh).as_deref(), Some(ssh));
+        assert_eq!(rewrite_url(ssh, Protocol::Https).as_deref(), Some(https));
+        assert_eq!(rewrite_url(ssh, Protocol::Ssh).as_deref(), Some(ssh));
+    }
+
+    #[test]
+    fn gitlab_subgroups_keep_their_path() {
+        assert_eq!(
+            rewrite_url("https://gitlab.com/group/sub/repo.git", Protocol::Ssh).as_deref(),
+            Some("git@gitlab.com:group/sub/repo.git")
+        );
+        assert_eq!(
+            rewrite_url("https://ci-token@gitlab.com/group/repo", Protocol::Ssh).as_deref(),
+            Some("git@gitlab.com:group/repo")
+        );
+    }
+
+    #[test]
+    fn self_hosted_ports_are_dropped() {
+        assert_eq!(
+            rewrite_url("https://git.example.com:8443/org/repo.git", Protocol::Ssh).as_deref(),
+            Some("git@git.example.com:org/repo.git")
+        );
+        assert_eq!(
+            rewrite_url(
+                "ssh://git@git.example.com:2222/org/repo.git",
+                Protocol::Https
+            )
+            .as_deref(),
+            Some("https://git.example.com/org/repo.git")
+        );
+    }
+
+    #[test]
+    fn unrecognized_urls_pass_through() {
+        for url in [
+            "../sibling",
+            "/srv/git/repo.git",
+            "file:///srv/repo",
+            "C:repo",
+        ] {
+            assert_eq!(rewrite_url(url, Protocol::Ssh), None, "{url}");
+            assert_eq!(apply_protocol(url, Some(Protocol::Https)), url);
+        }
+        assert_eq!(
+            "ftp".parse::<Protocol>().unwrap_err(),
+            "Unknown protocol 'ftp' (expected 'ssh' or 'https')"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! User-level defaults from `~/.config/meta-git/config.toml`.
+//!
+//! ```toml
+//! parallel = true   # run pass-through git commands in parallel by default
+//! jobs = 8          # concurrency for clone/update, commit and worktree exec --parallel
+//! color = "never"   # "auto" (default), "always" or "never"
+//! ```
+//!
+//! Precedence is CLI flags > user config > built-in defaults; `--sequential`
+//! turns a configured `parallel = true` off. `.meta` files don't carry these
+//! settings today, so there is no workspace layer yet. `$XDG_CONFIG_HOME` is
+//! honored when set. `jobs = 0` is rejected when the file is loaded.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static USER_CONFIG: OnceLock<UserConfig> = OnceLock::new();
+
+/// Built-in clone concurrency when the user config sets no `jobs`.
+const DEFAULT_CLONE_JOBS: usize = 4;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserConfig {
+    pub parallel: Option<bool>,
+    pub jobs: Option<usize>,
+    pub color: Option<ColorMode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Load the user config once and apply process-wide settings (color).
+///
+/// A missing file yields defaults; an unreadable or invalid one is reported
+/// on stderr and ignored so a typo never blocks git operations.
+pub fn init_user_config() -> &'static UserConfig {
+    let config = USER_CONFIG.get_or_init(|| {
+        let Some(path) = user_config_path() else {
+            return UserConfig::default();
+        };
+        match load_user_config(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("warning: ignoring {}: {e}", path.display());
+                UserConfig::default()
+            }
+        }
+    });
+
+    match config.color {
+        Some(ColorMode::Always) => {
+            console::set_colors_enabled(true);
+            colored::control::set_override(true);
+        }
+        Some(ColorMode::Never) => {
+            console::set_colors_enabled(false);
+            colored::control::set_override(false);
+        }
+        Some(ColorMode::Auto) | None => {}
+    }
+
+    config
+}
+
+/// The loaded user config (defaults if `init_user_config` was never called).
+pub(crate) fn user_config() -> &'static UserConfig {
+    USER_CONFIG.get_or_init(UserConfig::default)
+}
+
+/// The CLI's parallel choice: `Some(false)` for `--sequential`, `Some(true)`
+/// for `--parallel`, `None` when neither was given.
+pub(crate) fn cli_parallel(parallel_flag: bool, args: &[String]) -> Option<bool> {
+    if args.iter().any(|a| a == "--sequential") {
+        Some(false)
+    } else if parallel_flag {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Effective parallel flag: the CLI choice wins when given, else the user default.
+pub(crate) fn parallel_or(cli: Option<bool>) -> bool {
+    cli.unwrap_or_else(|| user_config().parallel.unwrap_or(false))
+}
+
+/// Concurrent clones for clone/update without `--parallel N`: user config
+/// `jobs`, else 4 to stay clear of SSH multiplexing limits.
+pub(crate) fn clone_jobs() -> usize {
+    user_config().jobs.unwrap_or(DEFAULT_CLONE_JOBS)
+}
+
+/// Default concurrency cap for `--parallel` without an explicit count:
+/// user config `jobs`, else the CPU count.
+pub(crate) fn default_jobs() -> usize {
+    if let Some(jobs) = user_config().jobs {
+        return jobs;
+    }
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("meta-git").join("config.toml"))
+}
+
+/// Read a config file; a missing file is not an error.
+pub(crate) fn load_user_config(path: &Path) -> anyhow::Result<UserConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_user_config(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(UserConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(crate) fn parse_user_config(contents: &str) -> anyhow::Result<UserConfig> {
+    let config: UserConfig = toml::from_str(contents)?;
+    if config.jobs == Some(0) {
+        anyhow::bail!("jobs must be at least 1");
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_settings() {
+        let config = parse_user_config("parallel = true\njobs = 8\ncolor = \"never\"\n").unwrap();
+        assert_eq!(
+            config,
+            UserConfig {
+                parallel: Some(true),
+                jobs: Some(8),
+                color: Some(ColorMode::Never),
+            }
+        );
+    }
+
+    #[test]
+    fn empty_file_is_all_defaults() {
+        assert_eq!(parse_user_config("").unwrap(), UserConfig::default());
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        assert!(parse_user_config("paralel = true").is_err());
+        assert!(parse_user_config("color = \"sometimes\"").is_err());
+        assert!(parse_user_config("jobs = 0").is_err());
+    }
+
+    #[test]
+    fn cli_parallel_choice_beats_the_user_default() {
+        let sequential = vec!["--sequential".to_string()];
+        assert_eq!(cli_parallel(false, &[]), None);
+        assert_eq!(cli_parallel(true, &[]), Some(true));
+        assert_eq!(cli_parallel(false, &sequential), Some(false));
+        assert_eq!(cli_parallel(true, &sequential), Some(false));
+        assert!(!parallel_or(Some(false)));
+        assert!(parallel_or(Some(true)));
+    }
+
+    #[test]
+    fn missing_file_yields_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = load_user_config(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config, UserConfig::default());
+    }
+}